@@ -0,0 +1,33 @@
+use rust_practice_json_parser::tape;
+use std::hint::black_box;
+use std::time::Instant;
+
+// 对比两阶段（tape）解析与递归下降解析的性能：cargo bench
+fn main() {
+    let users: Vec<String> = (0..20_000)
+        .map(|i| {
+            format!(
+                r#"{{"id": {}, "name": "user{}", "score": {}.5, "active": true, "tags": ["a", "b"], "manager": null}}"#,
+                i, i, i
+            )
+        })
+        .collect();
+    let json = format!("[{}]", users.join(", "));
+    let rounds = 10;
+
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(rust_practice_json_parser::parse_str(black_box(&json)).unwrap());
+    }
+    let recursive = start.elapsed() / rounds;
+
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(tape::parse_str_fast(black_box(&json)).unwrap());
+    }
+    let fast = start.elapsed() / rounds;
+
+    println!("Input size: {} bytes", json.len());
+    println!("  recursive descent: {:?}", recursive);
+    println!("  two-stage tape:    {:?}", fast);
+}
@@ -0,0 +1,287 @@
+// #[derive(ToJson, FromJson)] for rust-practice-json-parser; use it through the main
+// crate's `derive` feature rather than directly.
+//
+// Structs with named fields become objects, newtype structs their inner value, other
+// tuple structs arrays, unit structs null, and enums whose variants are all units the
+// variant name as a string. Field attributes, inside #[json(...)]:
+//   rename = "name"    the key used in JSON
+//   default            a missing member decodes to Default::default()
+//   default = "path"   ... or to what the function at `path` returns
+//   skip               never written, and decoded as Default::default()
+//   flatten            the field's own members are merged into this object
+// `rename` also works on enum variants.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DataEnum, DeriveInput, Fields, GenericParam, Generics, Ident, LitStr, Path,
+    parse_macro_input, parse_quote,
+};
+
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, Direction::To).into()
+}
+
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, Direction::From).into()
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    To,
+    From,
+}
+
+#[derive(Default)]
+struct Options {
+    rename: Option<String>,
+    default: Option<Fallback>,
+    skip: bool,
+    flatten: bool,
+}
+
+enum Fallback {
+    Trait,
+    Function(Path),
+}
+
+fn expand(input: &DeriveInput, direction: Direction) -> Tokens {
+    let body = match (&input.data, direction) {
+        (Data::Struct(data), Direction::To) => struct_to_json(&data.fields),
+        (Data::Struct(data), Direction::From) => struct_from_json(&data.fields),
+        (Data::Enum(data), Direction::To) => enum_to_json(data),
+        (Data::Enum(data), Direction::From) => enum_from_json(data),
+        (Data::Union(_), _) => Err(syn::Error::new_spanned(
+            &input.ident,
+            "unions can't be derived",
+        )),
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(error) => return error.to_compile_error(),
+    };
+    let name = &input.ident;
+    let json = quote!(::rust_practice_json_parser);
+    let generics = bounded(&input.generics, direction);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    match direction {
+        Direction::To => quote! {
+            impl #impl_generics #json::ToJson for #name #type_generics #where_clause {
+                fn to_json(&self) -> #json::Value {
+                    #body
+                }
+            }
+        },
+        Direction::From => quote! {
+            impl #impl_generics #json::FromJson for #name #type_generics #where_clause {
+                fn from_json(value: &#json::Value) -> ::std::result::Result<Self, #json::DecodeError> {
+                    #body
+                }
+            }
+        },
+    }
+}
+
+// every type parameter has to implement the trait being derived
+fn bounded(generics: &Generics, direction: Direction) -> Generics {
+    let mut generics = generics.clone();
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            match direction {
+                Direction::To => param
+                    .bounds
+                    .push(parse_quote!(::rust_practice_json_parser::ToJson)),
+                Direction::From => param
+                    .bounds
+                    .push(parse_quote!(::rust_practice_json_parser::FromJson)),
+            }
+        }
+    }
+    generics
+}
+
+fn options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                options.default = Some(match meta.input.peek(syn::Token![=]) {
+                    true => Fallback::Function(meta.value()?.parse::<LitStr>()?.parse()?),
+                    false => Fallback::Trait,
+                });
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                options.flatten = true;
+            } else {
+                return Err(meta.error("unknown json attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn key(ident: &Ident, options: &Options) -> String {
+    options
+        .rename
+        .clone()
+        .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string())
+}
+
+fn struct_to_json(fields: &Fields) -> syn::Result<Tokens> {
+    let json = quote!(::rust_practice_json_parser);
+    match fields {
+        Fields::Named(fields) => {
+            let mut members = Vec::new();
+            for field in &fields.named {
+                let options = options(&field.attrs)?;
+                let ident = field.ident.as_ref().expect("named field");
+                if options.skip {
+                    continue;
+                }
+                if options.flatten {
+                    members.push(quote! {
+                        if let #json::Value::Object(members) = #json::ToJson::to_json(&self.#ident) {
+                            for (key, value) in members {
+                                object[key.as_str()] = value;
+                            }
+                        }
+                    });
+                } else {
+                    let key = key(ident, &options);
+                    members.push(quote! {
+                        object[#key] = #json::ToJson::to_json(&self.#ident);
+                    });
+                }
+            }
+            Ok(quote! {
+                let mut object = #json::Value::object();
+                #(#members)*
+                object
+            })
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            Ok(quote!(#json::ToJson::to_json(&self.0)))
+        }
+        Fields::Unnamed(fields) => {
+            let indices = (0..fields.unnamed.len()).map(syn::Index::from);
+            Ok(quote! {
+                #json::Value::Array(vec![#(#json::ToJson::to_json(&self.#indices)),*])
+            })
+        }
+        Fields::Unit => Ok(quote!(#json::Value::Null)),
+    }
+}
+
+fn struct_from_json(fields: &Fields) -> syn::Result<Tokens> {
+    let json = quote!(::rust_practice_json_parser);
+    match fields {
+        Fields::Named(fields) => {
+            let mut initializers = Vec::new();
+            for field in &fields.named {
+                let options = options(&field.attrs)?;
+                let ident = field.ident.as_ref().expect("named field");
+                let key = key(ident, &options);
+                let fallback = match &options.default {
+                    Some(Fallback::Function(path)) => quote!(#path()),
+                    _ => quote!(::std::default::Default::default()),
+                };
+                let decode = if options.skip {
+                    fallback
+                } else if options.flatten {
+                    quote!(#json::FromJson::from_json(value)?)
+                } else if options.default.is_some() {
+                    quote! {
+                        match value.as_object().and_then(|object| object.get(#key)) {
+                            Some(member) => #json::FromJson::from_json(member)
+                                .map_err(|e| e.within(#key))?,
+                            None => #fallback,
+                        }
+                    }
+                } else {
+                    quote!(value.decode_field(#key)?)
+                };
+                initializers.push(quote!(#ident: #decode));
+            }
+            Ok(quote! {
+                if !value.is_object() {
+                    return Err(#json::DecodeError::mismatch("object", value));
+                }
+                Ok(Self { #(#initializers),* })
+            })
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            Ok(quote!(#json::FromJson::from_json(value).map(Self)))
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() > 8 => Err(syn::Error::new_spanned(
+            fields,
+            "tuple structs with more than 8 fields can't be derived",
+        )),
+        Fields::Unnamed(fields) => {
+            let names: Vec<_> = (0..fields.unnamed.len())
+                .map(|i| format_ident!("field{}", i))
+                .collect();
+            let types = fields.unnamed.iter().map(|field| &field.ty);
+            Ok(quote! {
+                let (#(#names,)*): (#(#types,)*) = #json::FromJson::from_json(value)?;
+                Ok(Self(#(#names),*))
+            })
+        }
+        Fields::Unit => Ok(quote! {
+            match value {
+                #json::Value::Null => Ok(Self),
+                _ => Err(#json::DecodeError::mismatch("null", value)),
+            }
+        }),
+    }
+}
+
+// (variant, its name in JSON) for enums whose variants all are units
+fn unit_variants(data: &DataEnum) -> syn::Result<Vec<(&Ident, String)>> {
+    data.variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unit => Ok((
+                &variant.ident,
+                key(&variant.ident, &options(&variant.attrs)?),
+            )),
+            _ => Err(syn::Error::new_spanned(
+                variant,
+                "only enums whose variants have no fields can be derived",
+            )),
+        })
+        .collect()
+}
+
+fn enum_to_json(data: &DataEnum) -> syn::Result<Tokens> {
+    let json = quote!(::rust_practice_json_parser);
+    let (idents, names): (Vec<_>, Vec<_>) = unit_variants(data)?.into_iter().unzip();
+    Ok(quote! {
+        match self {
+            #(Self::#idents => #json::Value::String(#names.to_string()),)*
+        }
+    })
+}
+
+fn enum_from_json(data: &DataEnum) -> syn::Result<Tokens> {
+    let json = quote!(::rust_practice_json_parser);
+    let (idents, names): (Vec<_>, Vec<_>) = unit_variants(data)?.into_iter().unzip();
+    let expected = names.join(", ");
+    Ok(quote! {
+        match value.as_str() {
+            #(Some(#names) => Ok(Self::#idents),)*
+            Some(other) => Err(#json::DecodeError::new(format!(
+                "unknown variant '{}', expected one of {}",
+                other, #expected
+            ))),
+            None => Err(#json::DecodeError::mismatch("string", value)),
+        }
+    })
+}
@@ -0,0 +1,29 @@
+use rust_practice_json_parser::arena::{self, Arena};
+
+// 演示把文档解析到 bump arena 中：节点和字符串都从 arena 分配，重置 arena 即可一次性释放（需要开启 arena feature）
+fn main() {
+    let documents = [
+        r#"{"id": 1, "name": "Alice", "tags": ["admin", "dev"]}"#,
+        r#"{"id": 2, "name": "Bob", "tags": []}"#,
+        r#"{"id": 3, "name": "Carol",}"#,
+    ];
+
+    let mut arena = Arena::with_capacity(4 * 1024);
+    for input in documents {
+        match arena::parse_in(&arena, input) {
+            Ok(document) => {
+                let name = document.get("name").and_then(|name| name.as_str());
+                let tags = document.get("tags").and_then(|tags| tags.as_array());
+                println!(
+                    "  {:?} with {} tags",
+                    name,
+                    tags.map_or(0, |tags| tags.len())
+                );
+            }
+            Err(e) => println!("  Error: {}", e),
+        }
+        println!("  arena holds {} bytes", arena.allocated_bytes());
+        // 处理完一批文档后重置，下一批复用同一块内存
+        arena.reset();
+    }
+}
@@ -0,0 +1,42 @@
+use rust_practice_json_parser::array_index::ArrayIndex;
+use std::io::Cursor;
+
+// 演示为超大顶层数组建立元素偏移索引，随机访问任意元素
+fn main() {
+    let elements: Vec<String> = (0..1000)
+        .map(|i| format!(r#"{{"id": {}, "name": "item{}"}}"#, i, i))
+        .collect();
+    let json = format!("[{}]", elements.join(",\n "));
+
+    // 内存中的数据（或 mmap）直接按切片建立索引
+    let index = match ArrayIndex::build(json.as_bytes()) {
+        Ok(index) => index,
+        Err(e) => {
+            println!("Index error: {}", e);
+            return;
+        }
+    };
+    println!("Indexed {} elements", index.len());
+    println!("  element 777 at {:?}", index.span(777));
+    println!(
+        "  element 777 = {:?}",
+        index.get_element(json.as_bytes(), 777)
+    );
+    println!(
+        "  elements 3..5 = {:?}",
+        index.get_range(json.as_bytes(), 3..5)
+    );
+
+    // 文件可以先流式建立索引，再通过 Seek 读取单个元素
+    let mut file = Cursor::new(json.as_bytes());
+    match ArrayIndex::from_reader(&mut file) {
+        Ok(index) if !index.is_empty() => {
+            println!(
+                "  last element = {:?}",
+                index.read_element(&mut file, index.len() - 1)
+            )
+        }
+        Ok(_) => println!("  empty array"),
+        Err(e) => println!("Index error: {}", e),
+    }
+}
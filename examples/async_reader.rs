@@ -0,0 +1,29 @@
+use rust_practice_json_parser::async_io::{self, AsyncEventReader};
+
+// 演示异步读取（需要开启 async feature）
+fn main() {
+    let json = r#"{"name": "Alice", "tags": ["a", "b"]}"#;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        // &[u8] 实现了 AsyncBufRead，实际使用时可以换成 hyper 的请求体
+        match async_io::parse_async_reader(json.as_bytes()).await {
+            Ok(ast) => println!("AST: {:?}", ast),
+            Err(e) => println!("Async error: {}", e),
+        }
+
+        let mut events = AsyncEventReader::new(json.as_bytes());
+        loop {
+            match events.next_event().await {
+                Ok(Some(event)) => println!("  {:?}", event),
+                Ok(None) => break,
+                Err(e) => {
+                    println!("Async error: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,32 @@
+use futures::{SinkExt, StreamExt};
+use rust_practice_json_parser::async_io::{self, JsonSeqSink, NdjsonStream};
+use rust_practice_json_parser::parse_str;
+
+// 演示把 NDJSON 读成 Stream、用 Sink 写成 json-seq（需要开启 async feature）
+fn main() {
+    let input = "{\"level\": \"info\"}\n\n{\"level\": \"warn\",}\n[1, 2]\n";
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let mut output = Vec::new();
+        let mut sink = JsonSeqSink::new(&mut output);
+        let mut lines = NdjsonStream::new(input.as_bytes());
+        while let Some(result) = lines.next().await {
+            match result {
+                Ok(value) => sink.send(value).await.unwrap(),
+                Err(e) => println!("  Skipped: {}", e),
+            }
+        }
+        sink.close().await.unwrap();
+        println!("json-seq: {:?}", String::from_utf8(output).unwrap());
+
+        let value = parse_str(r#"{"name": "Alice"}"#).unwrap();
+        let mut buffer = Vec::new();
+        match async_io::to_async_writer(&mut buffer, &value).await {
+            Ok(()) => println!("Written: {}", String::from_utf8(buffer).unwrap()),
+            Err(e) => println!("Async error: {}", e),
+        }
+    });
+}
@@ -0,0 +1,44 @@
+use rust_practice_json_parser::avro::{AvroSchema, from_avro, to_avro};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 Avro 编解码：使用给定的 schema，或从样本推断 schema
+fn main() -> Result<(), Error> {
+    // 消息总线上约定好的 schema
+    let schema: AvroSchema = r#"{
+        "type": "record",
+        "name": "Event",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["click", "view"]}},
+            {"name": "tags", "type": {"type": "array", "items": "string"}},
+            {"name": "score", "type": ["null", "double"], "default": null}
+        ]
+    }"#
+    .parse()?;
+
+    let event = parse_str(r#"{"id": 42, "kind": "click", "tags": ["a", "b"]}"#)?;
+    let bytes = to_avro(&event, &schema)?;
+    println!("{} 字节: {:02x?}", bytes.len(), bytes);
+    // 缺少的字段写入默认值，解码时按字段顺序还原
+    println!("{}", from_avro(&bytes, &schema)?);
+
+    // 不符合 schema 的值会指出位置
+    let wrong = parse_str(r#"{"id": 1, "kind": "scroll", "tags": []}"#)?;
+    println!("{:?}", to_avro(&wrong, &schema));
+
+    // 没有 schema 时，从 JSON 样本推断一个
+    let samples = parse_str(
+        r#"[
+            {"user": "alice", "age": 30, "address": {"city": "Paris"}},
+            {"user": "bob", "age": 41.5, "nickname": "b"}
+        ]"#,
+    )?;
+    let samples = samples.as_array().unwrap();
+    let inferred = AvroSchema::infer("User", samples);
+    println!("{}", inferred);
+    for sample in samples {
+        let bytes = to_avro(sample, &inferred)?;
+        println!("{}", from_avro(&bytes, &inferred)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 arbitrary_precision：超出 i64/u64/f64 精度的数字原样保留
+// 运行：cargo run --example big_numbers --features arbitrary_precision
+fn main() -> Result<(), Error> {
+    let text = r#"{"id": 123456789012345678901234567890, "amount": 0.1000000000000000055511151231257827, "price": 19.99}"#;
+    let value = parse_str(text)?;
+
+    for key in ["id", "amount", "price"] {
+        let number = value[key].as_number().unwrap();
+        println!(
+            "{}: {} (arbitrary: {}, as_f64: {})",
+            key,
+            number,
+            number.is_arbitrary(),
+            number.as_f64()
+        );
+    }
+
+    // 精确的整数和十进制值
+    let id = value["id"].as_number().and_then(|n| n.as_bigint());
+    println!("id as bigint: {:?}", id.map(|n| n.to_string()));
+    if let Some(amount) = value["amount"].as_number().and_then(|n| n.as_decimal()) {
+        println!("amount = {} x 10^{}", amount.digits(), amount.exponent());
+    }
+
+    // 序列化时原样输出，不会丢失精度
+    println!("{}", value);
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use rust_practice_json_parser::cbor::{from_cbor, to_cbor};
+use rust_practice_json_parser::{Error, parse_str, serializer};
+
+// 演示 CBOR 编解码：把解析好的文档以二进制形式存进缓存，比 JSON 文本更省空间
+fn main() -> Result<(), Error> {
+    let text = r#"{"id": 42, "name": "alice", "scores": [9.5, 100000, 0.1], "active": true, "manager": null}"#;
+    let value = parse_str(text)?;
+
+    let bytes = to_cbor(&value);
+    println!(
+        "JSON {} 字节，CBOR {} 字节",
+        serializer::to_string(&value).len(),
+        bytes.len()
+    );
+    println!("{:02x?}", &bytes[..8]);
+
+    // 解码后与原文档相同
+    let decoded = from_cbor(&bytes)?;
+    println!("{}", decoded);
+    println!("{}", decoded == value);
+
+    // 字节串等 JSON 没有的类型会报错
+    println!("{:?}", from_cbor(&[0x41, 0x00]));
+    // 截断的输入也会报错
+    println!("{:?}", from_cbor(&bytes[..bytes.len() - 1]));
+    Ok(())
+}
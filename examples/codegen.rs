@@ -0,0 +1,28 @@
+use rust_practice_json_parser::codegen::{RustOptions, to_rust, to_typescript};
+use rust_practice_json_parser::{Error, parse_str, schema};
+
+// 演示从 JSON 样本生成 Rust 结构体定义和 TypeScript 类型声明
+fn main() -> Result<(), Error> {
+    let samples = parse_str(
+        r#"[
+            {"id": 1, "firstName": "Alice", "type": "admin", "address": {"city": "Paris", "zip": "75001"},
+             "tags": ["a"], "orders": [{"sku": "x1", "price": 9.5}]},
+            {"id": 2, "firstName": "Bob", "type": "user", "nickname": null, "tags": [],
+             "orders": [{"sku": "y2", "price": 3}], "extra": 1}
+        ]"#,
+    )?;
+    // 先推断 schema，再生成代码；只出现在部分样本中或出现过 null 的字段成为 Option
+    let schema = schema::infer(samples.as_array().unwrap());
+    println!("{}", to_rust(&schema, "User", &RustOptions::new()));
+
+    // 也可以生成 serde 的 derive
+    let options = RustOptions::new()
+        .with_json_derives(false)
+        .with_serde_derives(true);
+    let schema = schema::infer(&[samples]);
+    println!("{}", to_rust(&schema, "Users", &options));
+
+    // 前端使用的 .d.ts 声明由同一个 schema 生成，两边不会不同步
+    println!("{}", to_typescript(&schema, "Users"));
+    Ok(())
+}
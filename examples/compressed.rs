@@ -0,0 +1,31 @@
+use rust_practice_json_parser::{ndjson, streaming};
+use std::io::Write;
+
+// 演示直接读取 gzip / zstd 压缩的输入（需要开启 gzip 和 zstd feature）
+fn main() {
+    let json = r#"{"users": [{"name": "Alice", "age": 25}, {"name": "Bob", "age": 30}]}"#;
+
+    // 实际使用时可以传入 BufReader::new(File::open("big.json.gz")?)
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(json.as_bytes()).unwrap();
+    let gzipped = encoder.finish().unwrap();
+    match streaming::parse_reader(gzipped.as_slice()) {
+        Ok(ast) => println!("gzip: {:?}", ast),
+        Err(e) => println!("Reader error: {}", e),
+    }
+
+    let lines = "{\"level\": \"info\"}\n{\"level\": \"warn\"}\n";
+    let compressed = zstd::encode_all(lines.as_bytes(), 0).unwrap();
+    for result in ndjson::Reader::new(compressed.as_slice()) {
+        match result {
+            Ok(ast) => println!("zstd: {:?}", ast),
+            Err(e) => println!("  Error: {}", e),
+        }
+    }
+
+    // 未压缩的输入照常解析
+    println!(
+        "plain: {:?}",
+        streaming::parse_reader(json.as_bytes()).is_ok()
+    );
+}
@@ -0,0 +1,20 @@
+use rust_practice_json_parser::serializer::Style;
+use rust_practice_json_parser::{Value, parse_str};
+
+// 演示读写磁盘上的配置文件；写入先落到临时文件再重命名，中途崩溃也不会留下写了一半的文件
+fn main() {
+    let path = std::env::temp_dir().join("json_parser_config_file.json");
+    let config = parse_str(r#"{"server": {"port": 8080}, "debug": false}"#).unwrap();
+
+    if let Err(e) = config.to_file(&path, &Style::pretty()) {
+        println!("Write error: {}", e);
+        return;
+    }
+    println!("{}", std::fs::read_to_string(&path).unwrap());
+
+    match Value::from_file(&path) {
+        Ok(read_back) => println!("Round trip equal: {}", read_back == config),
+        Err(e) => println!("Read error: {}", e),
+    }
+    let _ = std::fs::remove_file(&path);
+}
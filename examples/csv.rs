@@ -0,0 +1,36 @@
+use rust_practice_json_parser::csv::{CsvOptions, from_csv, to_csv};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 CSV 导出与导入：对象数组的每个元素是一行，嵌套字段展开成 a.b 这样的列名
+fn main() -> Result<(), Error> {
+    let users = parse_str(
+        r#"[
+        {"id": 1, "name": "alice", "address": {"city": "Tokyo", "zip": "100"}, "tags": ["admin"]},
+        {"id": 2, "name": "bob, jr.", "note": "says 'hi', twice", "address": {"city": "Osaka"}},
+        {"id": 3, "name": "carol", "active": false}
+    ]"#,
+    )?;
+
+    // 表头是所有行的字段并集，缺失或为 null 的字段留空
+    let mut out = Vec::new();
+    to_csv(&mut out, &users, &CsvOptions::new())?;
+    let csv = String::from_utf8(out).unwrap();
+    print!("{}", csv);
+
+    // 读回时按列名还原嵌套结构，数字和布尔值也会还原
+    let back = from_csv(csv.as_bytes(), &CsvOptions::new())?;
+    println!("{:#}", back);
+
+    // 分号分隔、所有单元格都当作字符串
+    let options = CsvOptions::new()
+        .with_delimiter(';')
+        .with_infer_types(false);
+    println!("{}", from_csv("code;count\n007;3\n".as_bytes(), &options)?);
+
+    // 不是对象数组时报错
+    println!(
+        "{:?}",
+        to_csv(Vec::new(), &parse_str("[1, 2]")?, &CsvOptions::new())
+    );
+    Ok(())
+}
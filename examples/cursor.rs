@@ -0,0 +1,31 @@
+use rust_practice_json_parser::cursor::Cursor;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 Cursor：在树中上下左右移动，并随时知道当前路径和父节点
+fn main() -> Result<(), Error> {
+    let document = parse_str(
+        r#"{"users": [{"name": "Alice", "age": 31}, {"name": "Bob", "age": 17}], "total": 2}"#,
+    )?;
+
+    let mut cursor = Cursor::new(&document);
+    cursor.down("users");
+    cursor.down(0);
+    cursor.first_child();
+    println!("at {} = {}", cursor.path(), cursor.value());
+
+    cursor.next_sibling();
+    println!("next sibling: {} = {}", cursor.path(), cursor.value());
+    println!("no more siblings: {:?}", cursor.next_sibling());
+
+    cursor.up();
+    cursor.next_sibling();
+    println!("moved to {} (depth {})", cursor.path(), cursor.depth());
+    println!(
+        "parent has {:?} elements",
+        cursor.parent().and_then(|p| p.as_array()).map(Vec::len)
+    );
+
+    while cursor.up().is_some() {}
+    println!("back at root: path {:?}", cursor.path().to_string());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use rust_practice_json_parser::{Error, FromJson, ToJson, parse_str};
+
+// 演示 #[derive(ToJson, FromJson)]：不用为几十个字段手写转换
+#[derive(Debug, ToJson, FromJson)]
+struct Service {
+    name: String,
+    #[json(rename = "listenPort")]
+    port: u16,
+    #[json(default)]
+    replicas: u32,
+    #[json(default = "default_region")]
+    region: String,
+    // 只在内存中使用，不写出也不读入
+    #[json(skip)]
+    connections: usize,
+    // 子结构的成员直接合并到当前对象中
+    #[json(flatten)]
+    limits: Limits,
+    level: Level,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, ToJson, FromJson)]
+struct Limits {
+    cpu: f64,
+    memory: u64,
+}
+
+#[derive(Debug, ToJson, FromJson)]
+enum Level {
+    #[json(rename = "debug")]
+    Debug,
+    #[json(rename = "info")]
+    Info,
+}
+
+// newtype 结构写成内部的值
+#[derive(Debug, ToJson, FromJson)]
+struct Meters(f64);
+
+fn default_region() -> String {
+    "us-east".to_string()
+}
+
+fn main() -> Result<(), Error> {
+    let value = parse_str(
+        r#"{"name": "api", "listenPort": 8080, "cpu": 0.5, "memory": 512,
+            "level": "info", "tags": ["web"]}"#,
+    )?;
+    let service = Service::from_json(&value)?;
+    println!("{:?}", service);
+    println!("connections: {}", service.connections);
+    println!("{:#}", service.to_json());
+
+    println!("{}", Meters(1.5).to_json());
+
+    // 解码错误仍然带有路径
+    let broken = parse_str(
+        r#"{"name": "api", "listenPort": 8080, "cpu": 1, "memory": 1, "level": "trace", "tags": []}"#,
+    )?;
+    println!("{}", Service::from_json(&broken).unwrap_err());
+    Ok(())
+}
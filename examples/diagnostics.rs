@@ -0,0 +1,30 @@
+use rust_practice_json_parser::diagnostic::{self, check, to_json, to_text};
+use rust_practice_json_parser::schema::Schema;
+use rust_practice_json_parser::{Error, parse_str, serializer};
+
+// 演示结构化诊断：语法错误和 schema 违规都带有代码、位置和 JSON Pointer
+fn main() -> Result<(), Error> {
+    // 语法错误指向出错的记号
+    let broken = "{\n  \"name\": \"Alice\",\n  \"tags\": [1, 2,]\n}";
+    if let Err(error) = diagnostic::parse(broken) {
+        print!("{}", to_text(&[error], broken, "broken.json"));
+    }
+    println!();
+
+    // schema 违规指向对应的值
+    let schema = Schema::compile(&parse_str(
+        r#"{"type": "object", "required": ["id"],
+            "properties": {"id": {"type": "integer", "minimum": 1}, "name": {"type": "string"}}}"#,
+    )?)?;
+    let source = "{\n  \"id\": 0,\n  \"name\": 42\n}";
+    let diagnostics = check(source, &schema);
+    print!("{}", to_text(&diagnostics, source, "user.json"));
+    println!();
+
+    // 给工具用的 JSON 数组
+    println!(
+        "{}",
+        serializer::to_string_pretty(&to_json(&diagnostics, source))
+    );
+    Ok(())
+}
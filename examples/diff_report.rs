@@ -0,0 +1,34 @@
+use rust_practice_json_parser::diff::{self, DifferenceKind};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示结构化对比：契约测试里比较期望的响应和实际的响应，而不是比较字符串
+fn main() -> Result<(), Error> {
+    let expected = parse_str(
+        r#"{"id": 42, "name": "alice", "roles": ["admin", "dev"],
+            "limits": {"cpu": 2, "memory": 512}, "debug": false}"#,
+    )?;
+    // 键的顺序不同不算差异
+    let actual = parse_str(
+        r#"{"name": "alice", "id": "42", "roles": ["admin", "dev", "ops"],
+            "limits": {"memory": 1024, "cpu": 2}, "region": "eu"}"#,
+    )?;
+
+    let differences = diff::diff(&expected, &actual);
+    print!("{}", diff::report(&differences));
+
+    // 每一项都带有路径、类型和两边的值，可以按需过滤
+    let breaking: Vec<_> = differences
+        .iter()
+        .filter(|d| {
+            matches!(
+                d.kind,
+                DifferenceKind::Removed | DifferenceKind::TypeChanged
+            )
+        })
+        .map(|d| d.path.to_string())
+        .collect();
+    println!("breaking: {:?}", breaking);
+
+    print!("{}", diff::report(&diff::diff(&expected, &expected)));
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use rust_practice_json_parser::render::to_dot;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示把文档结构导出为 Graphviz DOT 图：
+// cargo run -q --example dot | dot -Tsvg > tree.svg
+fn main() -> Result<(), Error> {
+    let value = parse_str(
+        r#"{"name": "John", "age": 30, "tags": ["a", "b"], "address": {"city": "Paris"}, "spouse": null}"#,
+    )?;
+    // 对象和数组是节点，键和下标是边上的标签
+    print!("{}", to_dot(&value));
+    Ok(())
+}
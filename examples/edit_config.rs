@@ -0,0 +1,65 @@
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示直接修改解析后的配置，不需要拆开再重建整个节点
+fn main() -> Result<(), Error> {
+    let mut config = parse_str(
+        r#"{"server": {"host": "localhost", "port": 8080, "debug": true}, "plugins": ["auth", "cache"]}"#,
+    )?;
+
+    let server = &mut config["server"];
+    let old_port = server.insert("port", 9090)?;
+    println!("port: {:?} -> {}", old_port, server["port"]);
+    println!("removed debug: {:?}", server.remove("debug"));
+
+    // take() 把值移出来，原位置留下 null
+    let plugins = config["plugins"].take();
+    println!(
+        "plugins taken: {}, left behind: {}",
+        plugins, config["plugins"]
+    );
+
+    let old = config["plugins"].replace(vec!["metrics"]);
+    println!("replaced {} with {}", old, config["plugins"]);
+
+    // 非对象上 insert 会返回错误
+    match config["plugins"].insert("oops", true) {
+        Ok(_) => println!("inserted into an array?"),
+        Err(e) => println!("insert into array: {}", e),
+    }
+
+    // 数组编辑：push / insert / remove / pop / retain / truncate
+    let plugins = &mut config["plugins"];
+    plugins.push("auth")?;
+    plugins.push("cache")?;
+    plugins.insert(0, "logging")?;
+    println!("plugins: {}", plugins);
+    println!("removed plugins[1]: {:?}", plugins.remove(1));
+    println!("popped: {:?}", plugins.pop());
+    plugins.retain(|plugin| plugin != "logging")?;
+    plugins.truncate(1)?;
+    println!("plugins: {}", plugins);
+    match config["server"].push(1) {
+        Ok(()) => println!("pushed onto an object?"),
+        Err(e) => println!("push onto object: {}", e),
+    }
+
+    // JSON Pointer：~1 表示 '/'，~0 表示 '~'，末尾的 "-" 表示追加到数组
+    println!("/server/host = {:?}", config.pointer("/server/host"));
+    if let Some(slot) = config.pointer_mut("/plugins/-") {
+        *slot = "metrics".into();
+    }
+    config["paths"]["a/b~c"] = true.into();
+    println!("/paths/a~1b~0c = {:?}", config.pointer("/paths/a~1b~0c"));
+
+    // 点号路径：users[0].name 风格，方括号里可以写带点号的键
+    config.set_path("server.tls.ports[1]", 443)?;
+    config.set_path(r#"paths["x.y"]"#, "dotted key")?;
+    println!("server.tls = {:?}", config.get_path("server.tls"));
+    println!("removed plugins[0]: {:?}", config.remove_path("plugins[0]"));
+    if let Err(e) = config.set_path("server.port.value", 1) {
+        println!("set_path through a number: {}", e);
+    }
+
+    println!("{:#}", config);
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use rust_practice_json_parser::Error;
+use rust_practice_json_parser::cst::Document;
+
+// 演示无损编辑：注释、空白和键的顺序都保留，只改动被编辑的那个值
+fn main() -> Result<(), Error> {
+    let text = r#"{
+  // 服务监听的端口
+  "port": 8080,
+  "hosts": [
+    "a.example.com", /* 主节点 */
+    "b.example.com",
+  ],
+  "debug": true, // 上线前记得关掉
+  "retired": "old-value"
+}
+"#;
+    let mut document = Document::parse(text)?;
+    println!("port = {:?}", document.get("/port"));
+
+    // 修改已有的值
+    document.set("/debug", false)?;
+    // 新增一个键，缩进与相邻的成员对齐
+    document.set("/timeout", 30)?;
+    // 向数组末尾追加
+    document.set("/hosts/-", "c.example.com")?;
+    // 删除一个成员，连同它的逗号
+    let removed = document.remove("/retired")?;
+    println!("removed {}", removed);
+
+    print!("{}", document);
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use rust_practice_json_parser::serializer::{self, SerializeOptions};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示输出时的转义选项
+fn main() -> Result<(), Error> {
+    let value = parse_str(r#"{"city": "Zürich", "greeting": "你好", "emoji": "🎉"}"#)?;
+
+    // 默认直接输出 UTF-8
+    println!("{}", value);
+
+    // 下游系统会弄乱 UTF-8 时，把所有非 ASCII 字符写成 \uXXXX（BMP 以外的字符写成代理对）
+    let ascii = SerializeOptions::new().with_ascii_only(true);
+    println!("{}", serializer::to_string_with(&value, &ascii));
+    println!(
+        "{}",
+        serializer::to_string_with(&value, &ascii.with_pretty(true))
+    );
+
+    // 嵌入 <script> 标签时，转义 <、>、& 以及 U+2028 / U+2029
+    let state = parse_str("{\"comment\": \"</script><script>alert(1)</script> & more\u{2028}\"}")?;
+    let html = SerializeOptions::new().with_html_safe(true);
+    println!(
+        "<script>var state = {};</script>",
+        serializer::to_string_with(&state, &html)
+    );
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use rust_practice_json_parser::http;
+
+// 演示解析 URL 返回的 JSON（需要开启 http feature）
+fn main() {
+    let url = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "https://api.github.com/repos/rust-lang/rust".to_string());
+    match http::parse_url(&url) {
+        Ok(ast) => println!("AST: {:?}", ast),
+        Err(e) => println!("Fetch error: {}", e),
+    }
+}
@@ -0,0 +1,37 @@
+use rust_practice_json_parser::filter::Filter;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示类似 jq 的过滤表达式：编译一次，对多个文档重复使用
+fn main() -> Result<(), Error> {
+    let document = parse_str(
+        r#"{"users": [
+            {"name": "Alice", "age": 31, "address": {"city": "Paris"}, "tags": ["admin"]},
+            {"name": "Bob", "age": 17, "address": {"city": "Berlin"}, "tags": []},
+            {"name": "Carol", "age": 45, "address": null, "tags": ["dev", "ops"]}
+        ]}"#,
+    )?;
+
+    let filters = [
+        ".users[0].name",
+        ".users[] | select(.age >= 18) | {name, city: .address.city}",
+        ".users | map(.tags | length)",
+        "[.users[] | select((.tags | length) > 0 and .age < 40) | .name]",
+        r#".users[1] | keys, .["name"]"#,
+        ".users[] | .name, .missing",
+    ];
+    for source in filters {
+        let filter = Filter::compile(source)?;
+        let outputs = filter.apply(&document)?;
+        let outputs: Vec<String> = outputs.iter().map(|value| value.to_string()).collect();
+        println!("{}\n  => {}", source, outputs.join("  "));
+    }
+
+    // 编译错误和运行时错误
+    for source in [".users[", "lenght", ".users.name"] {
+        let result = Filter::compile(source).and_then(|filter| filter.apply(&document));
+        if let Err(e) = result {
+            println!("{}\n  => error: {}", source, e);
+        }
+    }
+    Ok(())
+}
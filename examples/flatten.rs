@@ -0,0 +1,32 @@
+use rust_practice_json_parser::flatten::{ArrayStyle, FlattenOptions};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示把嵌套 JSON 展平成单层的 "a.b[0].c" 形式，再还原回来
+fn main() -> Result<(), Error> {
+    let value = parse_str(
+        r#"{"user": {"name": "Alice", "roles": ["admin", "dev"], "address": {"city": "Paris"}}, "tags": [], "active": true}"#,
+    )?;
+
+    let flat = value.flatten();
+    println!("{:#}", flat);
+    println!("round trip equal: {}", flat.unflatten()? == value);
+
+    // 自定义分隔符和数组写法
+    let options = FlattenOptions::new()
+        .with_separator("/")
+        .with_array_style(ArrayStyle::Separator);
+    let flat = value.flatten_with(&options);
+    println!("{}", flat);
+    println!(
+        "round trip equal: {}",
+        flat.unflatten_with(&options)? == value
+    );
+
+    // 互相冲突的路径会报错
+    let broken = parse_str(r#"{"a": 1, "a.b": 2}"#)?;
+    match broken.unflatten() {
+        Ok(value) => println!("unexpected: {}", value),
+        Err(e) => println!("unflatten error: {}", e),
+    }
+    Ok(())
+}
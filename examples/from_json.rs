@@ -0,0 +1,49 @@
+use rust_practice_json_parser::{DecodeError, Error, FromJson, Value, parse_str};
+use std::collections::HashMap;
+
+// 演示 FromJson：手写解码，出错时报告具体位置
+#[derive(Debug)]
+struct Server {
+    host: String,
+    port: u16,
+    tags: Vec<String>,
+    timeout: Option<u32>, // 缺省时为 None
+}
+
+impl FromJson for Server {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(Server {
+            host: value.decode_field("host")?,
+            port: value.decode_field("port")?,
+            tags: value.decode_field("tags")?,
+            timeout: value.decode_field("timeout")?,
+        })
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let config = parse_str(
+        r#"{"web": {"host": "a.example.com", "port": 80, "tags": ["edge"]},
+            "db": {"host": "b.example.com", "port": 5432, "tags": [], "timeout": 30}}"#,
+    )?;
+    let servers: HashMap<String, Server> = config.decode()?;
+    let db = &servers["db"];
+    println!(
+        "{}:{} tags={:?} timeout={:?}",
+        db.host, db.port, db.tags, db.timeout
+    );
+
+    // 错误信息带有路径
+    let broken = parse_str(r#"{"web": {"host": "a", "port": 80, "tags": ["x", 1]}}"#)?;
+    let result: Result<HashMap<String, Server>, _> = broken.decode();
+    println!("{}", result.unwrap_err());
+    let broken = parse_str(r#"[{"host": "a", "port": 70000, "tags": []}]"#)?;
+    println!("{}", broken.decode::<Vec<Server>>().unwrap_err());
+    let broken = parse_str(r#"{"port": 80}"#)?;
+    println!("{}", broken.decode::<Server>().unwrap_err());
+
+    // 元组对应定长数组
+    let pair: (String, f64) = parse_str(r#"["pi", 3.14]"#)?.decode()?;
+    println!("{:?}", pair);
+    Ok(())
+}
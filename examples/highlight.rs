@@ -0,0 +1,22 @@
+use rust_practice_json_parser::highlight::{LSP_LEGEND, classify, to_lsp_data};
+
+// 演示编辑器插件用的语义高亮：把源文本切分成带分类的区间
+fn main() {
+    // 允许注释、转义，也容忍还没写完的文本
+    let text = "{\n  // 用户\n  \"name\": \"J\\\"ohn\", \"age\": -30.5e2,\n  \"ok\": true, /* 待定 */ \"x\": nul";
+    for span in classify(text) {
+        println!(
+            "{:>3}..{:<3} {:?} {}",
+            span.start,
+            span.end,
+            span.class,
+            &text[span.start..span.end]
+        );
+    }
+
+    // LSP 的 semanticTokens 数据，每个记号五个数，类型按 LSP_LEGEND 编号
+    println!("legend: {:?}", LSP_LEGEND);
+    for token in to_lsp_data(text, &classify(text)).chunks(5) {
+        println!("{:?}", token);
+    }
+}
@@ -0,0 +1,18 @@
+use rust_practice_json_parser::render::{HtmlOptions, to_html, to_html_with};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示把文档渲染成可折叠的 HTML 树，可以直接嵌入调试页面
+fn main() -> Result<(), Error> {
+    let value = parse_str(
+        r#"{"service": "<api>", "ok": true, "latency": 12.5, "error": null,
+            "hosts": [{"name": "a", "port": 8080}, {"name": "b", "port": 8081}], "tags": []}"#,
+    )?;
+    println!("{}", to_html(&value));
+
+    // 只展开第一层，不附带样式表（由页面自己提供 json-* 类的样式）
+    let options = HtmlOptions::new()
+        .with_open_depth(Some(1))
+        .with_style(false);
+    println!("{}", to_html_with(&value, &options));
+    Ok(())
+}
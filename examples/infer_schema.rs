@@ -0,0 +1,31 @@
+use rust_practice_json_parser::{Error, parse_str, schema};
+
+// 演示从样例文档推断结构：合并各样例中的类型，找出可选字段、可为 null 的字段和数组元素类型
+fn main() -> Result<(), Error> {
+    let samples = [
+        r#"{"id": 1, "name": "Alice", "tags": ["admin"], "score": 9.5, "manager": null}"#,
+        r#"{"id": 2, "name": "Bob", "tags": [], "score": 7, "manager": {"id": 1}}"#,
+        r#"{"id": 3, "name": "Carol", "tags": ["dev", "ops"], "nickname": "CJ"}"#,
+    ]
+    .iter()
+    .map(|text| parse_str(text))
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let inferred = schema::infer(&samples);
+    for (key, field) in inferred.properties() {
+        println!(
+            "{:<9} types {:?}, required: {}, nullable: {}",
+            key,
+            field.types(),
+            inferred.is_required(key),
+            field.is_nullable()
+        );
+    }
+    if let Some(tags) = inferred.property("tags").and_then(|tags| tags.items()) {
+        println!("tags elements: {:?}", tags.types());
+    }
+
+    // 输出为 JSON Schema
+    println!("{:#}", inferred.to_json_schema());
+    Ok(())
+}
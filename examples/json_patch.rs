@@ -0,0 +1,43 @@
+use rust_practice_json_parser::patch::{self, Patch};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 JSON Patch（RFC 6902）：在服务之间同步文档时，只传输变化的部分
+fn main() -> Result<(), Error> {
+    let old = parse_str(
+        r#"{"name": "service-a", "replicas": 2, "tags": ["web", "prod"],
+            "limits": {"cpu": 1, "memory": 512}, "debug": true}"#,
+    )?;
+    let new = parse_str(
+        r#"{"name": "service-a", "replicas": 3, "tags": ["web", "canary", "prod"],
+            "limits": {"cpu": 1, "memory": 1024}, "owner": "team-x"}"#,
+    )?;
+
+    // 发送方：计算从 old 到 new 的补丁
+    let diff = patch::diff(&old, &new);
+    println!("{} operations:\n{:#}", diff.len(), diff.to_value());
+
+    // 接收方：从线上格式读回补丁并应用
+    let received = Patch::from_value(&parse_str(&diff.to_string())?)?;
+    let mut document = old.clone();
+    patch::apply(&mut document, &received)?;
+    println!("in sync: {}", document.deep_eq_unordered(&new));
+
+    // test 操作失败时整个补丁都不生效，文档保持原样
+    let guarded = Patch::from_value(&parse_str(
+        r#"[{"op": "replace", "path": "/replicas", "value": 5},
+            {"op": "test", "path": "/owner", "value": "team-y"}]"#,
+    )?)?;
+    match patch::apply(&mut document, &guarded) {
+        Ok(()) => println!("applied"),
+        Err(error) => println!("{} (replicas still {})", error, document["replicas"]),
+    }
+
+    // move 和 copy
+    let reshape = Patch::from_value(&parse_str(
+        r#"[{"op": "move", "from": "/owner", "path": "/meta"},
+            {"op": "copy", "from": "/tags/0", "path": "/tags/-"}]"#,
+    )?)?;
+    patch::apply(&mut document, &reshape)?;
+    println!("{}", document);
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use rust_practice_json_parser::{Value, json_seq};
+
+// 演示 RFC 7464 JSON 文本序列的读写
+fn main() {
+    let mut writer = json_seq::Writer::new(Vec::new());
+    for record in [r#"{"id": 1}"#, "[1, 2,", "42", r#""done""#] {
+        if let Err(e) = writer.write_record(record) {
+            println!("Write error: {}", e);
+            return;
+        }
+    }
+    // 也可以直接写入 Value，按紧凑格式序列化
+    let mut value = Value::object();
+    value["event"] = "closed".into();
+    if let Err(e) = writer.write_value(&value) {
+        println!("Write error: {}", e);
+        return;
+    }
+    if let Err(e) = writer.flush() {
+        println!("Write error: {}", e);
+        return;
+    }
+    let bytes = writer.into_inner();
+    println!("Encoded: {:?}", String::from_utf8_lossy(&bytes));
+
+    // 单条记录出错不影响后续记录
+    let mut reader = json_seq::Reader::new(bytes.as_slice());
+    for result in reader.by_ref() {
+        match result {
+            Ok(ast) => println!("  {:?}", ast),
+            Err(e) => println!("  Error: {}", e),
+        }
+    }
+    println!("Read {} records", reader.record_number());
+}
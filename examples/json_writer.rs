@@ -0,0 +1,39 @@
+use rust_practice_json_parser::serializer::JsonWriter;
+use rust_practice_json_parser::{Error, parse_str};
+use std::io::{self, BufWriter};
+
+// 演示流式写出：导出大量记录时边生成边写，不需要先构建完整的 Value
+fn main() -> Result<(), Error> {
+    let stdout = io::stdout();
+    let mut writer = JsonWriter::new(BufWriter::new(stdout.lock()));
+    writer.begin_object()?;
+    writer.key("source")?;
+    writer.value("exporter")?;
+    writer.key("records")?;
+    writer.begin_array()?;
+    for id in 0..5u64 {
+        writer.begin_object()?;
+        writer.key("id")?;
+        writer.value(id)?;
+        writer.key("name")?;
+        writer.value(format!("item \"{}\"", id))?;
+        writer.end()?;
+    }
+    writer.end()?;
+    // 已经构建好的子树也可以整体写入
+    writer.key("meta")?;
+    writer.value(parse_str(r#"{"version": 2, "tags": ["a", "b"]}"#)?)?;
+    writer.end()?;
+    writer.finish()?;
+    println!();
+
+    // 嵌套不正确时返回错误，而不是写出损坏的 JSON
+    let mut writer = JsonWriter::new(Vec::new());
+    writer.begin_array()?;
+    println!("{:?}", writer.key("id").map_err(|e| e.to_string()));
+    println!(
+        "{:?}",
+        writer.finish().map(|_| ()).map_err(|e| e.to_string())
+    );
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use rust_practice_json_parser::Error;
+use rust_practice_json_parser::lazy::{LazyDocument, LazyValue};
+
+// 演示按需解析：只有被访问到的子树才会真正解析
+fn main() {
+    // 只访问 users[index]，meta 和 payload 不会被解析
+    fn user_summary(root: LazyValue, index: usize) -> Result<Option<String>, Error> {
+        let Some(users) = root.get("users")? else {
+            return Ok(None);
+        };
+        let Some(user) = users.get_index(index)? else {
+            return Ok(None);
+        };
+        let name = user.get("name")?.and_then(|name| name.as_str());
+        let admin = user.get("admin")?.and_then(|admin| admin.as_bool());
+        let manager = user
+            .get("manager")?
+            .is_some_and(|manager| manager.is_null());
+        Ok(Some(format!(
+            "name: {:?}, admin: {:?}, no manager: {}",
+            name, admin, manager
+        )))
+    }
+
+    let json = r#"{"meta": {"version": 3}, "users": [{"name": "Alice", "admin": true, "manager": null}, {"name": "Bob", "admin": false}], "payload": [1, 2, 3]}"#;
+
+    let document = match LazyDocument::parse(json) {
+        Ok(document) => document,
+        Err(e) => {
+            println!("Lazy error: {}", e);
+            return;
+        }
+    };
+    let root = document.root();
+
+    for index in 0..3 {
+        match user_summary(root, index) {
+            Ok(Some(summary)) => println!("users[{}] -> {}", index, summary),
+            Ok(None) => println!("users[{}] not found", index),
+            Err(e) => println!("Lazy error: {}", e),
+        }
+    }
+
+    for entry in root.entries() {
+        match entry {
+            Ok((key, value)) => println!(
+                "  {} (object: {}, array: {}) -> {}",
+                key,
+                value.is_object(),
+                value.is_array(),
+                value.raw().unwrap_or("?")
+            ),
+            Err(e) => println!("Lazy error: {}", e),
+        }
+    }
+
+    // 需要完整子树时再调用 parse()
+    if let Ok(Some(payload)) = root.get("payload") {
+        let sum: f64 = payload
+            .elements()
+            .filter_map(|element| element.ok()?.as_f64())
+            .sum();
+        println!("payload sum = {}, AST = {:?}", sum, payload.parse());
+    }
+}
@@ -0,0 +1,40 @@
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示带类型的取值辅助方法：JSON Pointer 查找 + 类型转换 + 清晰的错误信息
+struct Config {
+    name: String,
+    port: u64,
+    ratio: f64,
+    debug: bool,
+    timeout: Option<i64>,
+}
+
+fn load(text: &str) -> Result<Config, Error> {
+    let value = parse_str(text)?;
+    Ok(Config {
+        name: value.required_str("/service/name")?.to_string(),
+        port: value.required_u64("/service/port")?,
+        ratio: value.optional_f64("/sampling/ratio")?.unwrap_or(1.0),
+        debug: value.optional_bool("/debug")?.unwrap_or(false),
+        timeout: value.optional_i64("/service/timeout")?,
+    })
+}
+
+fn main() {
+    let inputs = [
+        r#"{"service": {"name": "billing", "port": 8080}, "sampling": {"ratio": 0.25}}"#,
+        r#"{"service": {"name": "billing", "port": 8080, "timeout": null}, "debug": true}"#,
+        r#"{"service": {"name": 42, "port": 8080}}"#,
+        r#"{"service": {"name": "billing"}}"#,
+        r#"{"service": {"name": "billing", "port": 80}, "debug": "yes"}"#,
+    ];
+    for input in inputs {
+        match load(input) {
+            Ok(config) => println!(
+                "{} on port {} (ratio {}, debug {}, timeout {:?})",
+                config.name, config.port, config.ratio, config.debug, config.timeout
+            ),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use rust_practice_json_parser::merge::merge3;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示三方合并：两个人基于同一个版本修改了配置，合并时只在真正冲突的地方报错
+fn main() -> Result<(), Error> {
+    let base = parse_str(
+        r#"{"name": "api", "replicas": 2, "ports": [80, 443],
+            "env": {"LOG_LEVEL": "info", "REGION": "eu"}}"#,
+    )?;
+    let ours = parse_str(
+        r#"{"name": "api", "replicas": 3, "ports": [8080, 443],
+            "env": {"LOG_LEVEL": "info", "REGION": "eu", "FEATURE_X": "on"}}"#,
+    )?;
+    let theirs = parse_str(
+        r#"{"name": "api", "replicas": 2, "ports": [80, 8443],
+            "env": {"LOG_LEVEL": "debug"}, "owner": "team-x"}"#,
+    )?;
+
+    // 不同的键、数组里不同的元素分别被修改，可以自动合并
+    match merge3(&base, &ours, &theirs) {
+        Ok(merged) => println!("merged:\n{:#}", merged),
+        Err(conflicts) => println!("{} conflicts", conflicts.len()),
+    }
+
+    // 双方把同一个值改成了不同的内容，报告冲突而不是默默覆盖
+    let theirs = parse_str(
+        r#"{"name": "api", "replicas": 5, "ports": [80, 443, 8443],
+            "env": {"LOG_LEVEL": "info", "REGION": "eu"}}"#,
+    )?;
+    if let Err(conflicts) = merge3(&base, &ours, &theirs) {
+        for conflict in &conflicts {
+            println!("conflict at {}", conflict);
+        }
+    }
+    Ok(())
+}
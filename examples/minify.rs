@@ -0,0 +1,32 @@
+use rust_practice_json_parser::{Error, format};
+use std::io::{BufReader, Cursor};
+
+// 演示流式压缩：逐个 token 处理，不构建 Value，内存占用与输入大小无关
+fn main() -> Result<(), Error> {
+    let input = r#"
+    {
+        "name" : "demo",
+        "price": 12.50,
+        "tags" : [ "a", "b" , [] , {} ],
+        "nested": { "ok": true, "none": null }
+    }
+    "#;
+    let mut output = Vec::new();
+    format::minify(input.as_bytes(), &mut output)?;
+    println!("{}", String::from_utf8_lossy(&output));
+
+    // 大文件可以直接从文件读、写到文件，例如：
+    // format::minify(BufReader::new(File::open("big.json")?), File::create("big.min.json")?)?;
+    let reader = BufReader::with_capacity(16, Cursor::new(r#"[1, 2, {"a": "x y"}]"#));
+    let mut output = Vec::new();
+    format::minify(reader, &mut output)?;
+    println!("{}", String::from_utf8_lossy(&output));
+
+    // 语法错误会被报告
+    let mut output = Vec::new();
+    println!(
+        "{:?}",
+        format::minify(r#"{"a": [1, 2}"#.as_bytes(), &mut output)
+    );
+    Ok(())
+}
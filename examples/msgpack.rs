@@ -0,0 +1,19 @@
+use rust_practice_json_parser::msgpack::{from_msgpack, to_msgpack};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 MessagePack 编解码：经由 Value 在 JSON 和 MessagePack 之间转换
+fn main() -> Result<(), Error> {
+    let value = parse_str(r#"{"compact": true, "schema": 0, "tags": ["a", "b"], "ratio": 0.25}"#)?;
+
+    let bytes = to_msgpack(&value);
+    println!("{} 字节: {:02x?}", bytes.len(), bytes);
+
+    // 其他服务发来的 MessagePack 可以直接转成 JSON
+    let decoded = from_msgpack(&bytes)?;
+    println!("{}", decoded);
+    println!("{}", decoded == value);
+
+    // 二进制数据在 JSON 中没有对应类型
+    println!("{:?}", from_msgpack(&[0xc4, 0x01, 0xff]));
+    Ok(())
+}
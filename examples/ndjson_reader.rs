@@ -0,0 +1,15 @@
+use rust_practice_json_parser::ndjson;
+
+// 演示逐行读取 NDJSON（JSON Lines）
+fn main() {
+    let input = "{\"level\": \"info\"}\n\n{\"level\": \"warn\",}\n[1, 2]\n";
+    // 实际使用时可以传入 BufReader::new(File::open("logs.ndjson")?)
+    let mut reader = ndjson::Reader::new(input.as_bytes());
+    for result in reader.by_ref() {
+        match result {
+            Ok(ast) => println!("  {:?}", ast),
+            Err(e) => println!("  Error: {}", e),
+        }
+    }
+    println!("Read {} lines", reader.line_number());
+}
@@ -0,0 +1,31 @@
+use rust_practice_json_parser::{Error, parse_str, serializer};
+
+// 演示排序工具：在对比或做快照测试之前，把文档整理成固定的形式
+fn main() -> Result<(), Error> {
+    let mut before = parse_str(
+        r#"{"version": 2, "users": [{"name": "Bob", "id": 7}, {"id": 3, "name": "Alice"}],
+            "meta": {"z": true, "a": null}}"#,
+    )?;
+    let mut after = parse_str(
+        r#"{"meta": {"a": null, "z": true},
+            "users": [{"id": 3, "name": "Alice"}, {"name": "Bob", "id": 7}], "version": 2}"#,
+    )?;
+
+    for document in [&mut before, &mut after] {
+        document.sort_keys_recursive();
+        document.sort_array_by_path("users", |a, b| a["id"].as_u64().cmp(&b["id"].as_u64()))?;
+    }
+    println!("{:#}", before);
+    println!(
+        "identical text: {}",
+        serializer::to_string(&before) == serializer::to_string(&after)
+    );
+
+    // 路径不存在或不是数组时返回错误
+    for path in ["meta", "missing"] {
+        if let Err(e) = before.sort_array_by_path(path, |_, _| std::cmp::Ordering::Equal) {
+            println!("sort {}: {}", path, e);
+        }
+    }
+    Ok(())
+}
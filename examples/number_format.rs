@@ -0,0 +1,28 @@
+use rust_practice_json_parser::serializer::{self, SerializeOptions};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示数字的输出格式：导出给对格式有要求的下游系统
+fn main() -> Result<(), Error> {
+    let prices = parse_str(r#"{"unit": 0.1, "total": 1234567.5, "count": 3, "rate": 0.0000025}"#)?;
+
+    // 默认输出能精确还原的最短形式
+    println!("{}", prices);
+
+    // 浮点数固定保留两位小数（整数不受影响）
+    let fixed = SerializeOptions::new().with_float_decimals(Some(2));
+    println!("{}", serializer::to_string_with(&prices, &fixed));
+
+    // 调整切换到指数形式的阈值
+    let scientific = SerializeOptions::new().with_exponent_thresholds(1e-3, 1e6);
+    println!("{}", serializer::to_string_with(&prices, &scientific));
+
+    // 整数值的浮点数保留 .0
+    let floats = parse_str("[1.0, 2.5, 10]")?;
+    let point = SerializeOptions::new().with_float_point(true);
+    println!(
+        "{} -> {}",
+        floats,
+        serializer::to_string_with(&floats, &point)
+    );
+    Ok(())
+}
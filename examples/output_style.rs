@@ -0,0 +1,43 @@
+use rust_practice_json_parser::serializer::{
+    self, Indent, KeyQuoting, Newline, SerializeOptions, Style,
+};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示输出风格：缩进、空格、键的引号和换行符可以组合成一个 Style
+fn main() -> Result<(), Error> {
+    let config =
+        parse_str(r#"{"name": "demo", "version": 2, "ports": [80, 443], "my-key": null}"#)?;
+
+    for (label, style) in [
+        ("compact", Style::compact()),
+        ("pretty", Style::pretty()),
+        // 键按字典序排列、每个元素一行、末尾带换行，适合放进版本库
+        ("diff_friendly", Style::diff_friendly()),
+    ] {
+        let options = SerializeOptions::new().with_style(style);
+        println!(
+            "{}:\n{}",
+            label,
+            serializer::to_string_with(&config, &options)
+        );
+    }
+
+    // 自定义：单行但带空格，标识符形式的键不加引号（JSON5）
+    let json5 = Style::compact()
+        .with_space_after_colon(true)
+        .with_space_after_comma(true)
+        .with_key_quoting(KeyQuoting::WhenNeeded);
+    let options = SerializeOptions::new().with_style(json5);
+    println!("{}", serializer::to_string_with(&config, &options));
+
+    // Tab 缩进和 CRLF 换行
+    let windows = Style::pretty()
+        .with_indent(Some(Indent::Tab))
+        .with_newline(Newline::CrLf);
+    let options = SerializeOptions::new().with_style(windows);
+    println!(
+        "{:?}",
+        serializer::to_string_with(&config["ports"], &options)
+    );
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use rust_practice_json_parser::sax;
+
+// 演示解析首尾相连的多个 JSON 文档
+fn main() {
+    let input = r#"{"a": 1}{"a": 2} 3 "x" [true]"#;
+    for (i, result) in sax::parse_many(input).enumerate() {
+        match result {
+            Ok(ast) => println!("  {}. {:?}", i + 1, ast),
+            Err(e) => println!("  {}. Error: {}", i + 1, e),
+        }
+    }
+}
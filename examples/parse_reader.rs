@@ -0,0 +1,20 @@
+use rust_practice_json_parser::streaming::{self, DEFAULT_BUFFER_SIZE};
+
+// 演示直接从 io::Read 解析，无需先读入整个字符串
+fn main() {
+    let json = r#"{"users": [{"name": "Alice", "age": 25}, {"name": "Bob", "age": 30}]}"#;
+
+    // 实际使用时可以传入 BufReader::new(File::open("big.json")?)
+    match streaming::parse_reader(json.as_bytes()) {
+        Ok(ast) => println!("AST: {:?}", ast),
+        Err(e) => println!("Reader error: {}", e),
+    }
+
+    // 缓冲区很小也能正确解析，只是读取次数更多
+    for buffer_size in [4, DEFAULT_BUFFER_SIZE] {
+        match streaming::parse_reader_with_buffer_size(json.as_bytes(), buffer_size) {
+            Ok(_) => println!("Parsed with buffer size {}", buffer_size),
+            Err(e) => println!("Reader error: {}", e),
+        }
+    }
+}
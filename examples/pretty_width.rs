@@ -0,0 +1,21 @@
+use rust_practice_json_parser::serializer::{self, SerializeOptions};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示按宽度排版：放得下一行的小数组和小对象保持在一行，配置文件更易读
+fn main() -> Result<(), Error> {
+    let config = parse_str(
+        r#"{"name": "demo", "ports": [80, 443, 8080], "matrix": [[1, 2], [3, 4]],
+            "servers": [{"host": "a.example.com", "weight": 1},
+                        {"host": "b.example.com", "weight": 2, "tags": ["primary", "eu-west-1", "ssd", "backup"]}]}"#,
+    )?;
+
+    // 普通的格式化输出：每个元素各占一行
+    println!("{:#}", config);
+
+    // 宽度不超过 60 列时放在一行
+    let options = SerializeOptions::new()
+        .with_pretty(true)
+        .with_max_width(Some(60));
+    println!("{}", serializer::to_string_with(&config, &options));
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use rust_practice_json_parser::sax::TreeBuilder;
+use rust_practice_json_parser::streaming::{self, CancellationToken, StreamingParser};
+
+// 演示进度回调与取消解析
+fn main() {
+    let elements: Vec<String> = (0..10_000).map(|i| i.to_string()).collect();
+    let json = format!("[{}]", elements.join(", "));
+
+    // 每解析 16 KiB 汇报一次进度
+    let parser = StreamingParser::new(TreeBuilder::new()).with_progress(16 * 1024, |progress| {
+        println!(
+            "  progress: {} bytes, {} values",
+            progress.bytes, progress.values
+        )
+    });
+    match streaming::feed_reader(json.as_bytes(), parser) {
+        Ok(_) => println!("Parsed {} bytes", json.len()),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    // 在回调中（或其他线程里）取消解析
+    let token = CancellationToken::new();
+    let trigger = token.clone();
+    let parser = StreamingParser::new(TreeBuilder::new())
+        .with_cancellation(token)
+        .with_progress(8 * 1024, move |progress| {
+            if progress.values > 2_000 {
+                trigger.cancel();
+            }
+        });
+    match streaming::feed_reader(json.as_bytes(), parser) {
+        Ok(_) => println!("Parsed {} bytes", json.len()),
+        Err(e) => println!("Error: {}", e),
+    }
+}
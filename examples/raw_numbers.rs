@@ -0,0 +1,26 @@
+use rust_practice_json_parser::parser::ParseOptions;
+use rust_practice_json_parser::serializer;
+use rust_practice_json_parser::{Error, parse_str, parse_str_with};
+
+// 演示 raw_numbers：保留数字的原始写法，输出时逐字节还原
+fn main() -> Result<(), Error> {
+    let text = r#"{"rate":0.1000,"total":1.50,"count":10}"#;
+
+    // 默认解析：数字被规范化
+    println!("parsed: {}", parse_str(text)?);
+
+    // 保留原文：序列化结果与输入完全一致
+    let options = ParseOptions::new().with_raw_numbers(true);
+    let value = parse_str_with(text, &options)?;
+    println!("raw:    {}", value);
+    println!(
+        "round-trip exact: {}",
+        serializer::to_string(&value) == text
+    );
+
+    // 原始文本交给调用方自行解析，同时仍可按数值使用
+    let rate = value["rate"].as_number().unwrap();
+    println!("rate lexeme {:?}, as_f64 {}", rate.as_raw(), rate.as_f64());
+    println!("total == 1.5: {}", value["total"] == 1.5);
+    Ok(())
+}
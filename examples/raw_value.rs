@@ -0,0 +1,31 @@
+use rust_practice_json_parser::lazy::LazyDocument;
+use rust_practice_json_parser::{Error, RawValue, Value};
+
+// 演示 RawValue：只检查少数字段，其余部分原样转发
+fn main() -> Result<(), Error> {
+    let request = r#"{"route": "orders", "trace": "a1b2",
+        "payload": {"items": [{"sku": "X-1", "price": 10.50}], "note": "  keep   spacing  "}}"#;
+
+    // 懒解析：只读取 route，payload 不展开
+    let document = LazyDocument::parse(request)?;
+    let root = document.root();
+    let route = root.get("route")?.and_then(|v| v.as_str()).unwrap_or("?");
+    let payload = root
+        .get("payload")?
+        .ok_or("missing payload")?
+        .to_raw_value()?;
+
+    // 把未解析的 payload 嵌入新的信封中，序列化时逐字节输出
+    let mut envelope = Value::object();
+    envelope["target"] = format!("backend/{}", route).into();
+    envelope["payload"] = payload.into();
+    println!("{:#}", envelope);
+
+    // 需要时仍然可以解析出来
+    let items = envelope["payload"].as_raw_value().unwrap().parse()?;
+    println!("items: {}", items["items"]);
+
+    // 非法的 JSON 文本无法构造 RawValue
+    println!("{:?}", RawValue::from_string("{\"a\": }"));
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use rust_practice_json_parser::path::PathPattern;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示脱敏：在写日志之前，把匹配路径模式的敏感字段替换掉
+fn main() -> Result<(), Error> {
+    let mut event = parse_str(
+        r#"{"user": {"name": "alice", "password": "hunter2"},
+            "admin": {"name": "root", "password": "toor"},
+            "sessions": [{"id": 1, "token": "abc"}, {"id": 2, "token": "def"}],
+            "payment": {"card": {"number": "4111111111111111", "cvv": "123"}, "amount": 10}}"#,
+    )?;
+
+    // `*` 匹配任意一个键或下标，`..` 匹配任意层级
+    let patterns = ["$.*.password", "sessions[*].token", "$..cvv"]
+        .iter()
+        .map(|p| PathPattern::parse(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let count = event.redact(&patterns, "***");
+    println!("redacted {} fields:\n{:#}", count, event);
+
+    // 整个子树也可以一起替换
+    let card = [PathPattern::parse("payment.card")?];
+    event.redact(&card, "[removed]");
+    println!("payment: {}", event["payment"]);
+
+    println!("{:?}", PathPattern::parse("$.users[").map(|_| ()));
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use rust_practice_json_parser::serializer::{Indent, KeyQuoting, Style};
+use rust_practice_json_parser::{Error, format};
+
+// 演示流式重排版：直接根据 token 流重新缩进，不构建 Value
+fn main() -> Result<(), Error> {
+    let input = r#"{"name":"demo","price":12.50,"tags":["a","b"],"empty":{},"list":[],"nested":{"ok":true}}"#;
+
+    for (label, style) in [
+        ("pretty", Style::pretty()),
+        ("tab", Style::pretty().with_indent(Some(Indent::Tab))),
+        (
+            "json5",
+            Style::compact()
+                .with_space_after_colon(true)
+                .with_space_after_comma(true)
+                .with_key_quoting(KeyQuoting::WhenNeeded),
+        ),
+    ] {
+        let mut output = Vec::new();
+        format::reformat(input.as_bytes(), &mut output, &style)?;
+        println!("{}:\n{}", label, String::from_utf8_lossy(&output));
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use rust_practice_json_parser::refs::ResolveOptions;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示展开文档内部的 $ref：OpenAPI 和 JSON Schema 文档经常通过引用复用定义
+fn main() -> Result<(), Error> {
+    let spec = parse_str(
+        r##"{"definitions": {
+                "address": {"type": "object", "properties": {"city": {"type": "string"}}},
+                "person": {"type": "object", "properties": {
+                    "home": {"$ref": "#/definitions/address"},
+                    "work": {"$ref": "#/definitions/address"}}}},
+            "schema": {"$ref": "#/definitions/person"}}"##,
+    )?;
+    let resolved = spec.resolve_refs()?;
+    println!("{:#}", resolved["schema"]);
+
+    // 引用自身的定义无法展开，返回错误而不是无限递归
+    let tree = parse_str(
+        r##"{"definitions": {"node": {"type": "object",
+                "properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}}}},
+            "root": {"$ref": "#/definitions/node"}}"##,
+    )?;
+    println!("{}", tree.resolve_refs().unwrap_err());
+
+    // 限制展开的次数，防止层层引用把文档放大到失控
+    let limited = ResolveOptions::new().with_max_expansions(2);
+    println!("{}", spec.resolve_refs_with(&limited).unwrap_err());
+
+    let broken = parse_str(r##"{"a": {"$ref": "#/missing"}}"##)?;
+    println!("{}", broken.resolve_refs().unwrap_err());
+    Ok(())
+}
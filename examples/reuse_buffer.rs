@@ -0,0 +1,39 @@
+use rust_practice_json_parser::serializer::{self, SerializeOptions, Style};
+use rust_practice_json_parser::{Error, parse_str};
+use std::fmt::Write;
+
+// 演示复用缓冲区：循环中序列化大量文档时，不必每次都分配新的 String
+fn main() -> Result<(), Error> {
+    let records = [
+        parse_str(r#"{"id": 1, "name": "a"}"#)?,
+        parse_str(r#"{"id": 2, "name": "b"}"#)?,
+    ];
+
+    let mut line = String::new();
+    for record in &records {
+        line.clear();
+        serializer::to_string_into(record, &mut line);
+        println!("{} (capacity {})", line, line.capacity());
+    }
+
+    // 直接写成字节，例如准备发送到网络
+    let mut bytes = Vec::new();
+    for record in &records {
+        serializer::to_vec_into(record, &mut bytes);
+        bytes.push(b'\n');
+    }
+    println!("{} bytes", bytes.len());
+
+    // 写入任意 fmt::Write，并且可以带上输出选项
+    let mut report = String::from("records: ");
+    let style = Style::compact()
+        .with_space_after_colon(true)
+        .with_space_after_comma(true);
+    let options = SerializeOptions::new().with_style(style);
+    for record in &records {
+        serializer::to_fmt_writer(&mut report, record, &options).map_err(|e| e.to_string())?;
+        report.write_char(' ').map_err(|e| e.to_string())?;
+    }
+    println!("{}", report);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use rust_practice_json_parser::Number;
+use rust_practice_json_parser::sax::{self, ContentHandler};
+
+// 演示 SAX 风格的回调解析
+fn main() {
+    // 只统计事件数量，不构建 AST
+    #[derive(Default)]
+    struct Counter {
+        keys: usize,
+        values: usize,
+        depth: usize,
+        max_depth: usize,
+    }
+
+    impl ContentHandler for Counter {
+        fn on_object_start(&mut self) {
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+        }
+        fn on_object_end(&mut self) {
+            self.depth -= 1;
+        }
+        fn on_array_start(&mut self) {
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+        }
+        fn on_array_end(&mut self) {
+            self.depth -= 1;
+        }
+        fn on_key(&mut self, _key: &str) {
+            self.keys += 1;
+        }
+        fn on_string(&mut self, _value: &str) {
+            self.values += 1;
+        }
+        fn on_number(&mut self, _value: Number) {
+            self.values += 1;
+        }
+        fn on_bool(&mut self, _value: bool) {
+            self.values += 1;
+        }
+        fn on_null(&mut self) {
+            self.values += 1;
+        }
+    }
+
+    let json = r#"{"users": [{"name": "Alice", "age": 25}, {"name": "Bob", "admin": true}]}"#;
+    let mut counter = Counter::default();
+    match sax::generate(json, &mut counter) {
+        Ok(()) => println!(
+            "keys: {}, values: {}, max depth: {}",
+            counter.keys, counter.values, counter.max_depth
+        ),
+        Err(e) => println!("SAX error: {}", e),
+    }
+}
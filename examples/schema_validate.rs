@@ -0,0 +1,44 @@
+use rust_practice_json_parser::schema::Schema;
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 JSON Schema 校验：编译一次，返回所有违规项及其在实例和 schema 中的位置
+fn main() -> Result<(), Error> {
+    let schema = Schema::compile(&parse_str(
+        r##"{
+            "type": "object",
+            "required": ["id", "email", "roles"],
+            "properties": {
+                "id": {"type": "integer", "minimum": 1},
+                "email": {"type": "string", "pattern": "^[^@ ]+@[a-z0-9-]+([.][a-z0-9-]+)*[.][a-z]{2,}$"},
+                "roles": {"type": "array", "minItems": 1, "items": {"enum": ["admin", "dev", "ops"]}},
+                "manager": {"$ref": "#"},
+                "nickname": {"type": ["string", "null"], "maxLength": 8}
+            },
+            "additionalProperties": false
+        }"##,
+    )?)?;
+
+    let inputs = [
+        r#"{"id": 1, "email": "alice@example.com", "roles": ["admin"]}"#,
+        r#"{"id": 2, "email": "bob@example.com", "roles": ["dev"],
+            "manager": {"id": 1, "email": "alice@example.com", "roles": ["admin"]}, "nickname": null}"#,
+        r#"{"id": 0, "email": "not-an-email", "roles": [], "nickname": "far too long", "extra": true}"#,
+        r#"{"email": "carol@example.com", "roles": ["root"], "manager": {"id": "x", "roles": ["ops"]}}"#,
+    ];
+    for input in inputs {
+        match schema.validate(&parse_str(input)?) {
+            Ok(()) => println!("valid"),
+            Err(violations) => {
+                println!("{} violations:", violations.len());
+                for violation in violations {
+                    println!("  {}", violation);
+                }
+            }
+        }
+    }
+
+    // schema 本身有误时 compile 返回错误
+    let broken = parse_str(r##"{"properties": {"a": {"$ref": "#/$defs/missing"}}}"##)?;
+    println!("{}", Schema::compile(&broken).unwrap_err());
+    Ok(())
+}
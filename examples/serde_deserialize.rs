@@ -0,0 +1,69 @@
+use rust_practice_json_parser::{Error, Value, de};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+// 演示 serde 反序列化：直接从文本得到结构体，中间不生成 Value
+#[derive(Deserialize)]
+struct Order<'a> {
+    id: u64,
+    // 借用输入中的字符串，不复制
+    customer: &'a str,
+    items: Vec<Item>,
+    status: Status,
+    note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    sku: String,
+    quantity: u32,
+    price: f64,
+}
+
+#[derive(Deserialize)]
+enum Status {
+    Paid,
+    Shipped { carrier: String },
+}
+
+fn main() -> Result<(), Error> {
+    let text = r#"{
+        "id": 42,
+        "customer": "alice",
+        "items": [{"sku": "A-1", "quantity": 2, "price": 9.5}],
+        "status": {"Shipped": {"carrier": "post"}},
+        "note": null
+    }"#;
+    let order: Order = de::from_str(text)?;
+    println!("#{} {} note={:?}", order.id, order.customer, order.note);
+    for item in &order.items {
+        println!("  {} x{} @ {}", item.sku, item.quantity, item.price);
+    }
+    describe(&order.status);
+
+    let status: Status = de::from_str(r#""Paid""#)?;
+    describe(&status);
+
+    // 从任意 io::Read 读取，结果不能借用输入
+    let counts: BTreeMap<String, u32> = de::from_reader(r#"{"a": 1, "b": 2}"#.as_bytes())?;
+    println!("{:?}", counts);
+
+    // Value 也实现了 Deserialize
+    let value: Value = de::from_str("[1, true, null]")?;
+    println!("{}", value);
+
+    // 类型不符或缺少字段时返回错误
+    println!(
+        "{:?}",
+        de::from_str::<Item>(r#"{"sku": "A-1", "quantity": "two"}"#)
+    );
+    println!("{:?}", de::from_str::<Vec<u32>>("[1, 2,]"));
+    Ok(())
+}
+
+fn describe(status: &Status) {
+    match status {
+        Status::Paid => println!("paid"),
+        Status::Shipped { carrier } => println!("shipped by {}", carrier),
+    }
+}
@@ -0,0 +1,65 @@
+use rust_practice_json_parser::serializer::SerializeOptions;
+use rust_practice_json_parser::{Error, ser};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// 演示 serde 支持：任何实现了 Serialize 的类型都可以直接输出，不需要 serde_json
+#[derive(Serialize)]
+struct Order {
+    id: u64,
+    customer: String,
+    items: Vec<Item>,
+    status: Status,
+    note: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Item {
+    sku: &'static str,
+    quantity: u32,
+    price: f64,
+}
+
+#[derive(Serialize)]
+enum Status {
+    Paid,
+    Shipped { carrier: String },
+}
+
+fn main() -> Result<(), Error> {
+    let order = Order {
+        id: 42,
+        customer: "alice".to_string(),
+        items: vec![Item {
+            sku: "A-1",
+            quantity: 2,
+            price: 9.5,
+        }],
+        status: Status::Shipped {
+            carrier: "post".to_string(),
+        },
+        note: None,
+    };
+    println!("{}", ser::to_string(&order)?);
+
+    // 先得到 Value，可以继续修改
+    let mut value = ser::to_value(&order)?;
+    value["note"] = "leave at door".into();
+    println!("{}", value["note"]);
+
+    let options = SerializeOptions::new().with_pretty(true);
+    println!("{}", ser::to_string_with(&Status::Paid, &options)?);
+
+    // 写入任意 io::Write
+    let mut counts = BTreeMap::new();
+    counts.insert(1, "one");
+    counts.insert(2, "two");
+    let mut out = Vec::new();
+    ser::to_writer(&mut out, &counts)?;
+    println!("{}", String::from_utf8_lossy(&out));
+
+    // 不是字符串的键会报错
+    let bad: BTreeMap<Vec<u8>, u8> = [(vec![1], 1)].into_iter().collect();
+    println!("{:?}", ser::to_string(&bad));
+    Ok(())
+}
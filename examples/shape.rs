@@ -0,0 +1,31 @@
+use rust_practice_json_parser::{Error, parse_str, shape};
+
+// 演示轻量的结构校验：用 shape! 描述期望的结构，一次报告所有不匹配的位置
+fn main() -> Result<(), Error> {
+    let request = shape!({
+        "id": u64,
+        "name": str,
+        "tags": [str],
+        "address": { "city": str, "zip"?: str },
+        "meta"?: (object | null)
+    });
+
+    let inputs = [
+        r#"{"id": 7, "name": "Alice", "tags": ["a"], "address": {"city": "Paris"}}"#,
+        r#"{"id": 7, "name": "Alice", "tags": [], "address": {"city": "Paris"}, "meta": null, "extra": 1}"#,
+        r#"{"id": "7", "tags": ["a", 2], "address": {"zip": 75001}, "meta": []}"#,
+    ];
+    for input in inputs {
+        let value = parse_str(input)?;
+        match request.check(&value) {
+            Ok(()) => println!("ok"),
+            Err(mismatches) => {
+                println!("{} mismatches:", mismatches.len());
+                for mismatch in mismatches {
+                    println!("  {}", mismatch);
+                }
+            }
+        }
+    }
+    Ok(())
+}
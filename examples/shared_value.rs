@@ -0,0 +1,62 @@
+use rust_practice_json_parser::shared::{self, ArcValue, KeyInterner};
+use rust_practice_json_parser::{Error, parse_str};
+use std::thread;
+
+// 演示 ArcValue：同一份配置分发给多个线程，clone 只增加引用计数
+fn main() -> Result<(), Error> {
+    let config = parse_str(
+        r#"{"service": "billing", "workers": 4, "limits": {"rps": 250, "burst": 50},
+            "regions": ["eu-west", "us-east"]}"#,
+    )?;
+    let shared = ArcValue::from(&config);
+
+    let handles: Vec<_> = (0..3)
+        .map(|id| {
+            let config = shared.clone(); // O(1)
+            thread::spawn(move || {
+                let rps = config["limits"]["rps"].as_u64().unwrap_or(0);
+                let region = config["regions"][id % 2].as_str().unwrap_or("?");
+                format!("worker {} -> {} rps in {}", id, rps, region)
+            })
+        })
+        .collect();
+    for handle in handles {
+        println!("{}", handle.join().unwrap());
+    }
+
+    // 需要修改时转换回普通的 Value
+    let mut edited = shared.to_value();
+    edited["workers"] = 8.into();
+    println!("shared: {}", shared["workers"]);
+    println!("edited: {}", edited["workers"]);
+
+    // 去重：相同的子树只保留一份，共享同一块内存
+    let telemetry = parse_str(
+        r#"[{"device": {"model": "T-100", "firmware": "2.1"}, "temp": 21},
+            {"device": {"model": "T-100", "firmware": "2.1"}, "temp": 23},
+            {"device": {"model": "T-100", "firmware": "2.1"}, "temp": 21}]"#,
+    )?;
+    let deduped = telemetry.dedup();
+    println!(
+        "devices shared: {}, identical readings shared: {}",
+        deduped[0]["device"].ptr_eq(&deduped[1]["device"]),
+        deduped[0].ptr_eq(&deduped[2])
+    );
+    println!("unchanged content: {}", deduped.to_value() == telemetry);
+
+    // 键字符串驻留：多次解析共用一个 KeyInterner，相同的键只分配一次
+    let mut interner = KeyInterner::new();
+    let batches = [
+        r#"[{"id": 1, "status": "ok"}, {"id": 2, "status": "ok"}]"#,
+        r#"[{"id": 3, "status": "failed", "retry": true}]"#,
+    ];
+    for batch in batches {
+        let rows = shared::parse_str(batch, &mut interner)?;
+        println!(
+            "{} rows, {} distinct keys so far",
+            rows.as_array().map_or(0, |r| r.len()),
+            interner.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示内存估算：整个文档的大小，以及各个子树分别占用多少
+fn main() -> Result<(), Error> {
+    let document = parse_str(
+        r#"{"users": [{"name": "Alice", "bio": "Writes parsers for fun and profit"},
+                      {"name": "Bob", "bio": ""}],
+            "settings": {"theme": "dark", "retries": 3},
+            "tags": ["a", "b", "c", "d", "e", "f", "g", "h"]}"#,
+    )?;
+
+    println!("total: ~{} bytes", document.estimated_size_bytes());
+
+    // 只看前两层，按路径列出
+    for (path, bytes) in document.size_breakdown(2) {
+        let indent = "  ".repeat(path.segments().len());
+        println!(
+            "{}{} ~{} bytes",
+            indent,
+            if path.segments().is_empty() {
+                "$".to_string()
+            } else {
+                path.to_string()
+            },
+            bytes
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use rust_practice_json_parser::sqlite::to_sqlite_script;
+use rust_practice_json_parser::{Error, parse_str};
+use std::io;
+
+// 演示把对象数组导出为 SQLite 脚本：
+// cargo run -q --example sqlite --features sqlite | sqlite3 dump.db
+fn main() -> Result<(), Error> {
+    let users = parse_str(
+        r#"[
+            {"id": 1, "name": "Alice", "active": true, "score": 9.5, "tags": ["admin"]},
+            {"id": 2, "name": "Bob's", "active": false, "address": {"city": "Paris"}},
+            {"id": 3, "name": "Carol", "score": null}
+        ]"#,
+    )?;
+    // 每个顶层键一列，嵌套的值存为 JSON 文本，可以用 json_extract 查询
+    to_sqlite_script(io::stdout(), "users", &users)
+}
@@ -0,0 +1,38 @@
+use rust_practice_json_parser::{Error, parse_str, serializer, stats};
+
+// 演示文档统计：找出一个响应为什么这么大、这么慢
+fn main() -> Result<(), Error> {
+    let mut events = Vec::new();
+    for i in 0..50 {
+        events.push(format!(
+            r#"{{"id": {}, "type": "click", "payload": {{"x": {}, "y": {}, "trace": "{}"}}}}"#,
+            i,
+            i * 3,
+            i * 7,
+            "ab".repeat(i)
+        ));
+    }
+    let document = parse_str(&format!(
+        r#"{{"user": {{"name": "alice", "tags": ["a", "b"], "active": true, "manager": null}},
+            "events": [{}], "thumbnail": "{}"}}"#,
+        events.join(","),
+        "x".repeat(700)
+    ))?;
+
+    let stats = stats::analyze(&document);
+    print!("{}", stats);
+
+    // 各项统计也可以直接读取
+    println!(
+        "largest: {:?}",
+        stats
+            .largest
+            .first()
+            .map(|(path, size)| (path.to_string(), *size))
+    );
+    println!(
+        "size check: {}",
+        stats.serialized_bytes == serializer::to_string(&document).len()
+    );
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use rust_practice_json_parser::sax::{Event, TreeBuilder};
+use rust_practice_json_parser::streaming::StreamingParser;
+use std::collections::VecDeque;
+
+// 演示分块喂入数据的增量解析
+fn main() {
+    // 模拟网络上分多次到达的数据，切分点可以落在 token 中间
+    let chunks: [&[u8]; 4] = [
+        b"{\"na",
+        b"me\": \"Alice\", \"ag",
+        b"e\": 2",
+        b"5, \"tags\": [true, null]}",
+    ];
+
+    let mut parser = StreamingParser::new(TreeBuilder::new());
+    for chunk in chunks {
+        if let Err(e) = parser.feed(chunk) {
+            println!("Streaming error: {}", e);
+            return;
+        }
+    }
+    match parser.finish() {
+        Ok(builder) => println!("AST: {:?}", builder.into_value()),
+        Err(e) => println!("Streaming error: {}", e),
+    }
+
+    // 也可以不构建 AST，每收到一块数据就取出已经产生的事件
+    let mut parser = StreamingParser::new(VecDeque::<Event>::new());
+    for chunk in chunks {
+        if let Err(e) = parser.feed(chunk) {
+            println!("Streaming error: {}", e);
+            return;
+        }
+        let events: Vec<Event> = parser.handler_mut().drain(..).collect();
+        println!(
+            "  chunk {:?} -> {:?}",
+            String::from_utf8_lossy(chunk),
+            events
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use rust_practice_json_parser::path::{JsonPath, PathPattern};
+use rust_practice_json_parser::serializer::{self, SerializeOptions, StringHook};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 在序列化时脱敏：不修改 Value，只改变写出的内容
+struct Mask {
+    patterns: Vec<PathPattern>,
+}
+
+impl StringHook for Mask {
+    fn replace(&self, path: &JsonPath, string: &str) -> Option<String> {
+        // 只保留最后四个字符
+        self.patterns.iter().any(|p| p.matches(path)).then(|| {
+            let visible: String = string.chars().rev().take(4).collect();
+            format!("****{}", visible.chars().rev().collect::<String>())
+        })
+    }
+
+    // 下游系统不认识 '/'，强制写成 \u002f
+    fn escape(&self, c: char) -> bool {
+        c == '/'
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let event = parse_str(
+        r#"{"user": "alice", "card": {"number": "4111111111111111"}, "callback": "https://example.com/hook"}"#,
+    )?;
+    let mask = Mask {
+        patterns: vec![PathPattern::parse("$..number")?],
+    };
+    let options = SerializeOptions::new().with_pretty(true);
+    println!(
+        "{}",
+        serializer::to_string_with_hook(&event, &options, &mask)
+    );
+    // 原来的 Value 没有变化
+    println!("{}", event);
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use rust_practice_json_parser::substitute::{SubstituteOptions, Unresolved};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示占位符替换：部署配置里引用环境变量和配置中的其他值
+fn main() -> Result<(), Error> {
+    let config = parse_str(
+        r#"{"defaults": {"host": "db.internal", "port": 5432},
+            "database": {"url": "postgres://${DB_USER}@${defaults.host}:${defaults.port}/app",
+                         "port": "${defaults.port}"},
+            "banner": "cost: $${price} per ${UNIT}"}"#,
+    )?;
+
+    // 变量优先于进程的环境变量；只包含一个占位符的字符串会保留被引用值的类型
+    let options = SubstituteOptions::new()
+        .with_variable("DB_USER", "deploy")
+        .with_variable("UNIT", "hour");
+    println!("{:#}", config.substitute_with(&options)?);
+
+    // 默认情况下找不到的名字是错误
+    let without_vars = SubstituteOptions::new().with_environment(false);
+    println!("{}", config.substitute_with(&without_vars).unwrap_err());
+
+    // 也可以保留原样
+    let keep = without_vars.with_unresolved(Unresolved::Keep);
+    println!("{}", config.substitute_with(&keep)?["database"]["url"]);
+
+    let cycle = parse_str(r#"{"a": "${b}", "b": "x${a}"}"#)?;
+    println!("{}", cycle.substitute().unwrap_err());
+    Ok(())
+}
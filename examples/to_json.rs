@@ -0,0 +1,54 @@
+use rust_practice_json_parser::{ToJson, Value};
+use std::collections::BTreeMap;
+
+// 演示 ToJson：为自己的类型手写转换，组合出来的 Vec、Option、map 等自动可用
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+struct User {
+    name: String,
+    age: u32,
+    tags: Vec<&'static str>,
+    address: Address,
+}
+
+impl ToJson for Address {
+    fn to_json(&self) -> Value {
+        let mut object = Value::object();
+        object["city"] = self.city.to_json();
+        object["zip"] = self.zip.to_json();
+        object
+    }
+}
+
+impl ToJson for User {
+    fn to_json(&self) -> Value {
+        let mut object = Value::object();
+        object["name"] = self.name.to_json();
+        object["age"] = self.age.to_json();
+        object["tags"] = self.tags.to_json();
+        object["address"] = self.address.to_json();
+        object
+    }
+}
+
+fn main() {
+    let users = vec![User {
+        name: "alice".to_string(),
+        age: 30,
+        tags: vec!["admin", "ops"],
+        address: Address {
+            city: "Tokyo".to_string(),
+            zip: None,
+        },
+    }];
+    println!("{:#}", users.to_json());
+
+    // 元组写成数组，BTreeMap 的键按顺序输出
+    let mut scores = BTreeMap::new();
+    scores.insert("b", (2, 0.5));
+    scores.insert("a", (1, 1.5));
+    println!("{}", scores.to_json());
+}
@@ -0,0 +1,40 @@
+use rust_practice_json_parser::toml::{
+    NullPolicy, TomlOptions, from_toml_str, to_toml_string, to_toml_string_with,
+};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 TOML 与 JSON 互转
+fn main() -> Result<(), Error> {
+    let manifest = r#"
+[package]
+name = "demo"
+version = "0.1.0"
+authors = ["alice", 'bob']   # 字面量字符串
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+
+[[bin]]
+name = "cli"
+path = "src/main.rs"
+
+[[bin]]
+name = "server"
+released = 1979-05-27T07:32:00Z
+"#;
+    let value = from_toml_str(manifest)?;
+    println!("{:#}", value);
+
+    // 写回 TOML：对象成为 [表]，对象数组成为 [[表数组]]
+    print!("{}", to_toml_string(&value)?);
+
+    // TOML 没有 null，默认报错；可以选择跳过或写成空字符串
+    let config = parse_str(r#"{"name": "demo", "license": null, "tags": ["a", null]}"#)?;
+    println!("{:?}", to_toml_string(&config));
+    let options = TomlOptions::new().with_null_policy(NullPolicy::Skip);
+    print!("{}", to_toml_string_with(&config, &options)?);
+
+    // 顶层必须是对象
+    println!("{:?}", to_toml_string(&parse_str("[1, 2]")?));
+    Ok(())
+}
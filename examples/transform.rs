@@ -0,0 +1,54 @@
+use rust_practice_json_parser::path::PathSegment;
+use rust_practice_json_parser::visit::Action;
+use rust_practice_json_parser::{Error, Number, Value, parse_str};
+
+// 演示遍历 API，以及 transform：遍历时原地改写节点，不需要手写递归重建
+fn main() -> Result<(), Error> {
+    let mut value = parse_str(
+        r#"{"price": 12.3456, "name": "  Widget  ", "notes": null,
+            "sizes": [1.005, null, 2.5], "meta": {"owner": null}}"#,
+    )?;
+
+    // 遍历：对象的键值对、数组元素，以及带路径的深度遍历
+    for (key, field) in value.entries() {
+        println!("entry {} = {}", key, field);
+    }
+    println!("sizes: {} items", value["sizes"].items().count());
+    for (path, node) in value.iter_deep() {
+        if !node.is_object() && !node.is_array() {
+            println!("  {} = {}", path, node);
+        }
+    }
+
+    // 按条件查找：第一个匹配的节点，或者全部匹配的节点
+    let sizes = PathSegment::Key("sizes".to_string());
+    let first_null = value.find(|_, node| node.is_null());
+    println!(
+        "first null at {:?}",
+        first_null.map(|(path, _)| path.to_string())
+    );
+    let in_sizes = value.find_all(|path, _| path.segments().first() == Some(&sizes));
+    println!("{} nodes under sizes", in_sizes.len());
+
+    value.transform(|path, node| {
+        match node {
+            // 数字保留两位小数
+            Value::Number(number) if number.is_f64() => {
+                *number = Number::from((number.as_f64() * 100.0).round() / 100.0)
+            }
+            // 去掉字符串两端空白
+            Value::String(string) => *string = string.trim().to_string(),
+            // 删除 null 以及因此变空的对象
+            Value::Null => return Action::Remove,
+            Value::Object(properties) if properties.is_empty() => {
+                println!("dropping empty object at {}", path);
+                return Action::Remove;
+            }
+            _ => {}
+        }
+        Action::Keep
+    });
+
+    println!("{:#}", value);
+    Ok(())
+}
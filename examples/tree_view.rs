@@ -0,0 +1,20 @@
+use rust_practice_json_parser::render::{TreeOptions, to_tree_string, to_tree_string_with};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示在终端里用 tree 命令的样式查看文档
+fn main() -> Result<(), Error> {
+    let value = parse_str(
+        r#"{"name": "John", "age": 30, "tags": ["a", "b"],
+            "address": {"city": "Paris", "geo": {"lat": 48.85, "lng": 2.35}},
+            "bio": "a very long biography that goes on and on", "empty": {}}"#,
+    )?;
+    print!("{}", to_tree_string(&value));
+    println!();
+
+    // 只看两层，过长的值截断
+    let options = TreeOptions::new()
+        .with_max_depth(Some(1))
+        .with_max_value_chars(Some(12));
+    print!("{}", to_tree_string_with(&value, &options));
+    Ok(())
+}
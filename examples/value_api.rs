@@ -0,0 +1,115 @@
+use rust_practice_json_parser::{Error, Map, Value, parse_str};
+use std::collections::HashSet;
+
+// 演示 Value 的访问方法，不再需要为每个字段写完整的 match
+fn main() -> Result<(), Error> {
+    let value = parse_str(
+        r#"{"name": "Alice", "age": 25, "admin": true, "tags": ["a", "b"], "manager": null}"#,
+    )?;
+
+    let Some(properties) = value.as_object() else {
+        println!("not an object");
+        return Ok(());
+    };
+    for (key, field) in properties {
+        if let Some(name) = field.as_str() {
+            println!("{}: string {:?}", key, name);
+        } else if let Some(age) = field.as_i64() {
+            println!("{}: integer {}", key, age);
+        } else if let Some(admin) = field.as_bool() {
+            println!("{}: bool {}", key, admin);
+        } else if let Some(tags) = field.as_array() {
+            println!("{}: array of {} elements", key, tags.len());
+        } else if field.is_null() {
+            println!("{}: null", key);
+        }
+    }
+
+    // get() 返回 Option，可以用 ? 串联起来
+    let mut document = parse_str(r#"{"users": [{"name": "Alice"}, {"name": "Bob"}]}"#)?;
+    println!("users[1].name = {:?}", user_name(&document, 1));
+    println!("users[5].name = {:?}", user_name(&document, 5));
+
+    if let Some(name) = document
+        .get_mut("users")
+        .and_then(|users| users.get_mut(0))
+        .and_then(|user| user.get_mut("name"))
+    {
+        *name = Value::from("Alicia");
+    }
+    println!("users[0].name = {:?}", user_name(&document, 0));
+
+    // 下标访问：不存在的路径返回 Null，可变访问时自动创建对象
+    println!("users[1][\"name\"] = {:?}", document["users"][1]["name"]);
+    println!("missing = {:?}", document["nothing"]["here"][3]);
+    let mut config = Value::Null;
+    config["server"]["host"] = Value::from("localhost");
+    config["server"]["name"] = Value::from("primary");
+    println!("config = {:?}", config);
+
+    // 从 Rust 数据直接构造 Value
+    let scores: Value = vec![90, 85, 77].into();
+    let nickname: Value = None::<&str>.into();
+    let user: Value = [("name", Value::from("Carol")), ("scores", scores)]
+        .into_iter()
+        .chain([("nickname", nickname)])
+        .collect();
+    println!("user = {:?}", user);
+
+    // 反向转换：类型不符或数值越界时返回 Error::Conversion
+    let scores: Vec<u8> = user["scores"].clone().try_into()?;
+    println!("scores = {:?}", scores);
+    match String::try_from(&user["scores"]) {
+        Ok(text) => println!("scores as string = {}", text),
+        Err(e) => println!("scores as string: {}", e),
+    }
+    match i8::try_from(&Value::from(300)) {
+        Ok(number) => println!("300 as i8 = {}", number),
+        Err(e) => println!("300 as i8: {}", e),
+    }
+
+    // 可以直接和 Rust 的值比较
+    assert_eq!(user["name"], "Carol");
+    assert_eq!(user["scores"][0], 90);
+    assert!(user["nickname"] != "Dave");
+    println!("comparisons ok");
+
+    // 忽略对象键顺序，或者把数组当作多重集合比较
+    let expected = parse_str(r#"{"tags": ["b", "a"], "id": 7}"#)?;
+    let actual = parse_str(r#"{"id": 7, "tags": ["a", "b"]}"#)?;
+    println!(
+        "unordered: {}, unordered arrays: {}",
+        actual.deep_eq_unordered(&expected),
+        actual.deep_eq_unordered_arrays(&expected)
+    );
+
+    // Value 实现了 Eq 和 Hash，可以放进 HashSet 去重
+    let records = parse_str(r#"[{"id": 1}, {"id": 2}, {"id": 1}, [0.0], [0]]"#)?;
+    let unique: HashSet<&Value> = records.as_array().into_iter().flatten().collect();
+    println!(
+        "{} records, {} unique",
+        records.as_array().map_or(0, Vec::len),
+        unique.len()
+    );
+
+    // Display 输出真正的 JSON，{:#} 为格式化输出
+    println!("{}", user);
+    println!("{:#}", user);
+
+    // entry API：统计次数时只需要一次查找
+    let mut counts = Map::new();
+    for word in ["a", "b", "a", "c", "a"] {
+        let count = counts.entry(word).or_insert(Value::from(0));
+        *count = Value::from(count.as_f64().unwrap_or(0.0) + 1.0);
+    }
+    let seen = counts.get_or_insert_mut("seen", Value::array());
+    if let Some(seen) = seen.as_array_mut() {
+        seen.push(Value::from(5));
+    }
+    println!("counts = {}", Value::Object(counts));
+    Ok(())
+}
+
+fn user_name(document: &Value, index: usize) -> Option<&str> {
+    document.get("users")?.get(index)?.get("name")?.as_str()
+}
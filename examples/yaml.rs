@@ -0,0 +1,45 @@
+use rust_practice_json_parser::yaml::{from_yaml_str, to_yaml_string};
+use rust_practice_json_parser::{Error, parse_str};
+
+// 演示 YAML 与 JSON 互转：只支持能对应到 JSON 的那部分 YAML
+fn main() -> Result<(), Error> {
+    let config = r#"
+# 服务配置
+name: api-gateway
+port: 8080
+debug: false
+timeout: 2.5
+owner: ~
+hosts:
+  - alpha.internal
+  - beta.internal
+limits: {requests: 100, burst: 20}
+routes:
+- path: /users
+  methods: [GET, POST]
+- path: "/admin: root"
+  auth: true
+banner: |
+  第一行
+  第二行
+"#;
+    let value = from_yaml_str(config)?;
+    println!("{:#}", value);
+
+    // 再写回 YAML；会被误读成其他类型的字符串（如 "yes"、"8080"）会加引号
+    let json = parse_str(
+        r#"{"version": "8080", "answer": "yes", "list": [[1, 2], {"a": null}], "empty": []}"#,
+    )?;
+    print!("{}", to_yaml_string(&json));
+
+    // JSON 本身也是合法的 YAML
+    println!(
+        "{}",
+        from_yaml_str(r#"{"a": [1, 2, {"b": "é"}]}"#)?
+            == parse_str(r#"{"a": [1, 2, {"b": "é"}]}"#)?
+    );
+
+    // 锚点、别名等不支持的写法会报错，并给出行号
+    println!("{:?}", from_yaml_str("base: &b 1\ncopy: *b"));
+    Ok(())
+}
@@ -0,0 +1,109 @@
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#ifndef RUST_PRACTICE_JSON_PARSER_H
+#define RUST_PRACTICE_JSON_PARSER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The kind of a value, as returned by `json_type`.
+typedef enum JsonType {
+  JsonNull,
+  JsonBool,
+  JsonNumber,
+  JsonString,
+  JsonArray,
+  JsonObject,
+} JsonType;
+
+typedef struct JsonValue JsonValue;
+
+#ifdef __cplusplus
+extern "C" {
+#endif  // __cplusplus
+
+// Parses `length` bytes of UTF-8 at `text`. Returns a document to release with
+// `json_free`, or NULL on failure, in which case `*error` (when `error` is not NULL)
+// receives a message with the line and column, to release with `json_string_free`.
+//
+// # Safety
+//
+// `text` must point to `length` readable bytes, and `error` must be NULL or valid for
+// writing a pointer.
+struct JsonValue *json_parse(const char *text, size_t length, char **error);
+
+// Releases a document returned by `json_parse`. NULL is ignored.
+//
+// # Safety
+//
+// `value` must be NULL or a pointer returned by `json_parse` that hasn't been freed;
+// values borrowed from it with `json_pointer` are invalid afterwards.
+void json_free(struct JsonValue *value);
+
+// Looks up a JSON Pointer such as "/users/0/name". Returns a value borrowed from
+// `value`, valid until its document is freed, or NULL when nothing is there.
+//
+// # Safety
+//
+// `value` must be NULL or a live value, and `pointer` NULL or a NUL-terminated string.
+const struct JsonValue *json_pointer(const struct JsonValue *value, const char *pointer);
+
+// The kind of `value`; NULL reports `JsonNull`.
+//
+// # Safety
+//
+// `value` must be NULL or a live value.
+enum JsonType json_type(const struct JsonValue *value);
+
+// The number of elements of an array or members of an object, otherwise 0.
+//
+// # Safety
+//
+// `value` must be NULL or a live value.
+size_t json_length(const struct JsonValue *value);
+
+// Stores a boolean in `*out` and returns true, or returns false for any other value.
+//
+// # Safety
+//
+// `value` must be NULL or a live value, and `out` valid for writing a bool.
+bool json_as_bool(const struct JsonValue *value, bool *out);
+
+// Stores a number in `*out` and returns true, or returns false for any other value.
+//
+// # Safety
+//
+// `value` must be NULL or a live value, and `out` valid for writing a double.
+bool json_as_double(const struct JsonValue *value, double *out);
+
+// A copy of a string value, to release with `json_string_free`, or NULL for any other
+// value.
+//
+// # Safety
+//
+// `value` must be NULL or a live value.
+char *json_as_string(const struct JsonValue *value);
+
+// Serializes `value`, two-space indented when `pretty` is true. Returns a string to
+// release with `json_string_free`, or NULL when `value` is NULL.
+//
+// # Safety
+//
+// `value` must be NULL or a live value.
+char *json_serialize(const struct JsonValue *value, bool pretty);
+
+// Releases a string returned by this library. NULL is ignored.
+//
+// # Safety
+//
+// `s` must be NULL or a string returned by this library that hasn't been freed.
+void json_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_PRACTICE_JSON_PARSER_H */
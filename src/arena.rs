@@ -0,0 +1,250 @@
+// parsing into a bump arena: every node and string of the document is carved out of a
+// few large blocks, and dropping or resetting the arena frees them all at once. For batch
+// jobs that parse and discard many documents, where allocator time dominates.
+// The document is walked the way tape::parse_str_fast does.
+use crate::tape::{StructuralIndex, Walker, is_blank, parse_scalar};
+use crate::{Error, Map, Number, TokenType, Value};
+use bumpalo::Bump;
+
+#[derive(Default)]
+pub struct Arena {
+    bump: Bump,
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // preallocates `bytes`, e.g. a few times the size of a typical input
+    pub fn with_capacity(bytes: usize) -> Self {
+        Arena {
+            bump: Bump::with_capacity(bytes),
+        }
+    }
+
+    // frees every document at once and keeps the largest block for the next ones; the
+    // borrow checker makes sure no document is still in use
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
+
+// Copy, since everything it points to lives in the arena; nothing is ever dropped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'a> {
+    Null,
+    Bool(bool),
+    Number(&'a str), // the validated source text, converted on access
+    String(&'a str),
+    Array(&'a [ArenaValue<'a>]),
+    Object(&'a [(&'a str, ArenaValue<'a>)]), // in source order, duplicate keys included
+}
+
+impl<'a> ArenaValue<'a> {
+    // like Value::get: with duplicate keys the last one wins
+    pub fn get(&self, key: &str) -> Option<&'a ArenaValue<'a>> {
+        match *self {
+            ArenaValue::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn get_index(&self, index: usize) -> Option<&'a ArenaValue<'a>> {
+        match *self {
+            ArenaValue::Array(elements) => elements.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, ArenaValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            ArenaValue::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            ArenaValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<Number> {
+        match *self {
+            ArenaValue::Number(text) => text.parse().ok(), // validated while parsing
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(|n| n.as_f64())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|n| Value::Number(n).as_i64())
+    }
+
+    pub fn as_array(&self) -> Option<&'a [ArenaValue<'a>]> {
+        match *self {
+            ArenaValue::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&'a [(&'a str, ArenaValue<'a>)]> {
+        match *self {
+            ArenaValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    // a heap-allocated copy that outlives the arena
+    pub fn to_value(&self) -> Value {
+        match *self {
+            ArenaValue::Null => Value::Null,
+            ArenaValue::Bool(b) => Value::Bool(b),
+            ArenaValue::Number(_) => self.as_number().map_or(Value::Null, Value::Number),
+            ArenaValue::String(s) => Value::String(s.to_string()),
+            ArenaValue::Array(elements) => elements.iter().map(ArenaValue::to_value).collect(),
+            ArenaValue::Object(members) => {
+                let mut properties = Map::new();
+                for (key, value) in members {
+                    properties.insert(key.to_string(), value.to_value());
+                }
+                Value::Object(properties)
+            }
+        }
+    }
+}
+
+pub fn parse_in<'a>(arena: &'a Arena, input: &str) -> Result<&'a ArenaValue<'a>, Error> {
+    let index = StructuralIndex::build(input)?;
+    let mut builder = Builder {
+        bump: &arena.bump,
+        walker: Walker::new(input, index.positions(), 0, 0),
+        elements: Vec::new(),
+        members: Vec::new(),
+    };
+    let value = builder.parse()?;
+    let walker = &builder.walker;
+    if walker.next < index.positions().len() || !is_blank(walker.gap()) {
+        return Err("Unexpected trailing token".into());
+    }
+    Ok(arena.bump.alloc(value))
+}
+
+struct Builder<'a, 'i, 'p> {
+    bump: &'a Bump,
+    walker: Walker<'i, 'p>,
+    // children of the containers being parsed, innermost last; a container's children
+    // are copied into one exactly sized arena slice when it closes
+    elements: Vec<ArenaValue<'a>>,
+    members: Vec<(&'a str, ArenaValue<'a>)>,
+}
+
+impl<'a> Builder<'a, '_, '_> {
+    fn parse(&mut self) -> Result<ArenaValue<'a>, Error> {
+        let gap = self.walker.gap();
+        let scalar = gap.trim();
+        if !scalar.is_empty() {
+            self.walker.offset += gap.len();
+            return match parse_scalar(scalar)? {
+                Value::Bool(b) => Ok(ArenaValue::Bool(b)),
+                Value::Number(_) => Ok(ArenaValue::Number(self.bump.alloc_str(scalar))),
+                _ => Ok(ArenaValue::Null),
+            };
+        }
+        match self.walker.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => Ok(ArenaValue::String(self.string())),
+            Some(_) => Err("Invalid JSON token".into()),
+            None => Err("Unexpected end of input".into()),
+        }
+    }
+
+    fn string(&mut self) -> &'a str {
+        self.bump.alloc_str(self.walker.string_slice())
+    }
+
+    fn parse_object(&mut self) -> Result<ArenaValue<'a>, Error> {
+        self.walker.bump(); // consume '{'
+        let start = self.members.len();
+        if self.walker.peek() == Some(b'}') {
+            self.walker.bump();
+            return Ok(ArenaValue::Object(&[]));
+        }
+        loop {
+            if self.walker.peek() != Some(b'"') {
+                return Err("Expected string".into());
+            }
+            let key = self.string();
+            if self.walker.peek() != Some(b':') {
+                return Err(
+                    format!("Expected {:?}, found unexpected token", TokenType::Colon).into(),
+                );
+            }
+            self.walker.bump();
+            let value = self.parse()?;
+            self.members.push((key, value));
+
+            match self.walker.peek() {
+                Some(b',') => {
+                    self.walker.bump();
+                    if self.walker.peek() == Some(b'}') {
+                        return Err("Trailing comma in object".into());
+                    }
+                }
+                Some(b'}') => {
+                    self.walker.bump();
+                    let members = self.bump.alloc_slice_copy(&self.members[start..]);
+                    self.members.truncate(start);
+                    return Ok(ArenaValue::Object(members));
+                }
+                _ => return Err("Expected ',' or '}' in object".into()),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<ArenaValue<'a>, Error> {
+        self.walker.bump(); // consume '['
+        let start = self.elements.len();
+        if self.walker.peek() == Some(b']') {
+            self.walker.bump();
+            return Ok(ArenaValue::Array(&[]));
+        }
+        loop {
+            let element = self.parse()?;
+            self.elements.push(element);
+            match self.walker.peek() {
+                Some(b',') => {
+                    self.walker.bump();
+                    if self.walker.peek() == Some(b']') {
+                        return Err("Trailing comma in array".into());
+                    }
+                }
+                Some(b']') => {
+                    self.walker.bump();
+                    let elements = self.bump.alloc_slice_copy(&self.elements[start..]);
+                    self.elements.truncate(start);
+                    return Ok(ArenaValue::Array(elements));
+                }
+                _ => return Err("Expected ',' or ']' in array".into()),
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// records where each element of a top-level array starts and ends, so single
+// elements can be parsed later without touching the ones before them
+use crate::sax;
+use crate::{Error, Value};
+use std::io::{BufRead, ErrorKind, Read, Seek, SeekFrom};
+use std::ops::Range;
+
+pub struct ArrayIndex {
+    spans: Vec<Range<u64>>,
+}
+
+impl ArrayIndex {
+    // for data that is already in memory, e.g. a memory-mapped file
+    pub fn build(bytes: &[u8]) -> Result<Self, Error> {
+        let mut scanner = Scanner::default();
+        scanner.feed(bytes)?;
+        scanner.finish()
+    }
+
+    // scans the input once in constant memory, apart from the offsets themselves
+    pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Self, Error> {
+        let mut scanner = Scanner::default();
+        loop {
+            let chunk = match reader.fill_buf() {
+                Ok([]) => break, // end of input
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            scanner.feed(chunk)?;
+            let consumed = chunk.len();
+            reader.consume(consumed);
+        }
+        scanner.finish()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn span(&self, index: usize) -> Option<Range<u64>> {
+        self.spans.get(index).cloned()
+    }
+
+    pub fn get_element(&self, bytes: &[u8], index: usize) -> Result<Option<Value>, Error> {
+        match self.spans.get(index) {
+            Some(span) => parse_element(&bytes[span.start as usize..span.end as usize]).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_range(&self, bytes: &[u8], range: Range<usize>) -> Result<Vec<Value>, Error> {
+        self.spans
+            .get(range)
+            .ok_or("Element range out of bounds")?
+            .iter()
+            .map(|span| parse_element(&bytes[span.start as usize..span.end as usize]))
+            .collect()
+    }
+
+    pub fn read_element<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        index: usize,
+    ) -> Result<Option<Value>, Error> {
+        let Some(span) = self.spans.get(index) else {
+            return Ok(None);
+        };
+        let mut element = vec![0; (span.end - span.start) as usize];
+        reader
+            .seek(SeekFrom::Start(span.start))
+            .and_then(|_| reader.read_exact(&mut element))?;
+        parse_element(&element).map(Some)
+    }
+}
+
+fn parse_element(bytes: &[u8]) -> Result<Value, Error> {
+    let text = std::str::from_utf8(bytes).map_err(|_| "Invalid UTF-8 in input")?;
+    sax::build(text)
+}
+
+// tracks just enough structure to find element boundaries; element contents
+// are only validated once they're parsed
+#[derive(Default)]
+struct Scanner {
+    offset: u64,
+    depth: usize,
+    in_string: bool,
+    started: bool,
+    finished: bool,
+    element_start: Option<u64>,
+    element_end: u64, // one past the last non-whitespace byte of the current element
+    expect_element: bool,
+    spans: Vec<Range<u64>>,
+}
+
+impl Scanner {
+    fn feed(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        for &byte in chunk {
+            self.feed_byte(byte)?;
+            self.offset += 1;
+        }
+        Ok(())
+    }
+
+    fn feed_byte(&mut self, byte: u8) -> Result<(), Error> {
+        if self.in_string {
+            self.in_string = byte != b'"';
+            self.element_end = self.offset + 1;
+            return Ok(());
+        }
+        if byte.is_ascii_whitespace() {
+            return Ok(());
+        }
+        if self.finished {
+            return Err("Unexpected trailing token".into());
+        }
+        if !self.started {
+            if byte != b'[' {
+                return Err("Top-level value is not an array".into());
+            }
+            self.started = true;
+            self.depth = 1;
+            return Ok(());
+        }
+        if self.depth == 1 {
+            match byte {
+                b',' => return self.end_element(true),
+                b']' => {
+                    if self.expect_element {
+                        return Err("Trailing comma in array".into());
+                    }
+                    self.finished = true;
+                    return self.end_element(false);
+                }
+                _ if self.element_start.is_none() => {
+                    self.element_start = Some(self.offset);
+                    self.expect_element = false;
+                }
+                _ => {}
+            }
+        }
+        match byte {
+            b'"' => self.in_string = true,
+            b'{' | b'[' => self.depth += 1,
+            b'}' | b']' => self.depth -= 1,
+            _ => {}
+        }
+        self.element_end = self.offset + 1;
+        Ok(())
+    }
+
+    fn end_element(&mut self, comma: bool) -> Result<(), Error> {
+        match self.element_start.take() {
+            Some(start) => self.spans.push(start..self.element_end),
+            None if comma => return Err("Invalid JSON token".into()),
+            None => {} // empty array
+        }
+        self.expect_element = comma;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ArrayIndex, Error> {
+        if !self.finished {
+            return Err("Unexpected end of input".into());
+        }
+        Ok(ArrayIndex { spans: self.spans })
+    }
+}
@@ -0,0 +1,374 @@
+use crate::json_seq::{self, RECORD_SEPARATOR};
+use crate::ndjson;
+use crate::sax::{Event, TreeBuilder};
+use crate::serializer::{self, SerializeOptions};
+use crate::streaming::StreamingParser;
+use crate::{Error, Value};
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+pub async fn parse_async_reader<R: AsyncBufRead + Unpin>(mut reader: R) -> Result<Value, Error> {
+    let mut parser = StreamingParser::new(TreeBuilder::new());
+    loop {
+        let chunk = match reader.fill_buf().await {
+            Ok([]) => break, // end of input
+            Ok(chunk) => chunk,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        parser.feed(chunk)?;
+        let consumed = chunk.len();
+        reader.consume(consumed);
+    }
+    parser
+        .finish()?
+        .into_value()
+        .ok_or_else(|| "Unexpected end of input".into())
+}
+
+pub struct AsyncEventReader<R> {
+    reader: R,
+    parser: Option<StreamingParser<VecDeque<Event>>>, // None once the input is exhausted
+    events: VecDeque<Event>,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncEventReader<R> {
+    pub fn new(reader: R) -> Self {
+        AsyncEventReader {
+            reader,
+            parser: Some(StreamingParser::new(VecDeque::new())),
+            events: VecDeque::new(),
+        }
+    }
+
+    pub async fn next_event(&mut self) -> Result<Option<Event>, Error> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+            let Some(parser) = self.parser.as_mut() else {
+                return Ok(None);
+            };
+            let chunk = match self.reader.fill_buf().await {
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if chunk.is_empty() {
+                // flush whatever the final token produced
+                let parser = self.parser.take().unwrap();
+                self.events.extend(parser.finish()?);
+                continue;
+            }
+            parser.feed(chunk)?;
+            let consumed = chunk.len();
+            self.reader.consume(consumed);
+            self.events.extend(parser.handler_mut().drain(..));
+        }
+    }
+}
+
+// compact, like `serializer::to_string`; the writer is flushed before returning
+pub async fn to_async_writer<W: AsyncWrite + Unpin>(writer: W, value: &Value) -> Result<(), Error> {
+    to_async_writer_with(writer, value, &SerializeOptions::default()).await
+}
+
+pub async fn to_async_writer_with<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    value: &Value,
+    options: &SerializeOptions,
+) -> Result<(), Error> {
+    let text = serializer::to_string_with(value, options);
+    writer.write_all(text.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+// appends everything up to and including `delimiter` to `buffer`; stops early at the end
+// of input, which leaves `buffer` empty if nothing was left. A Pending keeps what was
+// read so far in `buffer`, so the next poll carries on where this one stopped
+fn poll_read_until<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    cx: &mut Context<'_>,
+    delimiter: u8,
+    buffer: &mut Vec<u8>,
+) -> Poll<io::Result<()>> {
+    loop {
+        let chunk = match ready!(Pin::new(&mut *reader).poll_fill_buf(cx)) {
+            Ok(chunk) => chunk,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Poll::Ready(Err(e)),
+        };
+        if chunk.is_empty() {
+            return Poll::Ready(Ok(())); // end of input
+        }
+        let (found, used) = match chunk.iter().position(|&b| b == delimiter) {
+            Some(i) => (true, i + 1),
+            None => (false, chunk.len()),
+        };
+        buffer.extend_from_slice(&chunk[..used]);
+        Pin::new(&mut *reader).consume(used);
+        if found {
+            return Poll::Ready(Ok(()));
+        }
+    }
+}
+
+// the async counterpart of `ndjson::Reader`: one value per line, and a malformed line
+// doesn't end the stream
+pub struct NdjsonStream<R> {
+    reader: R,
+    line_number: usize,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> NdjsonStream<R> {
+    pub fn new(reader: R) -> Self {
+        NdjsonStream {
+            reader,
+            line_number: 0,
+            buffer: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for NdjsonStream<R> {
+    type Item = Result<Value, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while !this.done {
+            let read = ready!(poll_read_until(
+                &mut this.reader,
+                cx,
+                b'\n',
+                &mut this.buffer
+            ));
+            if let Err(e) = read {
+                this.done = true; // the underlying reader can't be trusted anymore
+                let error = Error::Line(this.line_number + 1, Box::new(e.into()));
+                return Poll::Ready(Some(Err(error)));
+            }
+            if this.buffer.is_empty() {
+                this.done = true;
+                break;
+            }
+            this.line_number += 1;
+            let result = ndjson::parse_line(&this.buffer);
+            this.buffer.clear();
+            if let Some(result) = result {
+                let line_number = this.line_number;
+                return Poll::Ready(Some(
+                    result.map_err(|e| Error::Line(line_number, Box::new(e))),
+                ));
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+// the async counterpart of `json_seq::Reader`
+pub struct JsonSeqStream<R> {
+    reader: R,
+    record_number: usize,
+    buffer: Vec<u8>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> JsonSeqStream<R> {
+    pub fn new(reader: R) -> Self {
+        JsonSeqStream {
+            reader,
+            record_number: 0,
+            buffer: Vec::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    pub fn record_number(&self) -> usize {
+        self.record_number
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for JsonSeqStream<R> {
+    type Item = Result<Value, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while !this.done {
+            let read = ready!(poll_read_until(
+                &mut this.reader,
+                cx,
+                RECORD_SEPARATOR,
+                &mut this.buffer
+            ));
+            if let Err(e) = read {
+                this.done = true;
+                return Poll::Ready(Some(Err(e.into())));
+            }
+            if this.buffer.last() == Some(&RECORD_SEPARATOR) {
+                this.buffer.pop();
+            } else {
+                this.done = true; // no separator left, this is the last record
+            }
+            let leading = !std::mem::replace(&mut this.started, true);
+            if this.buffer.iter().all(u8::is_ascii_whitespace) {
+                this.buffer.clear();
+                continue; // empty records and the gap before the first RS are skipped
+            }
+            this.record_number += 1;
+            let result = if leading {
+                Err("Missing record separator".into())
+            } else {
+                json_seq::parse_record(&this.buffer)
+            };
+            this.buffer.clear();
+            let record_number = this.record_number;
+            return Poll::Ready(Some(
+                result.map_err(|e| Error::Record(record_number, Box::new(e))),
+            ));
+        }
+        Poll::Ready(None)
+    }
+}
+
+// one framed value at a time: poll_ready writes out the previous value before the next
+// one is accepted
+struct Framed<W> {
+    writer: W,
+    pending: Vec<u8>,
+    written: usize, // how much of `pending` the writer has taken
+}
+
+impl<W: AsyncWrite + Unpin> Framed<W> {
+    fn new(writer: W) -> Self {
+        Framed {
+            writer,
+            pending: Vec::new(),
+            written: 0,
+        }
+    }
+
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        while self.written < self.pending.len() {
+            let n =
+                ready!(Pin::new(&mut self.writer).poll_write(cx, &self.pending[self.written..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::Error::from(ErrorKind::WriteZero).into()));
+            }
+            self.written += n;
+        }
+        self.pending.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        ready!(self.poll_write_pending(cx))?;
+        Pin::new(&mut self.writer)
+            .poll_flush(cx)
+            .map_err(Error::from)
+    }
+
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        ready!(self.poll_write_pending(cx))?;
+        Pin::new(&mut self.writer)
+            .poll_shutdown(cx)
+            .map_err(Error::from)
+    }
+}
+
+// writes each value compactly on its own line; strings never contain a raw newline
+// because the serializer escapes control characters
+pub struct NdjsonSink<W> {
+    framed: Framed<W>,
+}
+
+impl<W: AsyncWrite + Unpin> NdjsonSink<W> {
+    pub fn new(writer: W) -> Self {
+        NdjsonSink {
+            framed: Framed::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.framed.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<Value> for NdjsonSink<W> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().framed.poll_write_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: Value) -> Result<(), Error> {
+        let pending = &mut self.get_mut().framed.pending;
+        serializer::to_vec_into(&value, pending);
+        pending.push(b'\n');
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().framed.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().framed.poll_close(cx)
+    }
+}
+
+// the async counterpart of `json_seq::Writer::write_value`
+pub struct JsonSeqSink<W> {
+    framed: Framed<W>,
+}
+
+impl<W: AsyncWrite + Unpin> JsonSeqSink<W> {
+    pub fn new(writer: W) -> Self {
+        JsonSeqSink {
+            framed: Framed::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.framed.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<Value> for JsonSeqSink<W> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().framed.poll_write_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: Value) -> Result<(), Error> {
+        let pending = &mut self.get_mut().framed.pending;
+        pending.push(RECORD_SEPARATOR);
+        serializer::to_vec_into(&value, pending);
+        pending.push(b'\n');
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().framed.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().framed.poll_close(cx)
+    }
+}
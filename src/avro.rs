@@ -0,0 +1,768 @@
+// Avro binary encoding of Values, one datum at a time as a message bus carries them; the
+// schema travels separately (a registry, a file), so both directions take an AvroSchema.
+// Bytes and fixed values are strings of code points 0-255, as in Avro's own JSON
+// encoding, and logical types are read as their underlying type.
+use crate::path::{JsonPath, PathSegment};
+use crate::schema::InferredSchema;
+use crate::{Error, Map, Number, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+// nested deeper than this, a datum is assumed to come from a schema that never ends
+const MAX_DEPTH: usize = 512;
+
+#[derive(Debug, Clone)]
+pub struct AvroSchema {
+    nodes: Vec<Node>, // named types are referred to by index, so recursion is just a cycle
+    root: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Array(usize),
+    Map(usize),
+    Record { name: String, fields: Vec<Field> },
+    Enum { name: String, symbols: Vec<String> },
+    Fixed { name: String, size: usize },
+    Union(Vec<usize>),
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    node: usize,
+    default: Option<Value>, // written when the member is missing
+}
+
+impl AvroSchema {
+    pub fn compile(schema: &Value) -> Result<AvroSchema, Error> {
+        let mut compiler = Compiler {
+            nodes: Vec::new(),
+            names: HashMap::new(),
+        };
+        let root = compiler.compile(schema, "")?;
+        Ok(AvroSchema {
+            nodes: compiler.nodes,
+            root,
+        })
+    }
+
+    // a schema every sample fits, built from schema::infer: objects become records (maps
+    // when their keys aren't valid Avro names), members missing from some samples become
+    // nullable with a null default, and mixed types become unions. Nested records are
+    // named after the path to them, e.g. "User_address".
+    pub fn infer(name: &str, samples: &[Value]) -> AvroSchema {
+        let mut schema = AvroSchema {
+            nodes: Vec::new(),
+            root: 0,
+        };
+        schema.root = schema.inferred(&crate::schema::infer(samples), name);
+        schema
+    }
+
+    // the schema as JSON, with every named type spelled out where it first appears
+    pub fn to_value(&self) -> Value {
+        self.node_value(self.root, &mut HashSet::new())
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn inferred(&mut self, schema: &InferredSchema, name: &str) -> usize {
+        let mut branches = Vec::new();
+        if schema.is_nullable() {
+            branches.push(self.push(Node::Null)); // first, so a null default is allowed
+        }
+        for kind in schema.types() {
+            let node = match kind {
+                "object" => self.inferred_object(schema, name),
+                "array" => {
+                    let items =
+                        self.inferred(schema.items().unwrap_or(&InferredSchema::new()), name);
+                    self.push(Node::Array(items))
+                }
+                "string" => self.push(Node::String),
+                "number" => self.push(Node::Double),
+                "integer" => self.push(Node::Long),
+                "boolean" => self.push(Node::Boolean),
+                _ => continue, // null, already first
+            };
+            branches.push(node);
+        }
+        match branches.as_slice() {
+            [] => self.push(Node::Null),
+            [single] => *single,
+            _ => self.push(Node::Union(branches)),
+        }
+    }
+
+    fn inferred_object(&mut self, schema: &InferredSchema, name: &str) -> usize {
+        if !schema.properties().all(|(key, _)| is_name(key)) {
+            let mut values = InferredSchema::new();
+            for (_, property) in schema.properties() {
+                values.merge(property);
+            }
+            let values = self.inferred(&values, name);
+            return self.push(Node::Map(values));
+        }
+        let mut fields = Vec::new();
+        for (key, property) in schema.properties() {
+            let mut node = self.inferred(property, &format!("{}_{}", name, key));
+            let required = schema.is_required(key);
+            if !required && !property.is_nullable() {
+                node = match &self.nodes[node] {
+                    Node::Null => node,
+                    Node::Union(branches) => {
+                        let mut branches = branches.clone();
+                        branches.insert(0, self.push(Node::Null));
+                        self.push(Node::Union(branches))
+                    }
+                    _ => {
+                        let null = self.push(Node::Null);
+                        self.push(Node::Union(vec![null, node]))
+                    }
+                };
+            }
+            fields.push(Field {
+                name: key.to_string(),
+                node,
+                default: (!required).then_some(Value::Null),
+            });
+        }
+        self.push(Node::Record {
+            name: name.to_string(),
+            fields,
+        })
+    }
+
+    fn node_value(&self, index: usize, defined: &mut HashSet<usize>) -> Value {
+        let mut schema = Value::object();
+        match &self.nodes[index] {
+            Node::Null => return "null".into(),
+            Node::Boolean => return "boolean".into(),
+            Node::Int => return "int".into(),
+            Node::Long => return "long".into(),
+            Node::Float => return "float".into(),
+            Node::Double => return "double".into(),
+            Node::Bytes => return "bytes".into(),
+            Node::String => return "string".into(),
+            Node::Array(items) => {
+                schema["type"] = "array".into();
+                schema["items"] = self.node_value(*items, defined);
+            }
+            Node::Map(values) => {
+                schema["type"] = "map".into();
+                schema["values"] = self.node_value(*values, defined);
+            }
+            Node::Union(branches) => {
+                let branches = branches.iter().map(|&b| self.node_value(b, defined));
+                return Value::Array(branches.collect());
+            }
+            Node::Record { name, .. } | Node::Enum { name, .. } | Node::Fixed { name, .. }
+                if !defined.insert(index) =>
+            {
+                return name.as_str().into();
+            }
+            Node::Record { name, fields } => {
+                schema["type"] = "record".into();
+                schema["name"] = name.as_str().into();
+                let fields = fields.iter().map(|field| {
+                    let mut value = Value::object();
+                    value["name"] = field.name.as_str().into();
+                    value["type"] = self.node_value(field.node, defined);
+                    if let Some(default) = &field.default {
+                        value["default"] = default.clone();
+                    }
+                    value
+                });
+                schema["fields"] = Value::Array(fields.collect());
+            }
+            Node::Enum { name, symbols } => {
+                schema["type"] = "enum".into();
+                schema["name"] = name.as_str().into();
+                let symbols = symbols.iter().map(|s| Value::from(s.as_str()));
+                schema["symbols"] = Value::Array(symbols.collect());
+            }
+            Node::Fixed { name, size } => {
+                schema["type"] = "fixed".into();
+                schema["name"] = name.as_str().into();
+                schema["size"] = Value::Number(Number::U64(*size as u64));
+            }
+        }
+        schema
+    }
+
+    fn kind(&self, index: usize) -> String {
+        match &self.nodes[index] {
+            Node::Null => "null".into(),
+            Node::Boolean => "boolean".into(),
+            Node::Int => "int".into(),
+            Node::Long => "long".into(),
+            Node::Float => "float".into(),
+            Node::Double => "double".into(),
+            Node::Bytes => "bytes".into(),
+            Node::String => "string".into(),
+            Node::Array(_) => "array".into(),
+            Node::Map(_) => "map".into(),
+            Node::Record { name, .. } => format!("record {}", name),
+            Node::Enum { name, .. } => format!("enum {}", name),
+            Node::Fixed { name, .. } => format!("fixed {}", name),
+            Node::Union(_) => "union".into(),
+        }
+    }
+
+    // whether a union branch can hold the value; the first one that can is written
+    fn matches(&self, index: usize, value: &Value) -> bool {
+        match (&self.nodes[index], value) {
+            (_, Value::Raw(raw)) => self.matches(index, &raw.parse().unwrap_or_default()),
+            (Node::Null, Value::Null)
+            | (Node::Boolean, Value::Bool(_))
+            | (Node::Float | Node::Double, Value::Number(_))
+            | (Node::String, Value::String(_))
+            | (Node::Array(_), Value::Array(_))
+            | (Node::Map(_), Value::Object(_)) => true,
+            (Node::Int, Value::Number(n)) => n.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+            (Node::Long, Value::Number(n)) => n.as_i64().is_some(),
+            (Node::Bytes, Value::String(s)) => is_latin1(s),
+            (Node::Fixed { size, .. }, Value::String(s)) => {
+                is_latin1(s) && s.chars().count() == *size
+            }
+            (Node::Enum { symbols, .. }, Value::String(s)) => symbols.contains(s),
+            (Node::Record { fields, .. }, Value::Object(properties)) => {
+                properties
+                    .keys()
+                    .all(|key| fields.iter().any(|field| field.name == *key))
+                    && fields.iter().all(|field| {
+                        field.default.is_some() || properties.contains_key(&field.name)
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for AvroSchema {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        AvroSchema::compile(&crate::parse_str(text)?)
+    }
+}
+
+impl fmt::Display for AvroSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_value(), f)
+    }
+}
+
+// fails with Error::Conversion naming where the value doesn't fit the schema
+pub fn to_avro(value: &Value, schema: &AvroSchema) -> Result<Vec<u8>, Error> {
+    let mut encoder = Encoder {
+        schema,
+        out: Vec::new(),
+        path: JsonPath::root(),
+    };
+    encoder.encode(schema.root, value)?;
+    Ok(encoder.out)
+}
+
+// records come back as objects in field order, maps as objects, and a union as whichever
+// branch was written, without Avro JSON's {"type": value} wrapper
+pub fn from_avro(bytes: &[u8], schema: &AvroSchema) -> Result<Value, Error> {
+    let mut decoder = Decoder {
+        bytes,
+        position: 0,
+        schema,
+        depth: 0,
+    };
+    let value = decoder.value(schema.root)?;
+    if decoder.position < bytes.len() {
+        return Err(decoder.error("trailing bytes after the value"));
+    }
+    Ok(value)
+}
+
+// [A-Za-z_][A-Za-z0-9_]*
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_latin1(s: &str) -> bool {
+    s.chars().all(|c| c as u32 <= 0xff)
+}
+
+struct Compiler {
+    nodes: Vec<Node>,
+    names: HashMap<String, usize>, // full names of the named types defined so far
+}
+
+impl Compiler {
+    fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn compile(&mut self, schema: &Value, namespace: &str) -> Result<usize, Error> {
+        let attributes = match schema {
+            Value::String(name) => return self.named(name, namespace),
+            Value::Array(branches) => {
+                let mut indices = Vec::with_capacity(branches.len());
+                for branch in branches {
+                    if branch.is_array() {
+                        return Err(invalid("a union can't directly contain another union"));
+                    }
+                    indices.push(self.compile(branch, namespace)?);
+                }
+                if indices.is_empty() {
+                    return Err(invalid("a union needs at least one branch"));
+                }
+                return Ok(self.push(Node::Union(indices)));
+            }
+            Value::Object(attributes) => attributes,
+            Value::Raw(raw) => return self.compile(&raw.parse()?, namespace),
+            other => {
+                return Err(invalid(&format!(
+                    "expected a type name, a union or an object, not {}",
+                    other.type_name()
+                )));
+            }
+        };
+        let kind = match attributes.get("type") {
+            Some(Value::String(kind)) => kind.as_str(),
+            Some(other) => return self.compile(other, namespace),
+            None => return Err(invalid("a schema object needs a type")),
+        };
+        match kind {
+            "record" | "error" => {
+                let (name, namespace) = self.define(attributes, namespace)?;
+                // defined before its fields, which may refer back to it
+                let index = self.push(Node::Null);
+                self.names.insert(name.clone(), index);
+                let Some(Value::Array(list)) = attributes.get("fields") else {
+                    return Err(invalid(&format!("record {} needs a fields array", name)));
+                };
+                let mut fields = Vec::with_capacity(list.len());
+                for field in list {
+                    let (Some(field_name), Some(schema)) = (
+                        field.as_object().and_then(|f| f.get("name")?.as_str()),
+                        field.as_object().and_then(|f| f.get("type")),
+                    ) else {
+                        let message = format!("every field of {} needs a name and a type", name);
+                        return Err(invalid(&message));
+                    };
+                    fields.push(Field {
+                        name: field_name.to_string(),
+                        node: self.compile(schema, &namespace)?,
+                        default: field.as_object().and_then(|f| f.get("default")).cloned(),
+                    });
+                }
+                self.nodes[index] = Node::Record { name, fields };
+                Ok(index)
+            }
+            "enum" => {
+                let (name, _) = self.define(attributes, namespace)?;
+                let symbols = match attributes.get("symbols") {
+                    Some(Value::Array(symbols)) if symbols.iter().all(Value::is_string) => symbols
+                        .iter()
+                        .filter_map(|s| s.as_str().map(str::to_string))
+                        .collect(),
+                    _ => return Err(invalid(&format!("enum {} needs a symbols array", name))),
+                };
+                let index = self.push(Node::Enum {
+                    name: name.clone(),
+                    symbols,
+                });
+                self.names.insert(name, index);
+                Ok(index)
+            }
+            "fixed" => {
+                let (name, _) = self.define(attributes, namespace)?;
+                let Some(size) = attributes.get("size").and_then(Value::as_u64) else {
+                    return Err(invalid(&format!("fixed {} needs a size", name)));
+                };
+                let index = self.push(Node::Fixed {
+                    name: name.clone(),
+                    size: size as usize,
+                });
+                self.names.insert(name, index);
+                Ok(index)
+            }
+            "array" => {
+                let Some(items) = attributes.get("items") else {
+                    return Err(invalid("an array schema needs items"));
+                };
+                let items = self.compile(items, namespace)?;
+                Ok(self.push(Node::Array(items)))
+            }
+            "map" => {
+                let Some(values) = attributes.get("values") else {
+                    return Err(invalid("a map schema needs values"));
+                };
+                let values = self.compile(values, namespace)?;
+                Ok(self.push(Node::Map(values)))
+            }
+            // a primitive with extra attributes, such as a logicalType
+            name => self.named(name, namespace),
+        }
+    }
+
+    // a primitive, or a reference to a named type defined earlier
+    fn named(&mut self, name: &str, namespace: &str) -> Result<usize, Error> {
+        let node = match name {
+            "null" => Node::Null,
+            "boolean" => Node::Boolean,
+            "int" => Node::Int,
+            "long" => Node::Long,
+            "float" => Node::Float,
+            "double" => Node::Double,
+            "bytes" => Node::Bytes,
+            "string" => Node::String,
+            _ => {
+                let qualified = format!("{}.{}", namespace, name);
+                let found = match name.contains('.') || namespace.is_empty() {
+                    true => self.names.get(name),
+                    false => self.names.get(&qualified).or(self.names.get(name)),
+                };
+                return found
+                    .copied()
+                    .ok_or_else(|| invalid(&format!("unknown type {}", name)));
+            }
+        };
+        Ok(self.push(node))
+    }
+
+    // the full name of a named type and the namespace its fields are in
+    fn define(&self, attributes: &Map, namespace: &str) -> Result<(String, String), Error> {
+        let Some(name) = attributes.get("name").and_then(Value::as_str) else {
+            return Err(invalid("records, enums and fixed types need a name"));
+        };
+        let (name, namespace) = match name.rsplit_once('.') {
+            Some((namespace, _)) => (name.to_string(), namespace.to_string()),
+            None => {
+                let namespace = attributes
+                    .get("namespace")
+                    .and_then(Value::as_str)
+                    .unwrap_or(namespace);
+                match namespace.is_empty() {
+                    true => (name.to_string(), String::new()),
+                    false => (format!("{}.{}", namespace, name), namespace.to_string()),
+                }
+            }
+        };
+        if self.names.contains_key(&name) {
+            return Err(invalid(&format!("{} is defined twice", name)));
+        }
+        Ok((name, namespace))
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    format!("Invalid Avro schema: {}", message).into()
+}
+
+// zigzag, then a little-endian base 128 varint
+fn write_long(n: i64, out: &mut Vec<u8>) {
+    let mut n = ((n << 1) ^ (n >> 63)) as u64;
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+struct Encoder<'s> {
+    schema: &'s AvroSchema,
+    out: Vec<u8>,
+    path: JsonPath, // to say where a value doesn't fit
+}
+
+impl Encoder<'_> {
+    fn error(&self, message: &str) -> Error {
+        match self.path.segments().is_empty() {
+            true => Error::Conversion(format!("$: {}", message)),
+            false => Error::Conversion(format!("{}: {}", self.path, message)),
+        }
+    }
+
+    fn mismatch(&self, index: usize, value: &Value) -> Error {
+        let expected = self.schema.kind(index);
+        self.error(&format!("expected {}, not {}", expected, value.type_name()))
+    }
+
+    fn encode(&mut self, index: usize, value: &Value) -> Result<(), Error> {
+        let schema = self.schema;
+        match (&schema.nodes[index], value) {
+            (_, Value::Raw(raw)) => return self.encode(index, &raw.parse()?),
+            (Node::Null, Value::Null) => {}
+            (Node::Boolean, Value::Bool(b)) => self.out.push(*b as u8),
+            (Node::Int, Value::Number(n)) => match n.as_i64().filter(|n| i32::try_from(*n).is_ok())
+            {
+                Some(n) => write_long(n, &mut self.out),
+                None => return Err(self.error(&format!("{} doesn't fit an int", n))),
+            },
+            (Node::Long, Value::Number(n)) => match n.as_i64() {
+                Some(n) => write_long(n, &mut self.out),
+                None => return Err(self.error(&format!("{} doesn't fit a long", n))),
+            },
+            (Node::Float, Value::Number(n)) => {
+                let n = n.as_f64() as f32;
+                self.out.extend_from_slice(&n.to_le_bytes());
+            }
+            (Node::Double, Value::Number(n)) => {
+                self.out.extend_from_slice(&n.as_f64().to_le_bytes())
+            }
+            (Node::String, Value::String(s)) => {
+                write_long(s.len() as i64, &mut self.out);
+                self.out.extend_from_slice(s.as_bytes());
+            }
+            (Node::Bytes, Value::String(s)) if is_latin1(s) => {
+                write_long(s.chars().count() as i64, &mut self.out);
+                self.out.extend(s.chars().map(|c| c as u8));
+            }
+            (Node::Fixed { size, .. }, Value::String(s)) if is_latin1(s) => {
+                if s.chars().count() != *size {
+                    return Err(self.error(&format!("expected {} bytes", size)));
+                }
+                self.out.extend(s.chars().map(|c| c as u8));
+            }
+            (Node::Bytes | Node::Fixed { .. }, Value::String(_)) => {
+                return Err(self.error("bytes must be characters U+0000 to U+00FF"));
+            }
+            (Node::Enum { name, symbols }, Value::String(s)) => {
+                match symbols.iter().position(|symbol| symbol == s) {
+                    Some(position) => write_long(position as i64, &mut self.out),
+                    None => return Err(self.error(&format!("{} is not a symbol of {}", s, name))),
+                }
+            }
+            // everything in one block, then the empty block that ends it
+            (Node::Array(items), Value::Array(elements)) => {
+                if !elements.is_empty() {
+                    write_long(elements.len() as i64, &mut self.out);
+                    for (i, element) in elements.iter().enumerate() {
+                        self.path.push(PathSegment::from(i));
+                        self.encode(*items, element)?;
+                        self.path.pop();
+                    }
+                }
+                self.out.push(0);
+            }
+            (Node::Map(values), Value::Object(properties)) => {
+                if !properties.is_empty() {
+                    write_long(properties.len() as i64, &mut self.out);
+                    for (key, value) in properties {
+                        write_long(key.len() as i64, &mut self.out);
+                        self.out.extend_from_slice(key.as_bytes());
+                        self.path.push(PathSegment::from(key.as_str()));
+                        self.encode(*values, value)?;
+                        self.path.pop();
+                    }
+                }
+                self.out.push(0);
+            }
+            (Node::Record { name, fields }, Value::Object(properties)) => {
+                if let Some(key) = properties
+                    .keys()
+                    .find(|key| !fields.iter().any(|field| field.name == **key))
+                {
+                    return Err(self.error(&format!("{} has no field {}", name, key)));
+                }
+                for field in fields {
+                    self.path.push(PathSegment::from(field.name.as_str()));
+                    match (properties.get(&field.name), &field.default) {
+                        (Some(value), _) | (None, Some(value)) => self.encode(field.node, value)?,
+                        (None, None) => {
+                            return Err(self.error("missing, and the field has no default"));
+                        }
+                    }
+                    self.path.pop();
+                }
+            }
+            (Node::Union(branches), value) => {
+                let Some(position) = branches.iter().position(|&b| schema.matches(b, value)) else {
+                    let message = format!("no branch of the union holds {}", value.type_name());
+                    return Err(self.error(&message));
+                };
+                write_long(position as i64, &mut self.out);
+                self.encode(branches[position], value)?;
+            }
+            (_, value) => return Err(self.mismatch(index, value)),
+        }
+        Ok(())
+    }
+}
+
+struct Decoder<'a, 's> {
+    bytes: &'a [u8],
+    position: usize,
+    schema: &'s AvroSchema,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a, '_> {
+    fn error(&self, message: &str) -> Error {
+        format!("Invalid Avro at byte {}: {}", self.position, message).into()
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn long(&mut self) -> Result<i64, Error> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            if shift == 63 && byte > 1 {
+                break;
+            }
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((n >> 1) as i64 ^ -((n & 1) as i64));
+            }
+        }
+        Err(self.error("varint longer than a long"))
+    }
+
+    // a length, no larger than what the rest of the input could possibly hold
+    fn length(&mut self) -> Result<usize, Error> {
+        let length = self.long()?;
+        usize::try_from(length)
+            .ok()
+            .filter(|&n| n <= self.bytes.len() - self.position)
+            .ok_or_else(|| self.error("length is negative or runs past the end of input"))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let length = self.length()?;
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("invalid UTF-8"))
+    }
+
+    // the item count of the next block, 0 once they're done; a negative count is
+    // followed by the block's size in bytes, which isn't needed here
+    fn block(&mut self) -> Result<u64, Error> {
+        let count = self.long()?;
+        if count < 0 {
+            self.long()?;
+        }
+        Ok(count.unsigned_abs())
+    }
+
+    fn value(&mut self, index: usize) -> Result<Value, Error> {
+        let schema = self.schema;
+        let number = |number| Ok(Value::Number(number));
+        match &schema.nodes[index] {
+            Node::Null => Ok(Value::Null),
+            Node::Boolean => match self.take(1)?[0] {
+                0 => Ok(Value::Bool(false)),
+                1 => Ok(Value::Bool(true)),
+                _ => Err(self.error("a boolean must be 0 or 1")),
+            },
+            Node::Int => match i32::try_from(self.long()?) {
+                Ok(n) => number(Number::I64(n as i64)),
+                Err(_) => Err(self.error("int out of range")),
+            },
+            Node::Long => number(Number::I64(self.long()?)),
+            Node::Float => {
+                let n = f32::from_le_bytes(self.take(4)?.try_into().unwrap());
+                number(Number::F64(n as f64))
+            }
+            Node::Double => number(Number::F64(f64::from_le_bytes(
+                self.take(8)?.try_into().unwrap(),
+            ))),
+            Node::Bytes => {
+                let length = self.length()?;
+                Ok(Value::String(
+                    self.take(length)?.iter().map(|&b| b as char).collect(),
+                ))
+            }
+            Node::Fixed { size, .. } => Ok(Value::String(
+                self.take(*size)?.iter().map(|&b| b as char).collect(),
+            )),
+            Node::String => Ok(Value::String(self.string()?)),
+            Node::Enum { symbols, .. } => {
+                let position = self.long()?;
+                match usize::try_from(position).ok().and_then(|p| symbols.get(p)) {
+                    Some(symbol) => Ok(Value::String(symbol.clone())),
+                    None => Err(self.error("enum index out of range")),
+                }
+            }
+            Node::Array(items) => {
+                self.descend()?;
+                let mut elements = Vec::new();
+                loop {
+                    let count = self.block()?;
+                    if count == 0 {
+                        break;
+                    }
+                    for _ in 0..count {
+                        elements.push(self.value(*items)?);
+                    }
+                }
+                self.depth -= 1;
+                Ok(Value::Array(elements))
+            }
+            Node::Map(values) => {
+                self.descend()?;
+                let mut properties = Map::new();
+                loop {
+                    let count = self.block()?;
+                    if count == 0 {
+                        break;
+                    }
+                    for _ in 0..count {
+                        let key = self.string()?;
+                        properties.insert(key, self.value(*values)?);
+                    }
+                }
+                self.depth -= 1;
+                Ok(Value::Object(properties))
+            }
+            Node::Record { fields, .. } => {
+                self.descend()?;
+                let mut properties = Map::new();
+                for field in fields {
+                    properties.insert(field.name.clone(), self.value(field.node)?);
+                }
+                self.depth -= 1;
+                Ok(Value::Object(properties))
+            }
+            Node::Union(branches) => {
+                let position = self.long()?;
+                match usize::try_from(position).ok().and_then(|p| branches.get(p)) {
+                    Some(&branch) => self.value(branch),
+                    None => Err(self.error("union index out of range")),
+                }
+            }
+        }
+    }
+
+    fn descend(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        match self.depth <= MAX_DEPTH {
+            true => Ok(()),
+            false => Err(self.error("nested too deeply")),
+        }
+    }
+}
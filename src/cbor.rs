@@ -0,0 +1,288 @@
+// CBOR (RFC 8949) encoding of Value, for binary caches where JSON text is wasteful.
+// Integers use major types 0 and 1, strings 3, arrays 4, objects 5, and the rest major
+// type 7. Floats are written as f32 when that is exact, as f64 otherwise.
+use crate::{Error, Map, Number, Value};
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+const FALSE: u8 = 20;
+const TRUE: u8 = 21;
+const NULL: u8 = 22;
+const UNDEFINED: u8 = 23;
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xff;
+
+pub fn to_cbor(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode(value, &mut out);
+    out
+}
+
+// tags are skipped and their content decoded as usual; undefined becomes null. Byte
+// strings, non-string keys and other simple values have no JSON equivalent and fail.
+pub fn from_cbor(bytes: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder { bytes, position: 0 };
+    let value = decoder.value()?;
+    if decoder.position < bytes.len() {
+        return Err(decoder.error("trailing bytes after the value"));
+    }
+    Ok(value)
+}
+
+fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(SIMPLE << 5 | NULL),
+        Value::Bool(b) => out.push(SIMPLE << 5 | if *b { TRUE } else { FALSE }),
+        Value::Number(number) => encode_number(number, out),
+        Value::String(s) => {
+            head(TEXT, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(elements) => {
+            head(ARRAY, elements.len() as u64, out);
+            for element in elements {
+                encode(element, out);
+            }
+        }
+        Value::Object(properties) => {
+            head(MAP, properties.len() as u64, out);
+            for (key, value) in properties {
+                head(TEXT, key.len() as u64, out);
+                out.extend_from_slice(key.as_bytes());
+                encode(value, out);
+            }
+        }
+        // validated when it was made, so parsing can't fail
+        Value::Raw(raw) => encode(&raw.parse().unwrap_or_default(), out),
+    }
+}
+
+// numbers that don't fit i64, u64 or f64 exactly go through f64, as in ser
+fn encode_number(number: &Number, out: &mut Vec<u8>) {
+    match number {
+        Number::U64(n) => head(UNSIGNED, *n, out),
+        Number::I64(n) if *n >= 0 => head(UNSIGNED, *n as u64, out),
+        Number::I64(n) => head(NEGATIVE, !(*n as u64), out), // -1 - n
+        Number::F64(n) => encode_float(*n, out),
+        number => match number.to_string().parse::<Number>() {
+            Ok(number @ (Number::I64(_) | Number::U64(_))) => encode_number(&number, out),
+            _ => encode_float(number.as_f64(), out),
+        },
+    }
+}
+
+fn encode_float(n: f64, out: &mut Vec<u8>) {
+    if n as f32 as f64 == n || n.is_nan() {
+        out.push(SIMPLE << 5 | 26);
+        out.extend_from_slice(&(n as f32).to_be_bytes());
+    } else {
+        out.push(SIMPLE << 5 | 27);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+// the initial byte and argument, in the shortest form that holds it
+fn head(major: u8, argument: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &str) -> Error {
+        format!("Invalid CBOR at byte {}: {}", self.position, message).into()
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn peek_break(&mut self) -> bool {
+        if self.bytes.get(self.position) == Some(&BREAK) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    // the argument that follows an initial byte; None for indefinite length
+    fn argument(&mut self, info: u8) -> Result<Option<u64>, Error> {
+        Ok(Some(match info {
+            0..=23 => info as u64,
+            24 => self.byte()? as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            INDEFINITE => return Ok(None),
+            _ => return Err(self.error("reserved additional information")),
+        }))
+    }
+
+    // a length, no larger than what the rest of the input could possibly hold
+    fn length(&self, argument: u64) -> Result<usize, Error> {
+        usize::try_from(argument)
+            .ok()
+            .filter(|&n| n <= self.bytes.len() - self.position)
+            .ok_or_else(|| self.error("length runs past the end of input"))
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let initial = self.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        if major == SIMPLE {
+            return self.simple(info);
+        }
+        let argument = self.argument(info)?;
+        match (major, argument) {
+            (UNSIGNED, Some(n)) => Ok(Value::Number(Number::U64(n))),
+            (NEGATIVE, Some(n)) => Ok(Value::Number(match i64::try_from(n) {
+                Ok(n) => Number::I64(-1 - n),
+                Err(_) => Number::F64(-1.0 - n as f64),
+            })),
+            (TEXT, length) => Ok(Value::String(self.text(length)?)),
+            (ARRAY, length) => {
+                let mut elements = Vec::new();
+                match length {
+                    Some(n) => {
+                        for _ in 0..self.length(n)? {
+                            elements.push(self.value()?);
+                        }
+                    }
+                    None => {
+                        while !self.peek_break() {
+                            elements.push(self.value()?);
+                        }
+                    }
+                }
+                Ok(Value::Array(elements))
+            }
+            (MAP, length) => {
+                let mut properties = Map::new();
+                match length {
+                    Some(n) => {
+                        for _ in 0..self.length(n)? {
+                            let key = self.key()?;
+                            properties.insert(key, self.value()?);
+                        }
+                    }
+                    None => {
+                        while !self.peek_break() {
+                            let key = self.key()?;
+                            properties.insert(key, self.value()?);
+                        }
+                    }
+                }
+                Ok(Value::Object(properties))
+            }
+            (TAG, Some(_)) => self.value(),
+            (BYTES, _) => Err(self.error("byte strings have no JSON equivalent")),
+            _ => Err(self.error("indefinite length on a type that can't have one")),
+        }
+    }
+
+    fn simple(&mut self, info: u8) -> Result<Value, Error> {
+        match info {
+            FALSE => Ok(Value::Bool(false)),
+            TRUE => Ok(Value::Bool(true)),
+            NULL | UNDEFINED => Ok(Value::Null),
+            25 => {
+                let bits = u16::from_be_bytes(self.take(2)?.try_into().unwrap());
+                Ok(Value::Number(Number::F64(half_to_f64(bits))))
+            }
+            26 => {
+                let bits = f32::from_be_bytes(self.take(4)?.try_into().unwrap());
+                Ok(Value::Number(Number::F64(bits as f64)))
+            }
+            27 => {
+                let bits = f64::from_be_bytes(self.take(8)?.try_into().unwrap());
+                Ok(Value::Number(Number::F64(bits)))
+            }
+            INDEFINITE => Err(self.error("unexpected break")),
+            _ => Err(self.error("simple values have no JSON equivalent")),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, Error> {
+        let initial = self.byte()?;
+        if initial >> 5 != TEXT {
+            return Err(self.error("object keys must be text strings"));
+        }
+        let length = self.argument(initial & 0x1f)?;
+        self.text(length)
+    }
+
+    // a definite string, or the chunks of an indefinite one joined up
+    fn text(&mut self, length: Option<u64>) -> Result<String, Error> {
+        match length {
+            Some(n) => {
+                let n = self.length(n)?;
+                let bytes = self.take(n)?;
+                String::from_utf8(bytes.to_vec()).map_err(|_| self.error("invalid UTF-8"))
+            }
+            None => {
+                let mut text = String::new();
+                while !self.peek_break() {
+                    let initial = self.byte()?;
+                    match self.argument(initial & 0x1f)? {
+                        Some(n) if initial >> 5 == TEXT => text.push_str(&self.text(Some(n))?),
+                        _ => return Err(self.error("string chunks must be definite text")),
+                    }
+                }
+                Ok(text)
+            }
+        }
+    }
+}
+
+fn half_to_f64(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if bits & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
@@ -0,0 +1,360 @@
+// source code for types that sample documents fit, generated from an InferredSchema:
+// Rust structs using this crate's derives (or serde's) and TypeScript interfaces, like
+// quicktype but fed by schema::infer. In Rust, members missing from some samples, or
+// sometimes null, become Options and values seen with more than one type fall back to
+// Value.
+use crate::Value;
+use crate::schema::InferredSchema;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+#[derive(Debug, Clone)]
+pub struct RustOptions {
+    json_derives: bool,
+    serde_derives: bool,
+}
+
+impl Default for RustOptions {
+    fn default() -> Self {
+        RustOptions {
+            json_derives: true,
+            serde_derives: false,
+        }
+    }
+}
+
+impl RustOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // #[derive(ToJson, FromJson)] and #[json(rename)]
+    pub fn with_json_derives(mut self, json_derives: bool) -> Self {
+        self.json_derives = json_derives;
+        self
+    }
+
+    // #[derive(Serialize, Deserialize)] and #[serde(rename)]
+    pub fn with_serde_derives(mut self, serde_derives: bool) -> Self {
+        self.serde_derives = serde_derives;
+        self
+    }
+}
+
+// `name` is the root type's; nested structs are named after the key they were found
+// under (singular for array elements, e.g. "tags" → Tag), numbered if that's taken.
+// A root that isn't an object becomes a type alias, e.g. `pub type Users = Vec<User>;`.
+pub fn to_rust(schema: &InferredSchema, name: &str, options: &RustOptions) -> String {
+    // taken already, so no struct shadows them
+    let mut names: HashSet<String> = ["Value", "Option", "Vec", "String"]
+        .map(String::from)
+        .into();
+    let alias = schema.is_nullable() || schema.types() != ["object"];
+    if alias {
+        names.insert(type_name(name));
+    }
+    let mut generator = RustGenerator {
+        options,
+        structs: Vec::new(),
+        names,
+        uses_value: false,
+    };
+    let root = generator.type_of(schema, name);
+    let mut out = String::new();
+    if !generator.structs.is_empty() && options.json_derives {
+        out.push_str("use rust_practice_json_parser::{FromJson, ToJson};\n");
+    }
+    if generator.uses_value {
+        out.push_str("use rust_practice_json_parser::Value;\n");
+    }
+    if !generator.structs.is_empty() && options.serde_derives {
+        out.push_str("use serde::{Deserialize, Serialize};\n");
+    }
+    if alias || root == "Value" {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "pub type {} = {};", type_name(name), root);
+    }
+    for definition in &generator.structs {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(definition);
+    }
+    out
+}
+
+struct RustGenerator<'o> {
+    options: &'o RustOptions,
+    structs: Vec<String>, // in the order they were first reached, the root's first
+    names: HashSet<String>,
+    uses_value: bool,
+}
+
+impl RustGenerator<'_> {
+    fn type_of(&mut self, schema: &InferredSchema, name: &str) -> String {
+        let types: Vec<_> = schema
+            .types()
+            .into_iter()
+            .filter(|t| *t != "null")
+            .collect();
+        let inner = match types.as_slice() {
+            ["string"] => "String".to_string(),
+            ["integer"] => "i64".to_string(),
+            ["number"] => "f64".to_string(),
+            ["boolean"] => "bool".to_string(),
+            ["array"] => {
+                let items = schema.items().cloned().unwrap_or_default();
+                format!("Vec<{}>", self.type_of(&items, &singular(name)))
+            }
+            ["object"] if schema.properties().next().is_some() => self.structure(schema, name),
+            // mixed types, nothing but null, or only ever {}
+            _ => {
+                self.uses_value = true;
+                return "Value".to_string();
+            }
+        };
+        match schema.is_nullable() {
+            true => format!("Option<{}>", inner),
+            false => inner,
+        }
+    }
+
+    fn structure(&mut self, schema: &InferredSchema, name: &str) -> String {
+        let struct_name = claim(&mut self.names, type_name(name));
+        // reserved now so the root comes out before what it contains
+        let index = self.structs.len();
+        self.structs.push(String::new());
+
+        let mut derives = vec!["Debug", "Clone", "PartialEq"];
+        if self.options.json_derives {
+            derives.extend(["ToJson", "FromJson"]);
+        }
+        if self.options.serde_derives {
+            derives.extend(["Serialize", "Deserialize"]);
+        }
+        let mut out = String::new();
+        let _ = writeln!(out, "#[derive({})]", derives.join(", "));
+        let _ = writeln!(out, "pub struct {} {{", struct_name);
+        let mut fields = HashSet::new();
+        for (key, property) in schema.properties() {
+            let base = field_name(key);
+            let mut field = base.clone();
+            let mut suffix = 1;
+            while !fields.insert(field.clone()) {
+                suffix += 1;
+                field = format!("{}_{}", base, suffix);
+            }
+            let mut ty = self.type_of(property, key);
+            if !schema.is_required(key) && !ty.starts_with("Option<") {
+                ty = format!("Option<{}>", ty);
+            }
+            if field.trim_start_matches("r#") != key {
+                if self.options.json_derives {
+                    let _ = writeln!(out, "    #[json(rename = {:?})]", key);
+                }
+                if self.options.serde_derives {
+                    let _ = writeln!(out, "    #[serde(rename = {:?})]", key);
+                }
+            }
+            let _ = writeln!(out, "    pub {}: {},", field, ty);
+        }
+        out.push_str("}\n");
+        self.structs[index] = out;
+        struct_name
+    }
+}
+
+// TypeScript declarations (a .d.ts file) for the same payloads: objects become exported
+// interfaces named as in to_rust, members missing from some samples are optional
+// (`key?:`), null joins the union (`string | null`), and a value nothing was learned
+// about is `unknown`. A root that isn't an object becomes `export type Users = User[];`.
+pub fn to_typescript(schema: &InferredSchema, name: &str) -> String {
+    // global types the declarations refer to, or that a reader would expect to mean
+    // the usual thing
+    let mut names: HashSet<String> = ["Record", "Array", "Object", "String", "Number", "Boolean"]
+        .map(String::from)
+        .into();
+    let alias = schema.is_nullable() || schema.types() != ["object"];
+    if alias {
+        names.insert(type_name(name));
+    }
+    let mut generator = TypeScriptGenerator {
+        interfaces: Vec::new(),
+        names,
+    };
+    let root = generator.type_of(schema, name);
+    let mut out = String::new();
+    if alias || generator.interfaces.is_empty() {
+        let _ = writeln!(out, "export type {} = {};", type_name(name), root);
+    }
+    for definition in &generator.interfaces {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(definition);
+    }
+    out
+}
+
+struct TypeScriptGenerator {
+    interfaces: Vec<String>, // in the order they were first reached, the root's first
+    names: HashSet<String>,
+}
+
+impl TypeScriptGenerator {
+    fn type_of(&mut self, schema: &InferredSchema, name: &str) -> String {
+        let mut types = Vec::new();
+        for kind in schema.types() {
+            types.push(match kind {
+                "object" if schema.properties().next().is_none() => {
+                    "Record<string, unknown>".to_string()
+                }
+                "object" => self.interface(schema, name),
+                "array" => {
+                    let items = schema.items().cloned().unwrap_or_default();
+                    let items = self.type_of(&items, &singular(name));
+                    match items.contains(' ') {
+                        true => format!("({})[]", items), // a union, or Record<...>
+                        false => format!("{}[]", items),
+                    }
+                }
+                "integer" | "number" => "number".to_string(),
+                other => other.to_string(), // string, boolean and null are spelled the same
+            });
+        }
+        match types.is_empty() {
+            true => "unknown".to_string(),
+            false => types.join(" | "),
+        }
+    }
+
+    fn interface(&mut self, schema: &InferredSchema, name: &str) -> String {
+        let interface_name = claim(&mut self.names, type_name(name));
+        let index = self.interfaces.len();
+        self.interfaces.push(String::new());
+
+        let mut out = String::new();
+        let _ = writeln!(out, "export interface {} {{", interface_name);
+        for (key, property) in schema.properties() {
+            let ty = self.type_of(property, key);
+            let optional = if schema.is_required(key) { "" } else { "?" };
+            let is_identifier = key
+                .starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+            match is_identifier {
+                true => {
+                    let _ = writeln!(out, "  {}{}: {};", key, optional, ty);
+                }
+                // a JSON string is a valid JavaScript one
+                false => {
+                    let _ = writeln!(out, "  {}{}: {};", Value::from(key), optional, ty);
+                }
+            }
+        }
+        out.push_str("}\n");
+        self.interfaces[index] = out;
+        interface_name
+    }
+}
+
+// the base name, or the first of base2, base3... that's still free
+fn claim(names: &mut HashSet<String>, base: String) -> String {
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while !names.insert(name.clone()) {
+        suffix += 1;
+        name = format!("{}{}", base, suffix);
+    }
+    name
+}
+
+// the words of a key: split at anything but letters and digits, and where camelCase
+// or an acronym changes case, so "firstName" and "HTTPServer" give two words each
+fn words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let chars: Vec<char> = key.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_numeric()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+// snake_case, made a legal identifier: keywords become raw identifiers, and a leading
+// digit or an empty key gets a prefix
+fn field_name(key: &str) -> String {
+    let words: Vec<String> = words(key).iter().map(|w| w.to_lowercase()).collect();
+    let name = words.join("_");
+    match name.as_str() {
+        "" => "field".to_string(),
+        "self" | "super" | "crate" => format!("{}_", name), // can't be raw
+        name if name.starts_with(|c: char| c.is_numeric()) => format!("field_{}", name),
+        name if KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ => name,
+    }
+}
+
+// PascalCase, made a legal type name
+fn type_name(key: &str) -> String {
+    let mut name = String::new();
+    for word in words(key) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    match name.as_str() {
+        "" => "Item".to_string(),
+        "Self" => "SelfType".to_string(),
+        _ if name.starts_with(|c: char| c.is_numeric()) => format!("Type{}", name),
+        _ => name,
+    }
+}
+
+// the name for one element of an array found under `name`: "tags" → "tag",
+// "entries" → "entry", anything without a plural ending → "dataItem"
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies").filter(|s| !s.is_empty()) {
+        format!("{}y", stem)
+    } else if let Some(stem) = name
+        .strip_suffix('s')
+        .filter(|s| !s.is_empty() && !s.ends_with('s'))
+    {
+        stem.to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
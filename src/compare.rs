@@ -0,0 +1,50 @@
+use crate::Value;
+
+impl Value {
+    // objects match regardless of key order; arrays still compare position by position
+    pub fn deep_eq_unordered(&self, other: &Value) -> bool {
+        deep_eq(self, other, false)
+    }
+
+    // like deep_eq_unordered, but arrays also match as multisets: [1, 2, 2] == [2, 1, 2]
+    pub fn deep_eq_unordered_arrays(&self, other: &Value) -> bool {
+        deep_eq(self, other, true)
+    }
+}
+
+fn deep_eq(a: &Value, b: &Value, unordered_arrays: bool) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, value)| {
+                    b.get(key)
+                        .is_some_and(|other| deep_eq(value, other, unordered_arrays))
+                })
+        }
+        (Value::Array(a), Value::Array(b)) if unordered_arrays => {
+            if a.len() != b.len() {
+                return false;
+            }
+            // the relation is an equivalence, so pairing each element with the first
+            // unused match never blocks a pairing that would have worked
+            let mut used = vec![false; b.len()];
+            a.iter().all(|element| {
+                let found = (0..b.len()).find(|&i| !used[i] && deep_eq(element, &b[i], true));
+                match found {
+                    Some(i) => {
+                        used[i] = true;
+                        true
+                    }
+                    None => false,
+                }
+            })
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| deep_eq(a, b, unordered_arrays))
+        }
+        _ => a == b,
+    }
+}
@@ -0,0 +1,124 @@
+// recognises gzip and zstd streams by their magic bytes and decompresses them on the fly,
+// so the readers accept `big.json.gz` as readily as `big.json`
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::io::BufReader;
+use std::io::{self, BufRead, Read};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+// looks at the buffered bytes without consuming them; a reader that returns fewer bytes
+// than the magic number on its first read is taken as uncompressed
+pub fn detect<R: BufRead>(reader: &mut R) -> io::Result<Compression> {
+    let head = loop {
+        match reader.fill_buf() {
+            Ok(head) => break head,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    };
+    Ok(if head.starts_with(&GZIP_MAGIC) {
+        Compression::Gzip
+    } else if head.starts_with(&ZSTD_MAGIC) {
+        Compression::Zstd
+    } else {
+        Compression::None
+    })
+}
+
+enum State<R> {
+    Detecting(Option<R>), // None only while switching to the detected decoder
+    Plain(R),
+    #[cfg(feature = "gzip")]
+    Gzip(BufReader<flate2::bufread::MultiGzDecoder<R>>),
+    #[cfg(feature = "zstd")]
+    Zstd(BufReader<zstd::stream::read::Decoder<'static, R>>),
+}
+
+// passes plain input through untouched; the format is detected on the first read, so
+// wrapping a reader never fails and never blocks
+pub struct Decoder<R> {
+    state: State<R>,
+}
+
+impl<R: BufRead> Decoder<R> {
+    pub fn new(reader: R) -> Self {
+        Decoder {
+            state: State::Detecting(Some(reader)),
+        }
+    }
+
+    fn inner(&mut self) -> io::Result<&mut dyn BufRead> {
+        if let State::Detecting(reader) = &mut self.state {
+            let compression = match reader {
+                Some(reader) => detect(reader)?,
+                None => return Err(io::Error::other("decoder failed to start")),
+            };
+            let reader = reader.take().expect("checked above");
+            self.state = match compression {
+                Compression::None => State::Plain(reader),
+                #[cfg(feature = "gzip")]
+                Compression::Gzip => {
+                    State::Gzip(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+                }
+                #[cfg(feature = "zstd")]
+                Compression::Zstd => State::Zstd(BufReader::new(
+                    zstd::stream::read::Decoder::with_buffer(reader)?,
+                )),
+                #[allow(unreachable_patterns)]
+                compression => return Err(unsupported(compression)),
+            };
+        }
+        Ok(match &mut self.state {
+            State::Detecting(_) => unreachable!("the format was detected above"),
+            State::Plain(reader) => reader,
+            #[cfg(feature = "gzip")]
+            State::Gzip(reader) => reader,
+            #[cfg(feature = "zstd")]
+            State::Zstd(reader) => reader,
+        })
+    }
+}
+
+// compressed input that this build can't decode is reported rather than parsed as garbage
+#[cfg_attr(all(feature = "gzip", feature = "zstd"), allow(dead_code))]
+fn unsupported(compression: Compression) -> io::Error {
+    let feature = match compression {
+        Compression::Gzip => "gzip",
+        Compression::Zstd => "zstd",
+        Compression::None => unreachable!("plain input is always supported"),
+    };
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "Input is {}-compressed; enable the `{}` feature to read it",
+            feature, feature
+        ),
+    )
+}
+
+impl<R: BufRead> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner()?.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for Decoder<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner()?.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // consume only ever follows a successful fill_buf, so the format is known by now
+        if let Ok(reader) = self.inner() {
+            reader.consume(amount);
+        }
+    }
+}
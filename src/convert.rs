@@ -0,0 +1,513 @@
+// opting application types into JSON without serde: implement ToJson / FromJson by hand
+// and everything built from them (Vec<T>, Option<T>, maps, tuples...) follows
+use crate::path::{JsonPath, PathSegment};
+use crate::{Error, Map, Number, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl ToJson for char {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+// NaN and infinities become null when written, as any other f64 does
+macro_rules! to_json_number {
+    ($($ty:ty)*) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    Value::Number(Number::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+to_json_number!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
+
+impl ToJson for Number {
+    fn to_json(&self) -> Value {
+        Value::Number(self.clone())
+    }
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> Value {
+        Value::Null
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Rc<T> {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Arc<T> {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for VecDeque<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+// sets come out as arrays, a HashSet in its iteration order
+impl<T: ToJson, S> ToJson for HashSet<T, S> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeSet<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+// a HashMap's properties come out in its iteration order, a BTreeMap's sorted
+impl<K: AsRef<str>, V: ToJson, S> ToJson for HashMap<K, V, S> {
+    fn to_json(&self) -> Value {
+        object(self.iter())
+    }
+}
+
+impl<K: AsRef<str>, V: ToJson> ToJson for BTreeMap<K, V> {
+    fn to_json(&self) -> Value {
+        object(self.iter())
+    }
+}
+
+impl ToJson for Map {
+    fn to_json(&self) -> Value {
+        Value::Object(self.clone())
+    }
+}
+
+fn object<'a, K, V, I>(entries: I) -> Value
+where
+    K: AsRef<str> + 'a,
+    V: ToJson + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    entries
+        .map(|(key, value)| (key.as_ref().to_string(), value.to_json()))
+        .collect()
+}
+
+// tuples are arrays, the way serde writes them
+macro_rules! to_json_tuple {
+    ($(($($name:ident)+))*) => {
+        $(
+            impl<$($name: ToJson),+> ToJson for ($($name,)+) {
+                #[allow(non_snake_case)]
+                fn to_json(&self) -> Value {
+                    let ($($name,)+) = self;
+                    Value::Array(vec![$($name.to_json()),+])
+                }
+            }
+        )*
+    };
+}
+
+to_json_tuple! {
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, DecodeError>;
+
+    // what a missing object member decodes to; None makes it an error
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+// where decoding failed and why; the path is relative to the value passed to from_json
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeError {
+    pub path: JsonPath,
+    pub message: String,
+}
+
+impl DecodeError {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        DecodeError {
+            path: JsonPath::root(),
+            message: message.into(),
+        }
+    }
+
+    // "expected string, found number"
+    pub fn mismatch(expected: &str, value: &Value) -> Self {
+        DecodeError::new(format!(
+            "expected {}, found {}",
+            expected,
+            value.type_name()
+        ))
+    }
+
+    // the same error one level further down, for impls that decode their children
+    pub fn within<S: Into<PathSegment>>(self, segment: S) -> Self {
+        let mut path = JsonPath::root();
+        path.push(segment.into());
+        for segment in self.path.segments() {
+            path.push(segment.clone());
+        }
+        DecodeError { path, ..self }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.segments() {
+            [] => write!(f, "$: {}", self.message),
+            _ => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Self {
+        Error::Conversion(error.to_string())
+    }
+}
+
+// the Conversion errors of the TryFrom impls, without a path yet
+impl From<Error> for DecodeError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Conversion(message) => DecodeError::new(message),
+            error => DecodeError::new(error.to_string()),
+        }
+    }
+}
+
+impl Value {
+    pub fn decode<T: FromJson>(&self) -> Result<T, DecodeError> {
+        T::from_json(self)
+    }
+
+    // one member of an object, for hand-written impls: errors carry the key in their
+    // path, and a missing member is an error unless T has a from_missing value
+    pub fn decode_field<T: FromJson>(&self, key: &str) -> Result<T, DecodeError> {
+        let properties = self
+            .as_object()
+            .ok_or_else(|| DecodeError::mismatch("object", self))?;
+        match properties.get(key) {
+            Some(value) => T::from_json(value).map_err(|e| e.within(key)),
+            None => T::from_missing()
+                .ok_or_else(|| DecodeError::new(format!("missing field '{}'", key))),
+        }
+    }
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(bool::try_from(value)?)
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(String::try_from(value)?)
+    }
+}
+
+impl FromJson for char {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        let string = value
+            .as_str()
+            .ok_or_else(|| DecodeError::mismatch("character", value))?;
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(DecodeError::new(format!(
+                "expected a single character, found \"{}\"",
+                string
+            ))),
+        }
+    }
+}
+
+// whole numbers that fit, as with TryFrom
+macro_rules! from_json_number {
+    ($($ty:ty)*) => {
+        $(
+            impl FromJson for $ty {
+                fn from_json(value: &Value) -> Result<Self, DecodeError> {
+                    Ok(<$ty>::try_from(value)?)
+                }
+            }
+        )*
+    };
+}
+
+from_json_number!(i8 i16 i32 i64 u8 u16 u32 u64 usize f64);
+
+impl FromJson for isize {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        let n = i64::from_json(value)?;
+        isize::try_from(n)
+            .map_err(|_| DecodeError::new(format!("number {} does not fit in isize", n)))
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        Ok(f64::from_json(value)? as f32)
+    }
+}
+
+impl FromJson for Number {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Number(number) => Ok(number.clone()),
+            _ => Err(DecodeError::mismatch("number", value)),
+        }
+    }
+}
+
+impl FromJson for () {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Null => Ok(()),
+            _ => Err(DecodeError::mismatch("null", value)),
+        }
+    }
+}
+
+// null and a missing member are both None
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl<T: FromJson> FromJson for Rc<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        T::from_json(value).map(Rc::new)
+    }
+}
+
+impl<T: FromJson> FromJson for Arc<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        T::from_json(value).map(Arc::new)
+    }
+}
+
+// decodes every element of an array, each error under its index
+fn elements<T: FromJson, C: FromIterator<T>>(value: &Value) -> Result<C, DecodeError> {
+    let elements = value
+        .as_array()
+        .ok_or_else(|| DecodeError::mismatch("array", value))?;
+    elements
+        .iter()
+        .enumerate()
+        .map(|(i, element)| T::from_json(element).map_err(|e| e.within(i)))
+        .collect()
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        elements(value)
+    }
+}
+
+impl<T: FromJson> FromJson for VecDeque<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        elements(value)
+    }
+}
+
+impl<T: FromJson + Eq + Hash, S: BuildHasher + Default> FromJson for HashSet<T, S> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        elements(value)
+    }
+}
+
+impl<T: FromJson + Ord> FromJson for BTreeSet<T> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        elements(value)
+    }
+}
+
+impl<T: FromJson, const N: usize> FromJson for [T; N] {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        let decoded: Vec<T> = elements(value)?;
+        let length = decoded.len();
+        decoded
+            .try_into()
+            .map_err(|_| DecodeError::new(format!("expected {} elements, found {}", N, length)))
+    }
+}
+
+// decodes every member of an object, each error under its key
+fn members<V: FromJson, C: FromIterator<(String, V)>>(value: &Value) -> Result<C, DecodeError> {
+    let properties = value
+        .as_object()
+        .ok_or_else(|| DecodeError::mismatch("object", value))?;
+    properties
+        .iter()
+        .map(|(key, value)| match V::from_json(value) {
+            Ok(value) => Ok((key.clone(), value)),
+            Err(e) => Err(e.within(key.as_str())),
+        })
+        .collect()
+}
+
+impl<V: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, V, S> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        members(value)
+    }
+}
+
+impl<V: FromJson> FromJson for BTreeMap<String, V> {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        members(value)
+    }
+}
+
+impl FromJson for Map {
+    fn from_json(value: &Value) -> Result<Self, DecodeError> {
+        value
+            .as_object()
+            .cloned()
+            .ok_or_else(|| DecodeError::mismatch("object", value))
+    }
+}
+
+// a tuple is an array of exactly that many elements
+macro_rules! from_json_tuple {
+    ($(($length:literal $($name:ident $index:tt)+))*) => {
+        $(
+            impl<$($name: FromJson),+> FromJson for ($($name,)+) {
+                fn from_json(value: &Value) -> Result<Self, DecodeError> {
+                    let elements = value
+                        .as_array()
+                        .ok_or_else(|| DecodeError::mismatch("array", value))?;
+                    if elements.len() != $length {
+                        return Err(DecodeError::new(format!(
+                            "expected {} elements, found {}",
+                            $length,
+                            elements.len()
+                        )));
+                    }
+                    Ok(($(
+                        $name::from_json(&elements[$index]).map_err(|e| e.within($index))?,
+                    )+))
+                }
+            }
+        )*
+    };
+}
+
+from_json_tuple! {
+    (1 A 0)
+    (2 A 0 B 1)
+    (3 A 0 B 1 C 2)
+    (4 A 0 B 1 C 2 D 3)
+    (5 A 0 B 1 C 2 D 3 E 4)
+    (6 A 0 B 1 C 2 D 3 E 4 F 5)
+    (7 A 0 B 1 C 2 D 3 E 4 F 5 G 6)
+    (8 A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7)
+}
@@ -0,0 +1,474 @@
+// a lossless view of a JSON or JSONC document (// and /* */ comments, trailing commas):
+// every node remembers where it sits in the original text, so an edit only rewrites the
+// text of the value it touches and comments, whitespace and key order stay as they were
+use crate::pointer::{array_index, tokens};
+use crate::serializer::{self, Newline, SerializeOptions, Style};
+use crate::{Error, Number, Value};
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    text: String,
+    root: Node,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    start: usize, // byte offsets of the value's own text, without surrounding trivia
+    end: usize,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Object(Vec<Member>),
+    Array(Vec<Node>),
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone)]
+struct Member {
+    key: String,
+    start: usize, // where the key's opening quote is
+    value: Node,
+}
+
+impl Document {
+    pub fn parse(text: &str) -> Result<Document, Error> {
+        let mut scanner = Scanner { text, position: 0 };
+        scanner.skip_trivia()?;
+        let root = scanner.value()?;
+        scanner.skip_trivia()?;
+        if scanner.position < text.len() {
+            return Err(scanner.error("Unexpected trailing content"));
+        }
+        Ok(Document {
+            text: text.to_string(),
+            root,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn value(&self) -> Value {
+        self.root.to_value()
+    }
+
+    pub fn get(&self, pointer: &str) -> Option<Value> {
+        Some(self.node(pointer)?.to_value())
+    }
+
+    // where the value at `pointer` is in the text, as a byte range
+    pub fn span(&self, pointer: &str) -> Option<Range<usize>> {
+        let node = self.node(pointer)?;
+        Some(node.start..node.end)
+    }
+
+    // replaces the value at `pointer`, or adds it when the pointer names a new key of an
+    // existing object or "-" of an existing array; only that part of the text changes
+    pub fn set<V: Into<Value>>(&mut self, pointer: &str, value: V) -> Result<(), Error> {
+        let value = value.into();
+        if let Some(node) = self.node(pointer) {
+            let indent = line_indent(&self.text, node.start).to_string();
+            let replacement = self.render(&value, &indent);
+            return self.splice(node.start, node.end, &replacement);
+        }
+        let (parent_pointer, last) = split_last(pointer)?;
+        let parent = self.node(parent_pointer).ok_or_else(|| missing(pointer))?;
+        let newline = self.newline();
+        let (anchor, entry) = match &parent.kind {
+            Kind::Object(members) => {
+                let mut key = String::new();
+                let _ = serializer::write_string(&mut key, &last, &SerializeOptions::default());
+                (members.last().map(|m| (m.start, m.value.end)), key + ": ")
+            }
+            Kind::Array(elements) if last == "-" => {
+                (elements.last().map(|e| (e.start, e.end)), String::new())
+            }
+            Kind::Array(_) => return Err(missing(pointer)),
+            _ => {
+                return Err(Error::Conversion(format!(
+                    "cannot add a child at {}",
+                    pointer
+                )));
+            }
+        };
+        match anchor {
+            // after the last member or element, lined up with it
+            Some((start, end)) => {
+                let indent = line_indent(&self.text, start).to_string();
+                let starts_line = self.text[..start].ends_with(indent.as_str())
+                    && self.text[..start - indent.len()].ends_with('\n');
+                let separator = match starts_line {
+                    true => format!(",{}{}", newline, indent),
+                    false => ", ".to_string(),
+                };
+                let text = format!("{}{}{}", separator, entry, self.render(&value, &indent));
+                self.splice(end, end, &text)
+            }
+            // the first one: right after the opening bracket, one level in
+            None => {
+                let outer = line_indent(&self.text, parent.start).to_string();
+                let inner = format!("{}  ", outer);
+                let rendered = self.render(&value, &inner);
+                let text = match self.text.contains('\n') {
+                    true => format!(
+                        "{}{}{}{}{}{}",
+                        newline, inner, entry, rendered, newline, outer
+                    ),
+                    false => format!("{}{}", entry, rendered),
+                };
+                self.splice(parent.start + 1, parent.start + 1, &text)
+            }
+        }
+    }
+
+    // removes a member or element along with its comma; comments on lines of their own
+    // above it are kept
+    pub fn remove(&mut self, pointer: &str) -> Result<Value, Error> {
+        let (parent_pointer, last) = split_last(pointer)?;
+        let parent = self.node(parent_pointer).ok_or_else(|| missing(pointer))?;
+        // (start, end) of every child in order, and which one goes
+        let (spans, index): (Vec<(usize, usize)>, Option<usize>) = match &parent.kind {
+            Kind::Object(members) => (
+                members.iter().map(|m| (m.start, m.value.end)).collect(),
+                members.iter().rposition(|m| m.key == last),
+            ),
+            Kind::Array(elements) => (
+                elements.iter().map(|e| (e.start, e.end)).collect(),
+                array_index(&last).filter(|&i| i < elements.len()),
+            ),
+            _ => (Vec::new(), None),
+        };
+        let index = index.ok_or_else(|| missing(pointer))?;
+        let removed = self
+            .node(pointer)
+            .ok_or_else(|| missing(pointer))?
+            .to_value();
+        let (start, end) = spans[index];
+        let (from, to) = if index + 1 < spans.len() {
+            (start, spans[index + 1].0)
+        } else if index > 0 {
+            (spans[index - 1].1, end)
+        } else {
+            // the only child: a trailing comma after it would be left dangling
+            let mut scanner = Scanner {
+                text: &self.text,
+                position: end,
+            };
+            scanner.skip_trivia()?;
+            match self.text[scanner.position..].starts_with(',') {
+                true => (start, scanner.position + 1),
+                false => (start, end),
+            }
+        };
+        self.splice(from, to, "")?;
+        Ok(removed)
+    }
+
+    fn node(&self, pointer: &str) -> Option<&Node> {
+        let mut node = &self.root;
+        for token in tokens(pointer)? {
+            node = match &node.kind {
+                // the last of repeated keys wins, as when parsing into a Value
+                Kind::Object(members) => &members.iter().rev().find(|m| m.key == token)?.value,
+                Kind::Array(elements) => elements.get(array_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    // containers are pretty-printed, short ones on one line, and indented to sit at
+    // `indent`; the document's own line endings are used
+    fn render(&self, value: &Value, indent: &str) -> String {
+        let newline = self.newline();
+        let style = Style::pretty().with_newline(match newline {
+            "\r\n" => Newline::CrLf,
+            _ => Newline::Lf,
+        });
+        let options = SerializeOptions::new()
+            .with_style(style)
+            .with_max_width(Some(80usize.saturating_sub(indent.len())));
+        serializer::to_string_with(value, &options)
+            .replace(newline, &format!("{}{}", newline, indent))
+    }
+
+    fn newline(&self) -> &'static str {
+        if self.text.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    // the edited text is parsed again, so offsets stay right for the next edit
+    fn splice(&mut self, start: usize, end: usize, replacement: &str) -> Result<(), Error> {
+        let mut text = String::with_capacity(self.text.len() + replacement.len());
+        text.push_str(&self.text[..start]);
+        text.push_str(replacement);
+        text.push_str(&self.text[end..]);
+        *self = Document::parse(&text)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Node {
+    fn to_value(&self) -> Value {
+        match &self.kind {
+            Kind::Object(members) => {
+                let mut object = Value::object();
+                for member in members {
+                    object[member.key.as_str()] = member.value.to_value();
+                }
+                object
+            }
+            Kind::Array(elements) => Value::Array(elements.iter().map(Node::to_value).collect()),
+            Kind::String(string) => Value::String(string.clone()),
+            Kind::Number(number) => Value::Number(number.clone()),
+            Kind::Bool(value) => Value::Bool(*value),
+            Kind::Null => Value::Null,
+        }
+    }
+}
+
+fn split_last(pointer: &str) -> Result<(&str, String), Error> {
+    let invalid = || Error::Conversion(format!("invalid JSON Pointer '{}'", pointer));
+    let last = tokens(pointer)
+        .ok_or_else(invalid)?
+        .pop()
+        .ok_or_else(invalid)?;
+    let slash = pointer.rfind('/').ok_or_else(invalid)?;
+    Ok((&pointer[..slash], last))
+}
+
+fn missing(pointer: &str) -> Error {
+    Error::Conversion(format!("no value at {}", pointer))
+}
+
+// the whitespace at the start of the line `position` is on
+fn line_indent(text: &str, position: usize) -> &str {
+    let line_start = text[..position].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    let width = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..width.min(position - line_start)]
+}
+
+struct Scanner<'t> {
+    text: &'t str,
+    position: usize,
+}
+
+impl<'t> Scanner<'t> {
+    fn error(&self, message: &str) -> Error {
+        let before = &self.text[..self.position];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        format!("{} at line {}, column {}", message, line, column).into()
+    }
+
+    fn rest(&self) -> &'t str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), Error> {
+        loop {
+            let rest = &self.text[self.position..];
+            let trimmed = rest.trim_start_matches([' ', '\t', '\n', '\r']);
+            self.position += rest.len() - trimmed.len();
+            if let Some(comment) = trimmed.strip_prefix("//") {
+                self.position += 2 + comment.find('\n').unwrap_or(comment.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                let end = comment
+                    .find("*/")
+                    .ok_or_else(|| self.error("Unclosed comment"))?;
+                self.position += 2 + end + 2;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, Error> {
+        let start = self.position;
+        let kind = match self.peek() {
+            Some('{') => self.object()?,
+            Some('[') => self.array()?,
+            Some('"') => Kind::String(self.string()?),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number()?,
+            Some(_) => self.keyword()?,
+            None => return Err(self.error("Unexpected end of input")),
+        };
+        Ok(Node {
+            start,
+            end: self.position,
+            kind,
+        })
+    }
+
+    fn object(&mut self) -> Result<Kind, Error> {
+        self.position += 1;
+        let mut members = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some('}') {
+                self.position += 1;
+                return Ok(Kind::Object(members));
+            }
+            if !members.is_empty() {
+                self.expect(',')?;
+                self.skip_trivia()?;
+                if self.peek() == Some('}') {
+                    continue; // a trailing comma
+                }
+            }
+            let start = self.position;
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected a key"));
+            }
+            let key = self.string()?;
+            self.skip_trivia()?;
+            self.expect(':')?;
+            self.skip_trivia()?;
+            let value = self.value()?;
+            members.push(Member { key, start, value });
+        }
+    }
+
+    fn array(&mut self) -> Result<Kind, Error> {
+        self.position += 1;
+        let mut elements = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(']') {
+                self.position += 1;
+                return Ok(Kind::Array(elements));
+            }
+            if !elements.is_empty() {
+                self.expect(',')?;
+                self.skip_trivia()?;
+                if self.peek() == Some(']') {
+                    continue;
+                }
+            }
+            elements.push(self.value()?);
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("Expected '{}', but found '{}'", expected, c))),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    // unlike the main lexer, escapes are decoded here, \uXXXX and surrogate pairs included
+    fn string(&mut self) -> Result<String, Error> {
+        self.position += 1;
+        let mut string = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("Unterminated string"))?;
+            self.position += c.len_utf8();
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| self.error("Unterminated string"))?;
+                    self.position += escaped.len_utf8();
+                    match escaped {
+                        '"' | '\\' | '/' => string.push(escaped),
+                        'b' => string.push('\u{08}'),
+                        'f' => string.push('\u{0C}'),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'u' => string.push(self.unicode_escape()?),
+                        _ => return Err(self.error("Invalid escape")),
+                    }
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, Error> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("Invalid \\u escape"));
+        }
+        if !self.rest().starts_with("\\u") {
+            return Err(self.error("Unpaired surrogate"));
+        }
+        self.position += 2;
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("Unpaired surrogate"));
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| self.error("Invalid \\u escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let digits = self
+            .rest()
+            .get(..4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("Invalid \\u escape"))?;
+        let value =
+            u32::from_str_radix(digits, 16).map_err(|_| self.error("Invalid \\u escape"))?;
+        self.position += 4;
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Kind, Error> {
+        let start = self.position;
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(self.rest().len());
+        self.position += length;
+        let text = &self.text[start..self.position];
+        text.parse::<Number>().map(Kind::Number).map_err(|_| {
+            self.position = start;
+            self.error(&format!("Invalid number '{}'", text))
+        })
+    }
+
+    fn keyword(&mut self) -> Result<Kind, Error> {
+        for (keyword, kind) in [
+            ("true", Kind::Bool(true)),
+            ("false", Kind::Bool(false)),
+            ("null", Kind::Null),
+        ] {
+            if self.rest().starts_with(keyword) {
+                self.position += keyword.len();
+                return Ok(kind);
+            }
+        }
+        let c = self.peek().unwrap_or_default();
+        Err(self.error(&format!("Unexpected character: '{}'", c)))
+    }
+}
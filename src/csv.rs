@@ -0,0 +1,292 @@
+// arrays of objects ⇄ CSV: each object is one row, flattened to paths like "a.b[0]"
+// so nested data gets a column per leaf, and the header is the union of every row's
+// paths in the order they first appear
+use crate::flatten::FlattenOptions;
+use crate::{Error, Map, Number, Value};
+use std::io::{self, BufWriter, Read, Write};
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    delimiter: char,
+    flatten: FlattenOptions,
+    infer_types: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            flatten: FlattenOptions::default(),
+            infer_types: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    // how nested paths are spelled in the header, both ways
+    pub fn with_flatten(mut self, flatten: FlattenOptions) -> Self {
+        self.flatten = flatten;
+        self
+    }
+
+    // when reading, turn cells that look like numbers, booleans, {} or [] back into
+    // them; off, every cell stays a string
+    pub fn with_infer_types(mut self, infer_types: bool) -> Self {
+        self.infer_types = infer_types;
+        self
+    }
+}
+
+// null and missing members both become empty cells
+pub fn to_csv<W: Write>(writer: W, value: &Value, options: &CsvOptions) -> Result<(), Error> {
+    let elements = value.as_array().ok_or_else(|| {
+        Error::Conversion(format!(
+            "CSV export needs an array of objects, not {}",
+            value.type_name()
+        ))
+    })?;
+    let mut rows = Vec::with_capacity(elements.len());
+    for (index, element) in elements.iter().enumerate() {
+        if !element.is_object() {
+            return Err(Error::Conversion(format!(
+                "[{}]: CSV rows must be objects, not {}",
+                index,
+                element.type_name()
+            )));
+        }
+        // {} flattens to itself under the empty path; as a row it's just empty
+        if element.as_object().is_some_and(Map::is_empty) {
+            rows.push(Map::new());
+            continue;
+        }
+        match element.flatten_with(&options.flatten) {
+            Value::Object(row) => rows.push(row),
+            _ => unreachable!("flattening always gives an object"),
+        }
+    }
+    let mut header = Map::new(); // a Map for its insertion order and lookups
+    for row in &rows {
+        for key in row.keys() {
+            if !header.contains_key(key) {
+                header.insert(key.clone(), Value::Null);
+            }
+        }
+    }
+    let mut writer = BufWriter::new(writer);
+    let mut line = String::new();
+    for (i, key) in header.keys().enumerate() {
+        if i > 0 {
+            line.push(options.delimiter);
+        }
+        push_field(&mut line, key, options.delimiter);
+    }
+    line.push_str("\r\n");
+    writer.write_all(line.as_bytes())?;
+    for row in &rows {
+        line.clear();
+        for (i, key) in header.keys().enumerate() {
+            if i > 0 {
+                line.push(options.delimiter);
+            }
+            match row.get(key) {
+                None | Some(Value::Null) => {}
+                Some(Value::String(s)) => push_field(&mut line, s, options.delimiter),
+                Some(other) => push_field(&mut line, &other.to_string(), options.delimiter),
+            }
+        }
+        if line.is_empty() && !header.is_empty() {
+            line.push_str("\"\""); // a blank line would be skipped when read back
+        }
+        line.push_str("\r\n");
+        writer.write_all(line.as_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// quoted only when it has to be, as RFC 4180 puts it
+fn push_field(line: &mut String, field: &str, delimiter: char) {
+    if field.contains([delimiter, '"', '\r', '\n']) {
+        line.push('"');
+        line.push_str(&field.replace('"', "\"\""));
+        line.push('"');
+    } else {
+        line.push_str(field);
+    }
+}
+
+// the first record is the header; empty cells leave the member out, and each row is
+// unflattened back into nested objects and arrays
+pub fn from_csv<R: Read>(mut reader: R, options: &CsvOptions) -> Result<Value, Error> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => "Invalid UTF-8 in input".into(),
+            _ => Error::from(e),
+        })?;
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    let mut records = Records {
+        chars: text.chars().peekable(),
+        delimiter: options.delimiter,
+        line: 1,
+    };
+    let Some((_, header)) = records.next_record()? else {
+        return Ok(Value::Array(Vec::new()));
+    };
+    let mut rows = Vec::new();
+    while let Some((line, fields)) = records.next_record()? {
+        if fields.len() > header.len() {
+            let message = format!(
+                "the row has {} fields but the header has {}",
+                fields.len(),
+                header.len()
+            );
+            return Err(Error::Line(line, Box::new(message.into())));
+        }
+        let mut flat = Map::new();
+        for (key, field) in header.iter().zip(fields) {
+            if field.is_empty() {
+                continue;
+            }
+            let cell = match options.infer_types {
+                true => infer(field),
+                false => Value::String(field),
+            };
+            flat.insert(key.clone(), cell);
+        }
+        let row = Value::Object(flat)
+            .unflatten_with(&options.flatten)
+            .map_err(|e| Error::Line(line, Box::new(e)))?;
+        // a row with every cell empty unflattens to null
+        rows.push(match row {
+            Value::Null => Value::object(),
+            row => row,
+        });
+    }
+    Ok(Value::Array(rows))
+}
+
+fn infer(field: String) -> Value {
+    match field.as_str() {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "{}" => Value::object(),
+        "[]" => Value::Array(Vec::new()),
+        text if is_number(text) => match text.parse::<Number>() {
+            Ok(number) => Value::Number(number),
+            Err(_) => Value::String(field),
+        },
+        _ => Value::String(field),
+    }
+}
+
+// JSON's number grammar, so "1e5" counts but "0x10", "1." and "inf" don't
+fn is_number(text: &str) -> bool {
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(e) => (&text[..e], Some(&text[e + 1..])),
+        None => (text, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    digits(whole)
+        && (whole == "0" || !whole.starts_with('0'))
+        && fraction.is_none_or(digits)
+        && exponent.is_none_or(|e| digits(e.strip_prefix(['+', '-']).unwrap_or(e)))
+}
+
+struct Records<I: Iterator<Item = char>> {
+    chars: std::iter::Peekable<I>,
+    delimiter: char,
+    line: usize,
+}
+
+impl<I: Iterator<Item = char>> Records<I> {
+    // the next record's starting line and fields; blank lines are skipped
+    fn next_record(&mut self) -> Result<Option<(usize, Vec<String>)>, Error> {
+        while matches!(self.chars.peek(), Some('\r' | '\n')) {
+            self.line_break();
+        }
+        if self.chars.peek().is_none() {
+            return Ok(None);
+        }
+        let start = self.line;
+        let mut fields = Vec::new();
+        loop {
+            fields.push(self.field(start)?);
+            match self.chars.peek() {
+                Some(&c) if c == self.delimiter => {
+                    self.chars.next();
+                }
+                Some('\r' | '\n') => {
+                    self.line_break();
+                    return Ok(Some((start, fields)));
+                }
+                None => return Ok(Some((start, fields))),
+                Some(_) => unreachable!("a field ends at a delimiter or line break"),
+            }
+        }
+    }
+
+    fn line_break(&mut self) {
+        if self.chars.next() == Some('\r') && self.chars.peek() == Some(&'\n') {
+            self.chars.next();
+        }
+        self.line += 1;
+    }
+
+    fn field(&mut self, start: usize) -> Result<String, Error> {
+        let mut field = String::new();
+        if self.chars.peek() != Some(&'"') {
+            while let Some(&c) = self.chars.peek() {
+                if c == self.delimiter || c == '\r' || c == '\n' {
+                    break;
+                }
+                field.push(c);
+                self.chars.next();
+            }
+            return Ok(field);
+        }
+        self.chars.next(); // consume the opening quote
+        loop {
+            match self.chars.next() {
+                Some('"') if self.chars.peek() == Some(&'"') => {
+                    self.chars.next();
+                    field.push('"');
+                }
+                Some('"') => break,
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    field.push(c);
+                }
+                None => {
+                    let error = Error::from("Unterminated quoted field");
+                    return Err(Error::Line(start, Box::new(error)));
+                }
+            }
+        }
+        match self.chars.peek() {
+            None | Some('\r' | '\n') => Ok(field),
+            Some(&c) if c == self.delimiter => Ok(field),
+            Some(_) => Err(Error::Line(
+                self.line,
+                Box::new("Unexpected text after a quoted field".into()),
+            )),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// a movable position in a tree that remembers how it got there, so it can go back up
+use crate::Value;
+use crate::path::{JsonPath, PathSegment};
+
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    ancestors: Vec<&'a Value>, // parents of the current node, root first
+    current: &'a Value,
+    path: JsonPath,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(root: &'a Value) -> Self {
+        Cursor {
+            ancestors: Vec::new(),
+            current: root,
+            path: JsonPath::root(),
+        }
+    }
+
+    pub fn value(&self) -> &'a Value {
+        self.current
+    }
+
+    pub fn path(&self) -> &JsonPath {
+        &self.path
+    }
+
+    pub fn depth(&self) -> usize {
+        self.ancestors.len()
+    }
+
+    pub fn parent(&self) -> Option<&'a Value> {
+        self.ancestors.last().copied()
+    }
+
+    // every move returns the node moved to; on None the cursor stays where it was
+    pub fn up(&mut self) -> Option<&'a Value> {
+        self.current = self.ancestors.pop()?;
+        self.path.pop();
+        Some(self.current)
+    }
+
+    pub fn down<S: Into<PathSegment>>(&mut self, segment: S) -> Option<&'a Value> {
+        let segment = segment.into();
+        let child = match &segment {
+            PathSegment::Key(key) => self.current.get(key)?,
+            PathSegment::Index(index) => self.current.get(*index)?,
+        };
+        self.ancestors.push(self.current);
+        self.current = child;
+        self.path.push(segment);
+        Some(child)
+    }
+
+    pub fn first_child(&mut self) -> Option<&'a Value> {
+        match self.current {
+            Value::Object(properties) => {
+                let key = properties.keys().next()?.clone();
+                self.down(key)
+            }
+            Value::Array(elements) if !elements.is_empty() => self.down(0),
+            _ => None,
+        }
+    }
+
+    pub fn next_sibling(&mut self) -> Option<&'a Value> {
+        let sibling = match (self.parent()?, self.path.last()?) {
+            (Value::Object(properties), PathSegment::Key(key)) => {
+                let mut keys = properties.keys().skip_while(|other| *other != key);
+                PathSegment::Key(keys.nth(1)?.clone())
+            }
+            (Value::Array(elements), PathSegment::Index(index)) if index + 1 < elements.len() => {
+                PathSegment::Index(index + 1)
+            }
+            _ => return None,
+        };
+        self.up();
+        self.down(sibling)
+    }
+
+    pub fn prev_sibling(&mut self) -> Option<&'a Value> {
+        let sibling = match (self.parent()?, self.path.last()?) {
+            (Value::Object(properties), PathSegment::Key(key)) => {
+                let previous = properties.keys().take_while(|other| *other != key).last();
+                PathSegment::Key(previous?.clone())
+            }
+            (Value::Array(_), PathSegment::Index(index)) if *index > 0 => {
+                PathSegment::Index(index - 1)
+            }
+            _ => return None,
+        };
+        self.up();
+        self.down(sibling)
+    }
+}
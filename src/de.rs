@@ -0,0 +1,349 @@
+// serde support for reading: decodes any T: Deserialize straight from the text, walking
+// the structural index the way tape::parse_str_fast does, without building a Value.
+// Strings are slices of the input, so T can borrow them as &str.
+use crate::tape::{StructuralIndex, Walker, is_blank, parse_scalar};
+use crate::{Error, Map, Number, TokenType, Value};
+use serde::de::{self, Deserialize, DeserializeOwned, IntoDeserializer, Visitor};
+use std::fmt;
+use std::io;
+
+pub fn from_str<'de, T: Deserialize<'de>>(input: &'de str) -> Result<T, Error> {
+    let index = StructuralIndex::build(input)?;
+    let mut deserializer = Deserializer {
+        walker: Walker::new(input, index.positions(), 0, 0),
+    };
+    let value = T::deserialize(&mut deserializer)?;
+    let walker = &deserializer.walker;
+    if walker.next < index.positions().len() || !is_blank(walker.gap()) {
+        return Err("Unexpected trailing token".into());
+    }
+    Ok(value)
+}
+
+// reads everything first; the result can't borrow from the reader
+pub fn from_reader<R: io::Read, T: DeserializeOwned>(mut reader: R) -> Result<T, Error> {
+    let mut input = String::new();
+    reader
+        .read_to_string(&mut input)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => "Invalid UTF-8 in input".into(),
+            _ => Error::from(e),
+        })?;
+    from_str(&input)
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error::Conversion(message.to_string())
+    }
+}
+
+pub struct Deserializer<'de, 'p> {
+    walker: Walker<'de, 'p>,
+}
+
+impl<'de> Deserializer<'de, '_> {
+    // a number or keyword sitting before the next structural, consumed
+    fn scalar(&mut self) -> Option<&'de str> {
+        let gap = self.walker.gap();
+        let scalar = gap.trim();
+        if scalar.is_empty() {
+            return None;
+        }
+        self.walker.offset += gap.len();
+        Some(scalar)
+    }
+
+    fn expect(&mut self, byte: u8, message: &str) -> Result<(), Error> {
+        match self.walker.peek() {
+            Some(b) if b == byte => {
+                self.walker.bump();
+                Ok(())
+            }
+            _ => Err(message.into()),
+        }
+    }
+
+    fn string(&mut self) -> Result<&'de str, Error> {
+        match self.walker.peek() {
+            Some(b'"') => Ok(self.walker.string_slice()),
+            Some(_) => Err("Expected string".into()),
+            None => Err("Unexpected end of input".into()),
+        }
+    }
+}
+
+fn visit_scalar<'de, V: Visitor<'de>>(text: &str, visitor: V) -> Result<V::Value, Error> {
+    match parse_scalar(text)? {
+        Value::Bool(b) => visitor.visit_bool(b),
+        Value::Number(Number::U64(n)) => visitor.visit_u64(n),
+        Value::Number(Number::I64(n)) => visitor.visit_i64(n),
+        Value::Number(number) => visitor.visit_f64(number.as_f64()),
+        _ => visitor.visit_unit(),
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de, '_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if let Some(scalar) = self.scalar() {
+            return visit_scalar(scalar, visitor);
+        }
+        match self.walker.peek() {
+            Some(b'"') => visitor.visit_borrowed_str(self.walker.string_slice()),
+            Some(b'[') => {
+                self.walker.bump();
+                let value = visitor.visit_seq(Sequence {
+                    deserializer: &mut *self,
+                    first: true,
+                })?;
+                self.expect(b']', "Expected ',' or ']' in array")?;
+                Ok(value)
+            }
+            Some(b'{') => {
+                self.walker.bump();
+                let value = visitor.visit_map(Members {
+                    deserializer: &mut *self,
+                    first: true,
+                })?;
+                self.expect(b'}', "Expected ',' or '}' in object")?;
+                Ok(value)
+            }
+            Some(_) => Err("Invalid JSON token".into()),
+            None => Err("Unexpected end of input".into()),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let gap = self.walker.gap();
+        if gap.trim() == "null" {
+            self.walker.offset += gap.len();
+            return visitor.visit_none();
+        }
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // "Variant" for unit variants, {"Variant": ...} for the rest, as ser writes them
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.walker.peek() {
+            Some(b'"') => {
+                let variant = self.walker.string_slice();
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            Some(b'{') => {
+                self.walker.bump();
+                let value = visitor.visit_enum(Variant {
+                    deserializer: &mut *self,
+                })?;
+                self.expect(b'}', "Expected a single key in an enum object")?;
+                Ok(value)
+            }
+            _ => Err(Error::Conversion(
+                "expected a string or an object for an enum".into(),
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+// the elements of an array; the closing ']' is left to the caller
+struct Sequence<'a, 'de, 'p> {
+    deserializer: &'a mut Deserializer<'de, 'p>,
+    first: bool,
+}
+
+impl<'de> de::SeqAccess<'de> for Sequence<'_, 'de, '_> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        let walker = &mut self.deserializer.walker;
+        match (self.first, walker.peek()) {
+            (_, Some(b']')) => return Ok(None),
+            (true, _) => {}
+            (false, Some(b',')) => {
+                walker.bump();
+                if walker.peek() == Some(b']') {
+                    return Err("Trailing comma in array".into());
+                }
+            }
+            (false, _) => return Err("Expected ',' or ']' in array".into()),
+        }
+        self.first = false;
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+}
+
+// the members of an object; the closing '}' is left to the caller
+struct Members<'a, 'de, 'p> {
+    deserializer: &'a mut Deserializer<'de, 'p>,
+    first: bool,
+}
+
+impl<'de> de::MapAccess<'de> for Members<'_, 'de, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let walker = &mut self.deserializer.walker;
+        match (self.first, walker.peek()) {
+            (_, Some(b'}')) => return Ok(None),
+            (true, _) => {}
+            (false, Some(b',')) => {
+                walker.bump();
+                if walker.peek() == Some(b'}') {
+                    return Err("Trailing comma in object".into());
+                }
+            }
+            (false, _) => return Err("Expected ',' or '}' in object".into()),
+        }
+        self.first = false;
+        let key = self.deserializer.string()?;
+        seed.deserialize(de::value::BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        self.deserializer.expect(
+            b':',
+            &format!("Expected {:?}, found unexpected token", TokenType::Colon),
+        )?;
+        seed.deserialize(&mut *self.deserializer)
+    }
+}
+
+// {"Variant": content}, positioned after the '{'
+struct Variant<'a, 'de, 'p> {
+    deserializer: &'a mut Deserializer<'de, 'p>,
+}
+
+impl<'de> de::EnumAccess<'de> for Variant<'_, 'de, '_> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let name = self.deserializer.string()?;
+        let variant = seed.deserialize(de::value::BorrowedStrDeserializer::<Error>::new(name))?;
+        self.deserializer.expect(
+            b':',
+            &format!("Expected {:?}, found unexpected token", TokenType::Colon),
+        )?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Variant<'_, 'de, '_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(&mut *self.deserializer)
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(&mut *self.deserializer)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(&mut *self.deserializer, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(&mut *self.deserializer, visitor)
+    }
+}
+
+// a Value can itself be read from any serde deserializer
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut elements = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(Value::Array(elements))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut properties = Map::new();
+        while let Some((key, value)) = map.next_entry::<String, Value>()? {
+            properties.insert(key, value);
+        }
+        Ok(Value::Object(properties))
+    }
+}
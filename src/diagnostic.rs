@@ -0,0 +1,231 @@
+// machine-readable problems with a document, for tools that wrap the crate: each has a
+// severity, a stable code, a message, and where it is, as a byte range into the source
+// and, for schema violations, a JSON Pointer. They come from the lexer and parser, which
+// report the token they stopped at, and from schema validation.
+use crate::cst::Document;
+use crate::schema::{Schema, Violation};
+use crate::{Error, Value, lexer, parser};
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: String, // e.g. "syntax/trailing-comma" or "schema/minimum"
+    pub message: String,
+    pub span: Option<Range<usize>>, // empty at the end of the source for a cut-off input
+    pub path: Option<String>,       // a JSON Pointer, "" for the root
+}
+
+impl Diagnostic {
+    pub fn error(code: &str, message: &str) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: code.to_string(),
+            message: message.to_string(),
+            span: None,
+            path: None,
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    // a lexer or parser failure; the code comes from which of their messages it is
+    pub(crate) fn syntax(error: &Error, span: Range<usize>) -> Self {
+        let message = error.to_string();
+        let code = match message.as_str() {
+            m if m.starts_with("Unexpected character") => "syntax/unexpected-character",
+            m if m.starts_with("Unexpected keyword") => "syntax/unknown-keyword",
+            m if m.starts_with("Unexpected end of input") => "syntax/unexpected-end",
+            m if m.starts_with("Unexpected trailing token") => "syntax/trailing-content",
+            m if m.starts_with("Trailing comma") => "syntax/trailing-comma",
+            m if m.starts_with("Invalid number") => "syntax/invalid-number",
+            _ => "syntax/unexpected-token",
+        };
+        Diagnostic::error(code, &message).with_span(span)
+    }
+
+    // the code is the failing keyword's, e.g. "schema/required", or "schema/false" for a
+    // `false` schema
+    pub fn from_violation(violation: &Violation) -> Self {
+        let keyword = match violation.schema_path.rsplit_once('/') {
+            Some((_, keyword)) if !keyword.is_empty() => keyword,
+            _ => "false",
+        };
+        Diagnostic::error(&format!("schema/{}", keyword), &violation.message)
+            .with_path(&violation.instance_path)
+    }
+}
+
+// "error[syntax/trailing-comma]: Trailing comma in array", then " at /path" if it has one
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity.as_str(),
+            self.code,
+            self.message
+        )?;
+        match &self.path {
+            Some(path) if path.is_empty() => write!(f, " at /"),
+            Some(path) => write!(f, " at {}", path),
+            None => Ok(()),
+        }
+    }
+}
+
+// parses like parse_str, but a failure is a Diagnostic pointing at where it happened
+pub fn parse(source: &str) -> Result<Value, Diagnostic> {
+    let tokens = lexer::generate_located(source)
+        .map_err(|(error, span)| Diagnostic::syntax(&error, span))?;
+    let (tokens, spans): (Vec<_>, Vec<_>) = tokens.into_iter().unzip();
+    parser::generate_located(&tokens, &parser::ParseOptions::default()).map_err(|(error, index)| {
+        let span = spans
+            .get(index)
+            .cloned()
+            .unwrap_or(source.len()..source.len());
+        Diagnostic::syntax(&error, span)
+    })
+}
+
+// everything wrong with the source against the schema: the syntax error if it doesn't
+// parse, otherwise a diagnostic per violation, spanning the value it's about
+pub fn check(source: &str, schema: &Schema) -> Vec<Diagnostic> {
+    let value = match parse(source) {
+        Ok(value) => value,
+        Err(diagnostic) => return vec![diagnostic],
+    };
+    let Err(violations) = schema.validate(&value) else {
+        return Vec::new();
+    };
+    let document = Document::parse(source).ok();
+    violations
+        .iter()
+        .map(|violation| {
+            let diagnostic = Diagnostic::from_violation(violation);
+            match document
+                .as_ref()
+                .and_then(|document| document.span(&violation.instance_path))
+            {
+                Some(span) => diagnostic.with_span(span),
+                None => diagnostic,
+            }
+        })
+        .collect()
+}
+
+// a JSON array with an object per diagnostic:
+//   {"severity": "error", "code": "...", "message": "...", "path": "/a" or null,
+//    "span": {"start": 4, "end": 5, "line": 1, "column": 5} or null}
+// offsets are bytes; lines and columns count from 1, columns in characters
+pub fn to_json(diagnostics: &[Diagnostic], source: &str) -> Value {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut object = Value::object();
+            object["severity"] = diagnostic.severity.as_str().into();
+            object["code"] = diagnostic.code.as_str().into();
+            object["message"] = diagnostic.message.as_str().into();
+            object["path"] = diagnostic.path.clone().into();
+            object["span"] = match &diagnostic.span {
+                Some(span) => {
+                    let (line, column) = line_column(source, span.start);
+                    let mut location = Value::object();
+                    location["start"] = span.start.into();
+                    location["end"] = span.end.into();
+                    location["line"] = line.into();
+                    location["column"] = column.into();
+                    location
+                }
+                None => Value::Null,
+            };
+            object
+        })
+        .collect()
+}
+
+// compiler-style text, a block per diagnostic, with the source line and the span marked
+// under it:
+//
+//   error[syntax/trailing-comma]: Trailing comma in array
+//    --> config.json:1:7
+//     |
+//   1 | [1, 2,]
+//     |      ^
+pub fn to_text(diagnostics: &[Diagnostic], source: &str, name: &str) -> String {
+    let mut out = String::new();
+    for (i, diagnostic) in diagnostics.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{}", diagnostic);
+        let Some(span) = &diagnostic.span else {
+            continue;
+        };
+        let (line, column) = line_column(source, span.start);
+        let number = line.to_string();
+        let gutter = " ".repeat(number.len());
+        let _ = writeln!(out, "{}--> {}:{}:{}", gutter, name, line, column);
+        let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[span.start..]
+            .find('\n')
+            .map_or(source.len(), |i| span.start + i);
+        let text = source[line_start..line_end].trim_end_matches('\r');
+        // only the part of the span on its first line is marked
+        let marked = source[span.start..span.end.min(line_start + text.len()).max(span.start)]
+            .chars()
+            .count()
+            .max(1);
+        let _ = writeln!(out, "{} |", gutter);
+        let _ = writeln!(out, "{} | {}", number, text);
+        let _ = writeln!(
+            out,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(marked)
+        );
+    }
+    out
+}
+
+// 1-based, the column in characters
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
@@ -0,0 +1,161 @@
+// structural comparison of two documents, e.g. an expected and an actual API response;
+// unlike patch::diff, the result is meant to be read rather than applied
+use crate::Value;
+use crate::path::{JsonPath, PathSegment};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifferenceKind {
+    Added,       // only on the right
+    Removed,     // only on the left
+    Changed,     // same type, different value
+    TypeChanged, // e.g. a number on the left and a string on the right
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub path: JsonPath,
+    pub kind: DifferenceKind,
+    pub left: Option<Value>,  // None for Added
+    pub right: Option<Value>, // None for Removed
+}
+
+// objects are compared key by key in any order, arrays position by position; a
+// difference is reported at the deepest path where the two sides still line up
+pub fn diff(left: &Value, right: &Value) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    diff_at(left, right, &mut JsonPath::root(), &mut differences);
+    differences
+}
+
+fn diff_at(left: &Value, right: &Value, path: &mut JsonPath, out: &mut Vec<Difference>) {
+    match (left, right) {
+        (Value::Raw(raw), _) => diff_at(&raw.parse().unwrap_or_default(), right, path, out),
+        (_, Value::Raw(raw)) => diff_at(left, &raw.parse().unwrap_or_default(), path, out),
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                path.push(PathSegment::Key(key.clone()));
+                match b.get(key) {
+                    Some(other) => diff_at(value, other, path, out),
+                    None => out.push(difference(path, DifferenceKind::Removed, Some(value), None)),
+                }
+                path.pop();
+            }
+            for (key, value) in b {
+                if !a.contains_key(key) {
+                    path.push(PathSegment::Key(key.clone()));
+                    out.push(difference(path, DifferenceKind::Added, None, Some(value)));
+                    path.pop();
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                path.push(PathSegment::Index(i));
+                match (a.get(i), b.get(i)) {
+                    (Some(value), Some(other)) => diff_at(value, other, path, out),
+                    (Some(value), None) => {
+                        out.push(difference(path, DifferenceKind::Removed, Some(value), None))
+                    }
+                    (None, Some(other)) => {
+                        out.push(difference(path, DifferenceKind::Added, None, Some(other)))
+                    }
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        _ if left.type_name() != right.type_name() => out.push(difference(
+            path,
+            DifferenceKind::TypeChanged,
+            Some(left),
+            Some(right),
+        )),
+        _ if left != right => out.push(difference(
+            path,
+            DifferenceKind::Changed,
+            Some(left),
+            Some(right),
+        )),
+        _ => {}
+    }
+}
+
+fn difference(
+    path: &JsonPath,
+    kind: DifferenceKind,
+    left: Option<&Value>,
+    right: Option<&Value>,
+) -> Difference {
+    Difference {
+        path: path.clone(),
+        kind,
+        left: left.cloned(),
+        right: right.cloned(),
+    }
+}
+
+// one line per difference:
+//   + tags[2]: "new"
+//   - debug: true
+//   ~ limits.memory: 512 -> 1024
+//   ! id: 42 -> "42" (number -> string)
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marker = match self.kind {
+            DifferenceKind::Added => '+',
+            DifferenceKind::Removed => '-',
+            DifferenceKind::Changed => '~',
+            DifferenceKind::TypeChanged => '!',
+        };
+        match self.path.segments() {
+            [] => write!(f, "{} $: ", marker)?,
+            _ => write!(f, "{} {}: ", marker, self.path)?,
+        }
+        match (&self.left, &self.right) {
+            (Some(left), Some(right)) if self.kind == DifferenceKind::TypeChanged => write!(
+                f,
+                "{} -> {} ({} -> {})",
+                left,
+                right,
+                left.type_name(),
+                right.type_name()
+            ),
+            (Some(left), Some(right)) => write!(f, "{} -> {}", left, right),
+            (Some(value), None) | (None, Some(value)) => write!(f, "{}", value),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+// every difference on its own line, then a summary such as
+// "3 differences: 1 added, 1 removed, 1 changed"
+pub fn report(differences: &[Difference]) -> String {
+    if differences.is_empty() {
+        return "no differences\n".to_string();
+    }
+    let mut report = String::new();
+    for difference in differences {
+        report.push_str(&difference.to_string());
+        report.push('\n');
+    }
+    let count = |kind| differences.iter().filter(|d| d.kind == kind).count();
+    let counts = [
+        (count(DifferenceKind::Added), "added"),
+        (count(DifferenceKind::Removed), "removed"),
+        (count(DifferenceKind::Changed), "changed"),
+        (count(DifferenceKind::TypeChanged), "changed type"),
+    ];
+    let summary: Vec<String> = counts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{} {}", n, label))
+        .collect();
+    report.push_str(&format!(
+        "{} difference{}: {}\n",
+        differences.len(),
+        if differences.len() == 1 { "" } else { "s" },
+        summary.join(", ")
+    ));
+    report
+}
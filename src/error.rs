@@ -0,0 +1,58 @@
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    Syntax(String),
+    Io(Arc<io::Error>), // Arc keeps the error cloneable
+    Cancelled,
+    Line(usize, Box<Error>),   // a failure inside one line of NDJSON input
+    Record(usize, Box<Error>), // a failure inside one json-seq record
+    Conversion(String),        // a Value that does not fit the requested Rust type
+    Patch(usize, Box<Error>),  // a failure in one JSON Patch operation, counted from 0
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Syntax(message) => write!(f, "{}", message),
+            Error::Io(error) => write!(f, "I/O error: {}", error),
+            Error::Cancelled => write!(f, "Parse cancelled"),
+            Error::Line(line, error) => write!(f, "line {}: {}", line, error),
+            Error::Record(record, error) => write!(f, "record {}: {}", record, error),
+            Error::Conversion(message) => write!(f, "{}", message),
+            Error::Patch(operation, error) => write!(f, "patch operation {}: {}", operation, error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error.as_ref()),
+            Error::Line(_, error) | Error::Record(_, error) | Error::Patch(_, error) => {
+                Some(error.as_ref())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(Arc::new(error))
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Syntax(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Syntax(message.to_string())
+    }
+}
@@ -0,0 +1,249 @@
+// a C API over the parser, built into the cdylib when the ffi feature is enabled.
+// `include/rust_practice_json_parser.h` is generated from this file by cbindgen (see
+// cbindgen.toml, which also exports `Value` to C as the opaque `JsonValue`), which is
+// why the items here carry `///` docs: they end up in the header.
+// No panic unwinds into C: every entry point catches it and reports a failure instead.
+use crate::serializer::{self, SerializeOptions};
+use crate::{Value, diagnostic};
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The kind of a value, as returned by `json_type`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonType {
+    JsonNull,
+    JsonBool,
+    JsonNumber,
+    JsonString,
+    JsonArray,
+    JsonObject,
+}
+
+// runs `f`, turning a panic into `fallback`
+fn guard<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(fallback)
+}
+
+// a string for C to free with json_string_free; interior NULs can't be represented, so
+// they are dropped
+fn to_c_string(s: String) -> *mut c_char {
+    let s = CString::new(s).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|&b| b != 0);
+        CString::new(bytes).expect("NULs were removed")
+    });
+    s.into_raw()
+}
+
+/// Parses `length` bytes of UTF-8 at `text`. Returns a document to release with
+/// `json_free`, or NULL on failure, in which case `*error` (when `error` is not NULL)
+/// receives a message with the line and column, to release with `json_string_free`.
+///
+/// # Safety
+///
+/// `text` must point to `length` readable bytes, and `error` must be NULL or valid for
+/// writing a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_parse(
+    text: *const c_char,
+    length: usize,
+    error: *mut *mut c_char,
+) -> *mut Value {
+    let result = guard(Err("Parser panicked".to_string()), || {
+        if text.is_null() {
+            return Err("Input is NULL".to_string());
+        }
+        // SAFETY: the caller guarantees `length` readable bytes at `text`
+        let bytes = unsafe { std::slice::from_raw_parts(text.cast::<u8>(), length) };
+        let source = std::str::from_utf8(bytes).map_err(|_| "Invalid UTF-8 in input")?;
+        diagnostic::parse(source).map_err(|d| diagnostic::to_text(&[d], source, "input"))
+    });
+    match result {
+        Ok(value) => Box::into_raw(Box::new(value)),
+        Err(message) => {
+            if !error.is_null() {
+                // SAFETY: the caller guarantees `error` is valid for writing
+                unsafe { *error = to_c_string(message) };
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a document returned by `json_parse`. NULL is ignored.
+///
+/// # Safety
+///
+/// `value` must be NULL or a pointer returned by `json_parse` that hasn't been freed;
+/// values borrowed from it with `json_pointer` are invalid afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_free(value: *mut Value) {
+    if !value.is_null() {
+        // SAFETY: the caller guarantees `value` came from Box::into_raw in json_parse
+        guard((), || drop(unsafe { Box::from_raw(value) }));
+    }
+}
+
+/// Looks up a JSON Pointer such as "/users/0/name". Returns a value borrowed from
+/// `value`, valid until its document is freed, or NULL when nothing is there.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value, and `pointer` NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_pointer(value: *const Value, pointer: *const c_char) -> *const Value {
+    if value.is_null() || pointer.is_null() {
+        return ptr::null();
+    }
+    guard(ptr::null(), || {
+        // SAFETY: both pointers were checked for NULL and the caller guarantees the rest
+        let (value, pointer) = unsafe { (&*value, CStr::from_ptr(pointer)) };
+        let Ok(pointer) = pointer.to_str() else {
+            return ptr::null();
+        };
+        value
+            .pointer(pointer)
+            .map_or(ptr::null(), |found| found as *const Value)
+    })
+}
+
+/// The kind of `value`; NULL reports `JsonNull`.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_type(value: *const Value) -> JsonType {
+    if value.is_null() {
+        return JsonType::JsonNull;
+    }
+    // SAFETY: checked for NULL; the caller guarantees it's live
+    match unsafe { &*value } {
+        Value::Null => JsonType::JsonNull,
+        Value::Bool(_) => JsonType::JsonBool,
+        Value::Number(_) => JsonType::JsonNumber,
+        Value::String(_) => JsonType::JsonString,
+        Value::Array(_) => JsonType::JsonArray,
+        Value::Object(_) => JsonType::JsonObject,
+        Value::Raw(raw) => match raw.parse() {
+            Ok(Value::Bool(_)) => JsonType::JsonBool,
+            Ok(Value::Number(_)) => JsonType::JsonNumber,
+            Ok(Value::String(_)) => JsonType::JsonString,
+            Ok(Value::Array(_)) => JsonType::JsonArray,
+            Ok(Value::Object(_)) => JsonType::JsonObject,
+            _ => JsonType::JsonNull,
+        },
+    }
+}
+
+/// The number of elements of an array or members of an object, otherwise 0.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_length(value: *const Value) -> usize {
+    if value.is_null() {
+        return 0;
+    }
+    // SAFETY: checked for NULL; the caller guarantees it's live
+    match unsafe { &*value } {
+        Value::Array(elements) => elements.len(),
+        Value::Object(properties) => properties.len(),
+        _ => 0,
+    }
+}
+
+/// Stores a boolean in `*out` and returns true, or returns false for any other value.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value, and `out` valid for writing a bool.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_as_bool(value: *const Value, out: *mut bool) -> bool {
+    if value.is_null() || out.is_null() {
+        return false;
+    }
+    // SAFETY: checked for NULL; the caller guarantees the rest
+    match unsafe { (*value).as_bool() } {
+        Some(b) => {
+            unsafe { *out = b };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stores a number in `*out` and returns true, or returns false for any other value.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value, and `out` valid for writing a double.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_as_double(value: *const Value, out: *mut f64) -> bool {
+    if value.is_null() || out.is_null() {
+        return false;
+    }
+    // SAFETY: checked for NULL; the caller guarantees the rest
+    match unsafe { (*value).as_f64() } {
+        Some(n) => {
+            unsafe { *out = n };
+            true
+        }
+        None => false,
+    }
+}
+
+/// A copy of a string value, to release with `json_string_free`, or NULL for any other
+/// value.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_as_string(value: *const Value) -> *mut c_char {
+    if value.is_null() {
+        return ptr::null_mut();
+    }
+    guard(ptr::null_mut(), || {
+        // SAFETY: checked for NULL; the caller guarantees it's live
+        match unsafe { (*value).as_str() } {
+            Some(s) => to_c_string(s.to_string()),
+            None => ptr::null_mut(),
+        }
+    })
+}
+
+/// Serializes `value`, two-space indented when `pretty` is true. Returns a string to
+/// release with `json_string_free`, or NULL when `value` is NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_serialize(value: *const Value, pretty: bool) -> *mut c_char {
+    if value.is_null() {
+        return ptr::null_mut();
+    }
+    guard(ptr::null_mut(), || {
+        // SAFETY: checked for NULL; the caller guarantees it's live
+        let value = unsafe { &*value };
+        let options = SerializeOptions::new().with_pretty(pretty);
+        to_c_string(serializer::to_string_with(value, &options))
+    })
+}
+
+/// Releases a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that hasn't been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the caller guarantees `s` came from CString::into_raw
+        guard((), || drop(unsafe { CString::from_raw(s) }));
+    }
+}
@@ -0,0 +1,69 @@
+// reading and writing documents on disk; writes never leave a half-written file behind
+use crate::serializer::{self, SerializeOptions, Style};
+use crate::{Error, Value, streaming};
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// distinguishes temporary files of concurrent writes from the same process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl Value {
+    // streams the file through the parser; compressed files are decoded when the gzip or
+    // zstd feature is enabled
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Value, Error> {
+        streaming::parse_reader(BufReader::new(File::open(path)?))
+    }
+
+    // writes to a temporary file next to `path`, flushes it to disk and renames it over
+    // `path`, so after a crash the file holds either the old document or the new one
+    pub fn to_file<P: AsRef<Path>>(&self, path: P, style: &Style) -> Result<(), Error> {
+        let path = path.as_ref();
+        let text = serializer::to_string_with(self, &SerializeOptions::new().with_style(*style));
+        let temp = temp_path(path);
+        let result = write_and_rename(&temp, path, text.as_bytes());
+        if result.is_err() {
+            let _ = fs::remove_file(&temp); // may not exist if creating it failed
+        }
+        result
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let unique = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), unique))
+}
+
+fn write_and_rename(temp: &Path, path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    let mut file = File::options().write(true).create_new(true).open(temp)?;
+    file.write_all(bytes)?;
+    // keep the permissions of the file being replaced, e.g. a config readable only by its owner
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()?;
+    drop(file);
+    fs::rename(temp, path)?;
+    sync_parent(path);
+    Ok(())
+}
+
+// makes the rename itself durable; best effort, since not every platform or
+// filesystem lets a directory be opened and synced
+fn sync_parent(path: &Path) {
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(directory) = File::open(parent) {
+            let _ = directory.sync_all();
+        }
+    }
+}
@@ -0,0 +1,541 @@
+// a small jq-like filter language, compiled once and applied to many values
+//
+//   .users[] | select(.age >= 18) | {name, city: .address.city}
+//
+// supported: . .foo .[0] .["key"] .[] | , [ ] { } map() select() length keys not empty
+// literals, == != < <= > >=, and, or
+use crate::value::mismatch;
+use crate::{Error, Map, Number, Value};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::vec::IntoIter;
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    expr: Expr,
+}
+
+impl Filter {
+    // filter text → tokens → expression tree, the same pipeline as the JSON parser
+    pub fn compile(source: &str) -> Result<Filter, Error> {
+        let tokens = tokenize(source)?;
+        let mut iter = tokens.into_iter().peekable();
+        let expr = parse_pipe(&mut iter)?;
+        if let Some(token) = iter.next() {
+            return Err(format!("Unexpected trailing token in filter: {:?}", token).into());
+        }
+        Ok(Filter { expr })
+    }
+
+    // like jq, a filter can produce any number of outputs
+    pub fn apply(&self, input: &Value) -> Result<Vec<Value>, Error> {
+        eval(&self.expr, input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Field(String), // .name
+    Ident(String),
+    String(String),
+    Number(Number),
+    OpenBracket,
+    CloseBracket,
+    OpenBrace,
+    CloseBrace,
+    OpenParen,
+    CloseParen,
+    Pipe,
+    Comma,
+    Colon,
+    Compare(Comparison),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Identity,
+    Literal(Value),
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>), // the index is evaluated against the original input
+    Iterate(Box<Expr>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(Expr, Expr)>),
+    Map(Box<Expr>),
+    Select(Box<Expr>),
+    Length,
+    Keys,
+    Not,
+    Empty,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    let mut iter = source.chars().peekable();
+    let mut tokens = Vec::new();
+    while let Some(&c) = iter.peek() {
+        if c.is_whitespace() {
+            iter.next();
+            continue;
+        }
+        let token = match c {
+            '.' => {
+                iter.next();
+                match iter.peek() {
+                    Some(&c) if is_ident_start(c) => Token::Field(take_ident(&mut iter)),
+                    _ => Token::Dot,
+                }
+            }
+            '"' => Token::String(take_string(&mut iter)?),
+            '0'..='9' => Token::Number(take_number(&mut iter)?),
+            c if is_ident_start(c) => Token::Ident(take_ident(&mut iter)),
+            '=' | '!' | '<' | '>' => Token::Compare(take_comparison(&mut iter)?),
+            _ => {
+                iter.next();
+                match c {
+                    '[' => Token::OpenBracket,
+                    ']' => Token::CloseBracket,
+                    '{' => Token::OpenBrace,
+                    '}' => Token::CloseBrace,
+                    '(' => Token::OpenParen,
+                    ')' => Token::CloseParen,
+                    '|' => Token::Pipe,
+                    ',' => Token::Comma,
+                    ':' => Token::Colon,
+                    _ => return Err(format!("Unexpected character in filter: '{}'", c).into()),
+                }
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn take_ident(iter: &mut Peekable<Chars>) -> String {
+    let mut ident = String::new();
+    while let Some(&c) = iter.peek() {
+        if !(c.is_alphanumeric() || c == '_') {
+            break;
+        }
+        ident.push(c);
+        iter.next();
+    }
+    ident
+}
+
+fn take_string(iter: &mut Peekable<Chars>) -> Result<String, Error> {
+    iter.next(); // consume opening quote
+    let mut string = String::new();
+    for c in iter.by_ref() {
+        if c == '"' {
+            return Ok(string);
+        }
+        string.push(c);
+    }
+    Err("Unterminated string in filter".into())
+}
+
+fn take_number(iter: &mut Peekable<Chars>) -> Result<Number, Error> {
+    let mut number = String::new();
+    while let Some(&c) = iter.peek() {
+        if !(c.is_ascii_digit() || c == '.') {
+            break;
+        }
+        number.push(c);
+        iter.next();
+    }
+    number
+        .parse()
+        .map_err(|_| format!("Invalid number in filter: '{}'", number).into())
+}
+
+fn take_comparison(iter: &mut Peekable<Chars>) -> Result<Comparison, Error> {
+    let first = iter.next().unwrap(); // the caller peeked it
+    let equals = iter.next_if_eq(&'=').is_some();
+    match (first, equals) {
+        ('=', true) => Ok(Comparison::Eq),
+        ('!', true) => Ok(Comparison::Ne),
+        ('<', true) => Ok(Comparison::Le),
+        ('<', false) => Ok(Comparison::Lt),
+        ('>', true) => Ok(Comparison::Ge),
+        ('>', false) => Ok(Comparison::Gt),
+        _ => Err(format!("Unexpected character in filter: '{}'", first).into()),
+    }
+}
+
+type Tokens = Peekable<IntoIter<Token>>;
+
+fn consume(iter: &mut Tokens, expected: Token) -> Result<(), Error> {
+    match iter.next() {
+        Some(token) if token == expected => Ok(()),
+        Some(token) => {
+            Err(format!("Expected {:?} in filter, but found {:?}", expected, token).into())
+        }
+        None => Err("Unexpected end of filter".into()),
+    }
+}
+
+// pipe binds loosest: a, b | c is (a, b) | c
+fn parse_pipe(iter: &mut Tokens) -> Result<Expr, Error> {
+    let left = parse_comma(iter)?;
+    if iter.next_if_eq(&Token::Pipe).is_some() {
+        let right = parse_pipe(iter)?;
+        return Ok(Expr::Pipe(Box::new(left), Box::new(right)));
+    }
+    Ok(left)
+}
+
+fn parse_comma(iter: &mut Tokens) -> Result<Expr, Error> {
+    let mut left = parse_or(iter)?;
+    while iter.next_if_eq(&Token::Comma).is_some() {
+        let right = parse_or(iter)?;
+        left = Expr::Comma(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_or(iter: &mut Tokens) -> Result<Expr, Error> {
+    let mut left = parse_and(iter)?;
+    while iter.next_if_eq(&Token::Ident("or".to_string())).is_some() {
+        let right = parse_and(iter)?;
+        left = Expr::Or(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_and(iter: &mut Tokens) -> Result<Expr, Error> {
+    let mut left = parse_comparison(iter)?;
+    while iter.next_if_eq(&Token::Ident("and".to_string())).is_some() {
+        let right = parse_comparison(iter)?;
+        left = Expr::And(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_comparison(iter: &mut Tokens) -> Result<Expr, Error> {
+    let left = parse_postfix(iter)?;
+    if let Some(Token::Compare(comparison)) =
+        iter.next_if(|token| matches!(token, Token::Compare(_)))
+    {
+        let right = parse_postfix(iter)?;
+        return Ok(Expr::Compare(comparison, Box::new(left), Box::new(right)));
+    }
+    Ok(left)
+}
+
+// a term followed by any number of .name, [index] and []
+fn parse_postfix(iter: &mut Tokens) -> Result<Expr, Error> {
+    let mut expr = parse_term(iter)?;
+    loop {
+        if let Some(Token::Field(name)) = iter.next_if(|token| matches!(token, Token::Field(_))) {
+            expr = Expr::Field(Box::new(expr), name);
+        } else if iter.next_if_eq(&Token::OpenBracket).is_some() {
+            if iter.next_if_eq(&Token::CloseBracket).is_some() {
+                expr = Expr::Iterate(Box::new(expr));
+            } else {
+                let index = parse_pipe(iter)?;
+                consume(iter, Token::CloseBracket)?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            }
+        } else {
+            return Ok(expr);
+        }
+    }
+}
+
+fn parse_term(iter: &mut Tokens) -> Result<Expr, Error> {
+    let Some(token) = iter.next() else {
+        return Err("Unexpected end of filter".into());
+    };
+    match token {
+        Token::Dot => Ok(Expr::Identity),
+        Token::Field(name) => Ok(Expr::Field(Box::new(Expr::Identity), name)),
+        Token::String(string) => Ok(Expr::Literal(Value::String(string))),
+        Token::Number(number) => Ok(Expr::Literal(Value::Number(number))),
+        Token::OpenParen => {
+            let expr = parse_pipe(iter)?;
+            consume(iter, Token::CloseParen)?;
+            Ok(expr)
+        }
+        Token::OpenBracket => {
+            if iter.next_if_eq(&Token::CloseBracket).is_some() {
+                return Ok(Expr::Array(None));
+            }
+            let expr = parse_pipe(iter)?;
+            consume(iter, Token::CloseBracket)?;
+            Ok(Expr::Array(Some(Box::new(expr))))
+        }
+        Token::OpenBrace => parse_object(iter),
+        Token::Ident(name) => parse_builtin(iter, &name),
+        token => Err(format!("Unexpected token in filter: {:?}", token).into()),
+    }
+}
+
+fn parse_builtin(iter: &mut Tokens, name: &str) -> Result<Expr, Error> {
+    match name {
+        "true" => Ok(Expr::Literal(Value::Bool(true))),
+        "false" => Ok(Expr::Literal(Value::Bool(false))),
+        "null" => Ok(Expr::Literal(Value::Null)),
+        "length" => Ok(Expr::Length),
+        "keys" => Ok(Expr::Keys),
+        "not" => Ok(Expr::Not),
+        "empty" => Ok(Expr::Empty),
+        "map" | "select" => {
+            consume(iter, Token::OpenParen)?;
+            let argument = Box::new(parse_pipe(iter)?);
+            consume(iter, Token::CloseParen)?;
+            Ok(match name {
+                "map" => Expr::Map(argument),
+                _ => Expr::Select(argument),
+            })
+        }
+        _ => Err(format!("Unknown function in filter: '{}'", name).into()),
+    }
+}
+
+// {a, "b": .x, (.k): .v} — values bind tighter than ',' so pipes need parentheses
+fn parse_object(iter: &mut Tokens) -> Result<Expr, Error> {
+    let mut entries = Vec::new();
+    if iter.next_if_eq(&Token::CloseBrace).is_some() {
+        return Ok(Expr::Object(entries));
+    }
+    loop {
+        let (key, shorthand) = match iter.next() {
+            Some(Token::Ident(name)) => (Expr::Literal(Value::String(name.clone())), Some(name)),
+            Some(Token::String(name)) => (Expr::Literal(Value::String(name.clone())), Some(name)),
+            Some(Token::OpenParen) => {
+                let key = parse_pipe(iter)?;
+                consume(iter, Token::CloseParen)?;
+                (key, None)
+            }
+            Some(token) => {
+                return Err(format!("Unexpected token in filter object: {:?}", token).into());
+            }
+            None => return Err("Unexpected end of filter".into()),
+        };
+        let value = if iter.next_if_eq(&Token::Colon).is_some() {
+            parse_or(iter)?
+        } else if let Some(name) = shorthand {
+            Expr::Field(Box::new(Expr::Identity), name) // {name} means {name: .name}
+        } else {
+            return Err("Expected ':' after computed key in filter object".into());
+        };
+        entries.push((key, value));
+        match iter.next() {
+            Some(Token::Comma) => continue,
+            Some(Token::CloseBrace) => return Ok(Expr::Object(entries)),
+            _ => return Err("Expected ',' or '}' in filter object".into()),
+        }
+    }
+}
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>, Error> {
+    match expr {
+        Expr::Identity => Ok(vec![input.clone()]),
+        Expr::Literal(value) => Ok(vec![value.clone()]),
+        Expr::Field(target, name) => eval(target, input)?
+            .iter()
+            .map(|value| field(value, name))
+            .collect(),
+        Expr::Index(target, index) => {
+            let mut outputs = Vec::new();
+            for value in eval(target, input)? {
+                for index in eval(index, input)? {
+                    outputs.push(index_value(&value, &index)?);
+                }
+            }
+            Ok(outputs)
+        }
+        Expr::Iterate(target) => {
+            let mut outputs = Vec::new();
+            for value in eval(target, input)? {
+                match value {
+                    Value::Array(elements) => outputs.extend(elements),
+                    Value::Object(properties) => {
+                        outputs.extend(properties.into_iter().map(|(_, v)| v))
+                    }
+                    _ => return Err(cannot("iterate over", &value)),
+                }
+            }
+            Ok(outputs)
+        }
+        Expr::Pipe(left, right) => {
+            let mut outputs = Vec::new();
+            for value in eval(left, input)? {
+                outputs.extend(eval(right, &value)?);
+            }
+            Ok(outputs)
+        }
+        Expr::Comma(left, right) => {
+            let mut outputs = eval(left, input)?;
+            outputs.extend(eval(right, input)?);
+            Ok(outputs)
+        }
+        Expr::Compare(comparison, left, right) => {
+            let mut outputs = Vec::new();
+            for a in eval(left, input)? {
+                for b in eval(right, input)? {
+                    outputs.push(Value::Bool(compare(*comparison, &a, &b)?));
+                }
+            }
+            Ok(outputs)
+        }
+        Expr::And(left, right) => logic(left, right, input, |a, b| a && b),
+        Expr::Or(left, right) => logic(left, right, input, |a, b| a || b),
+        Expr::Array(None) => Ok(vec![Value::array()]),
+        Expr::Array(Some(inner)) => Ok(vec![Value::Array(eval(inner, input)?)]),
+        Expr::Object(entries) => {
+            // each entry can produce several values, so build every combination
+            let mut objects = vec![Map::new()];
+            for (key, value) in entries {
+                let keys = eval(key, input)?;
+                let values = eval(value, input)?;
+                let mut next = Vec::new();
+                for object in &objects {
+                    for key in &keys {
+                        let Value::String(key) = key else {
+                            return Err(cannot("use as an object key", key));
+                        };
+                        for value in &values {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), value.clone());
+                            next.push(object);
+                        }
+                    }
+                }
+                objects = next;
+            }
+            Ok(objects.into_iter().map(Value::Object).collect())
+        }
+        Expr::Map(inner) => {
+            let iterate = Expr::Iterate(Box::new(Expr::Identity));
+            let mut mapped = Vec::new();
+            for value in eval(&iterate, input)? {
+                mapped.extend(eval(inner, &value)?);
+            }
+            Ok(vec![Value::Array(mapped)])
+        }
+        Expr::Select(condition) => Ok(eval(condition, input)?
+            .iter()
+            .filter(|value| truthy(value))
+            .map(|_| input.clone())
+            .collect()),
+        Expr::Length => Ok(vec![length(input)?]),
+        Expr::Keys => {
+            let properties = input.as_object().ok_or_else(|| mismatch("object", input))?;
+            let mut keys: Vec<&String> = properties.keys().collect();
+            keys.sort();
+            Ok(vec![keys.into_iter().map(String::as_str).collect()])
+        }
+        Expr::Not => Ok(vec![Value::Bool(!truthy(input))]),
+        Expr::Empty => Ok(Vec::new()),
+    }
+}
+
+// missing keys and null inputs give null, as in jq
+fn field(value: &Value, name: &str) -> Result<Value, Error> {
+    match value {
+        Value::Object(properties) => Ok(properties.get(name).cloned().unwrap_or_default()),
+        Value::Null => Ok(Value::Null),
+        _ => Err(Error::Conversion(format!(
+            "cannot index {} with \"{}\"",
+            value.type_name(),
+            name
+        ))),
+    }
+}
+
+fn index_value(value: &Value, index: &Value) -> Result<Value, Error> {
+    match (value, index) {
+        (_, Value::String(name)) => field(value, name),
+        (Value::Array(elements), Value::Number(_)) => {
+            let element = index.as_u64().and_then(|i| elements.get(i as usize));
+            Ok(element.cloned().unwrap_or_default())
+        }
+        (Value::Null, Value::Number(_)) => Ok(Value::Null),
+        _ => Err(Error::Conversion(format!(
+            "cannot index {} with {}",
+            value.type_name(),
+            index.type_name()
+        ))),
+    }
+}
+
+fn length(value: &Value) -> Result<Value, Error> {
+    match value {
+        Value::Object(properties) => Ok(properties.len().into()),
+        Value::Array(elements) => Ok(elements.len().into()),
+        Value::String(string) => Ok(string.chars().count().into()),
+        Value::Number(Number::I64(n)) => Ok(n.unsigned_abs().into()),
+        Value::Number(number) => Ok(number.as_f64().abs().into()),
+        Value::Null => Ok(0.into()),
+        Value::Bool(_) | Value::Raw(_) => Err(cannot("take the length of", value)),
+    }
+}
+
+fn compare(comparison: Comparison, a: &Value, b: &Value) -> Result<bool, Error> {
+    let ordering = match comparison {
+        Comparison::Eq => return Ok(a == b),
+        Comparison::Ne => return Ok(a != b),
+        _ => match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            _ => None,
+        },
+    };
+    let Some(ordering) = ordering else {
+        return Err(Error::Conversion(format!(
+            "cannot compare {} with {}",
+            a.type_name(),
+            b.type_name()
+        )));
+    };
+    Ok(match comparison {
+        Comparison::Lt => ordering.is_lt(),
+        Comparison::Le => ordering.is_le(),
+        Comparison::Gt => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    })
+}
+
+fn logic(
+    left: &Expr,
+    right: &Expr,
+    input: &Value,
+    op: fn(bool, bool) -> bool,
+) -> Result<Vec<Value>, Error> {
+    let mut outputs = Vec::new();
+    for a in eval(left, input)? {
+        for b in eval(right, input)? {
+            outputs.push(Value::Bool(op(truthy(&a), truthy(&b))));
+        }
+    }
+    Ok(outputs)
+}
+
+// only false and null are falsy
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Bool(false) | Value::Null)
+}
+
+fn cannot(action: &str, value: &Value) -> Error {
+    Error::Conversion(format!("cannot {} {}", action, value.type_name()))
+}
@@ -0,0 +1,158 @@
+// nested values ⇄ single-level objects keyed by paths like "a.b[0].c"
+use crate::path::{PathSegment, step};
+use crate::value::mismatch;
+use crate::{Error, Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayStyle {
+    Brackets,  // a.b[0].c
+    Separator, // a.b.0.c
+}
+
+#[derive(Debug, Clone)]
+pub struct FlattenOptions {
+    separator: String,
+    array_style: ArrayStyle,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        FlattenOptions {
+            separator: ".".to_string(),
+            array_style: ArrayStyle::Brackets,
+        }
+    }
+}
+
+impl FlattenOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    pub fn with_array_style(mut self, array_style: ArrayStyle) -> Self {
+        self.array_style = array_style;
+        self
+    }
+}
+
+impl Value {
+    pub fn flatten(&self) -> Value {
+        self.flatten_with(&FlattenOptions::default())
+    }
+
+    // empty objects and arrays are kept as leaves so unflatten can restore them
+    pub fn flatten_with(&self, options: &FlattenOptions) -> Value {
+        let mut flat = Map::new();
+        flatten_into(self, &mut String::new(), options, &mut flat);
+        Value::Object(flat)
+    }
+
+    pub fn unflatten(&self) -> Result<Value, Error> {
+        self.unflatten_with(&FlattenOptions::default())
+    }
+
+    // with ArrayStyle::Separator, every all-digit segment is read back as an array index
+    pub fn unflatten_with(&self, options: &FlattenOptions) -> Result<Value, Error> {
+        let flat = self.as_object().ok_or_else(|| mismatch("object", self))?;
+        let mut root = Value::Null;
+        for (path, value) in flat {
+            let mut node = &mut root;
+            for segment in split_path(path, options) {
+                node = step(node, &segment).map_err(|_| conflict(path))?;
+            }
+            if !node.is_null() {
+                return Err(conflict(path));
+            }
+            *node = value.clone();
+        }
+        Ok(root)
+    }
+}
+
+fn flatten_into(value: &Value, path: &mut String, options: &FlattenOptions, flat: &mut Map) {
+    let start = path.len();
+    match value {
+        Value::Object(properties) if !properties.is_empty() => {
+            for (key, child) in properties {
+                if !path.is_empty() {
+                    path.push_str(&options.separator);
+                }
+                path.push_str(key);
+                flatten_into(child, path, options, flat);
+                path.truncate(start);
+            }
+        }
+        Value::Array(elements) if !elements.is_empty() => {
+            for (i, child) in elements.iter().enumerate() {
+                match options.array_style {
+                    ArrayStyle::Brackets => path.push_str(&format!("[{}]", i)),
+                    ArrayStyle::Separator if path.is_empty() => path.push_str(&i.to_string()),
+                    ArrayStyle::Separator => path.push_str(&format!("{}{}", options.separator, i)),
+                }
+                flatten_into(child, path, options, flat);
+                path.truncate(start);
+            }
+        }
+        _ => {
+            flat.insert(path.clone(), value.clone());
+        }
+    }
+}
+
+fn split_path(path: &str, options: &FlattenOptions) -> Vec<PathSegment> {
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return segments;
+    }
+    for part in path.split(options.separator.as_str()) {
+        match options.array_style {
+            ArrayStyle::Separator => match part.parse() {
+                Ok(index) if part.bytes().all(|b| b.is_ascii_digit()) => {
+                    segments.push(PathSegment::Index(index))
+                }
+                _ => segments.push(PathSegment::Key(part.to_string())),
+            },
+            ArrayStyle::Brackets => match split_brackets(part) {
+                Some((key, indices)) => {
+                    if !key.is_empty() {
+                        segments.push(PathSegment::Key(key.to_string()));
+                    }
+                    segments.extend(indices.into_iter().map(PathSegment::Index));
+                }
+                None => segments.push(PathSegment::Key(part.to_string())),
+            },
+        }
+    }
+    segments
+}
+
+// "name[0][1]" → ("name", [0, 1]); None when the brackets aren't plain indices
+fn split_brackets(part: &str) -> Option<(&str, Vec<usize>)> {
+    let Some(open) = part.find('[') else {
+        return Some((part, Vec::new()));
+    };
+    let mut indices = Vec::new();
+    let mut rest = &part[open..];
+    while !rest.is_empty() {
+        let close = rest.find(']')?;
+        let digits = rest.get(1..close)?;
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        indices.push(digits.parse().ok()?);
+        rest = &rest[close + 1..];
+        if !rest.is_empty() && !rest.starts_with('[') {
+            return None;
+        }
+    }
+    Some((&part[..open], indices))
+}
+
+fn conflict(path: &str) -> Error {
+    Error::Conversion(format!("path '{}' conflicts with an earlier path", path))
+}
@@ -0,0 +1,198 @@
+// re-emitting JSON text straight from the token stream, without building a Value, so
+// memory stays flat however big the input is; the input is fully validated on the way
+use crate::sax::ContentHandler;
+use crate::serializer::{KeyQuoting, Style, is_identifier};
+use crate::streaming::StreamingParser;
+use crate::{Error, Number};
+use std::io::{self, BufRead, BufWriter, ErrorKind, Write};
+
+// copies `reader` to `writer` with all insignificant whitespace removed; strings and
+// numbers are copied exactly as written. On a syntax error the output so far has been
+// written, so write to a temporary file when that matters.
+pub fn minify<R: BufRead, W: Write>(reader: R, writer: W) -> Result<(), Error> {
+    reformat(reader, writer, &Style::compact())
+}
+
+// like minify, but lays the output out in `style`: indentation, spacing, key quoting
+// and line endings. Keys stay in input order, since sorting them would mean holding a
+// whole object in memory, so Style::with_sort_keys has no effect here.
+pub fn reformat<R: BufRead, W: Write>(reader: R, writer: W, style: &Style) -> Result<(), Error> {
+    let emitter = Emitter {
+        writer: BufWriter::new(writer),
+        style: *style,
+        stack: Vec::new(),
+        error: None,
+    };
+    let parser = StreamingParser::new(emitter).with_raw_numbers(true);
+    let mut emitter = drive(reader, parser)?;
+    if style.final_newline {
+        emitter.writer.write_all(style.newline_str().as_bytes())?;
+    }
+    emitter.writer.flush()?;
+    Ok(())
+}
+
+// like streaming::feed_reader, but stops as soon as the handler fails to write
+fn drive<R: BufRead, W: Write>(
+    mut reader: R,
+    mut parser: StreamingParser<Emitter<W>>,
+) -> Result<Emitter<W>, Error> {
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok([]) => break,
+            Ok(chunk) => chunk,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        parser.feed(chunk)?;
+        if let Some(error) = parser.handler_mut().error.take() {
+            return Err(error.into());
+        }
+        let consumed = chunk.len();
+        reader.consume(consumed);
+    }
+    let mut emitter = parser.finish()?;
+    match emitter.error.take() {
+        Some(error) => Err(error.into()),
+        None => Ok(emitter),
+    }
+}
+
+struct Emitter<W: Write> {
+    writer: W,
+    style: Style,
+    stack: Vec<Frame>,
+    error: Option<io::Error>, // handler callbacks can't fail, so the first error waits here
+}
+
+struct Frame {
+    object: bool,
+    count: usize, // members or elements written so far
+}
+
+impl<W: Write> Emitter<W> {
+    fn write(&mut self, text: &str) {
+        if self.error.is_none()
+            && let Err(e) = self.writer.write_all(text.as_bytes())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    // a line break and indentation for the current depth, when indenting
+    fn newline(&mut self) {
+        if self.style.indent.is_none() {
+            return;
+        }
+        self.write(self.style.newline_str());
+        for _ in 0..self.stack.len() {
+            self.write(self.style.indent_unit());
+        }
+    }
+
+    // what goes before a member's key or an array element: the comma, then the line
+    // break; neither is known to be needed until the first child shows up, which keeps
+    // empty containers as {} and []
+    fn begin_child(&mut self) {
+        let count = self.stack.last().map_or(0, |frame| frame.count);
+        if count > 0 {
+            let space = self.style.space_after_comma && self.style.indent.is_none();
+            self.write(if space { ", " } else { "," });
+        }
+        self.newline();
+    }
+
+    fn before_value(&mut self) {
+        if self.stack.last().is_some_and(|frame| !frame.object) {
+            self.begin_child();
+        }
+    }
+
+    fn after_value(&mut self) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.count += 1;
+        }
+    }
+
+    fn open(&mut self, object: bool) {
+        self.before_value();
+        self.write(if object { "{" } else { "[" });
+        self.stack.push(Frame { object, count: 0 });
+    }
+
+    fn close(&mut self) {
+        if let Some(frame) = self.stack.pop() {
+            if frame.count > 0 {
+                self.newline();
+            }
+            self.write(if frame.object { "}" } else { "]" });
+        }
+        self.after_value();
+    }
+
+    fn scalar(&mut self, text: &str) {
+        self.before_value();
+        self.write(text);
+        self.after_value();
+    }
+
+    // the streaming parser hands strings over as written between the quotes, escapes
+    // included, so they go back out unchanged
+    fn quoted(&mut self, text: &str) {
+        self.write("\"");
+        self.write(text);
+        self.write("\"");
+    }
+}
+
+impl<W: Write> ContentHandler for Emitter<W> {
+    fn on_object_start(&mut self) {
+        self.open(true);
+    }
+
+    fn on_object_end(&mut self) {
+        self.close();
+    }
+
+    fn on_array_start(&mut self) {
+        self.open(false);
+    }
+
+    fn on_array_end(&mut self) {
+        self.close();
+    }
+
+    fn on_key(&mut self, key: &str) {
+        self.begin_child();
+        match self.style.key_quoting {
+            KeyQuoting::WhenNeeded if is_identifier(key) => self.write(key),
+            _ => self.quoted(key),
+        }
+        self.write(if self.style.space_after_colon {
+            ": "
+        } else {
+            ":"
+        });
+    }
+
+    fn on_string(&mut self, value: &str) {
+        self.before_value();
+        self.quoted(value);
+        self.after_value();
+    }
+
+    fn on_number(&mut self, value: Number) {
+        match value.as_raw() {
+            Some(text) => self.scalar(text),
+            None => self.scalar(&value.to_string()),
+        }
+    }
+
+    fn on_bool(&mut self, value: bool) {
+        self.scalar(if value { "true" } else { "false" });
+    }
+
+    fn on_null(&mut self) {
+        self.scalar("null");
+    }
+}
@@ -0,0 +1,204 @@
+// semantic token classification for editors: spans of source text classified LSP-style.
+// Unlike the lexer this never fails, since an editor highlights text while it's being
+// typed: unterminated strings and comments run to the end of their line or the text,
+// and characters that start no token are skipped. Comments (// and /* */) and unquoted
+// JSON5-style keys are recognized as well.
+
+// what a span is; the order matches LSP_LEGEND
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Class {
+    Key,
+    String,
+    Number,
+    Keyword, // true, false and null
+    Punctuation,
+    Comment,
+}
+
+// a span of the text by byte offsets, start inclusive and end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub class: Class,
+    pub start: usize,
+    pub end: usize,
+}
+
+// the token types legend to register with an LSP client, indexed by Class as u32
+pub const LSP_LEGEND: [&str; 6] = [
+    "property", "string", "number", "keyword", "operator", "comment",
+];
+
+// in order, without overlaps
+pub fn classify(text: &str) -> Vec<Span> {
+    let mut scanner = Scanner {
+        text,
+        bytes: text.as_bytes(),
+        position: 0,
+        spans: Vec::new(),
+    };
+    scanner.run();
+    scanner.spans
+}
+
+// the spans as LSP's semanticTokens data: five numbers per token (line delta, start
+// delta, length, type, modifiers), with positions in UTF-16 code units. Spans covering
+// several lines, like block comments, are split at line breaks.
+pub fn to_lsp_data(text: &str, spans: &[Span]) -> Vec<u32> {
+    let mut data = Vec::with_capacity(spans.len() * 5);
+    let (mut line, mut line_start) = (0u32, 0usize); // the line being scanned
+    let (mut last_line, mut last_start) = (0u32, 0u32); // the previous token's position
+    let mut scanned = 0;
+    for span in spans {
+        // move to the line the span starts on
+        for (i, c) in text[scanned..span.start].char_indices() {
+            if c == '\n' {
+                line += 1;
+                line_start = scanned + i + 1;
+            }
+        }
+        let mut start = span.start;
+        for piece in text[span.start..span.end].split_inclusive('\n') {
+            let trimmed = piece.trim_end_matches(['\n', '\r']);
+            let column = utf16_len(&text[line_start..start]);
+            let length = utf16_len(trimmed);
+            if length > 0 {
+                let delta_line = line - last_line;
+                let delta_start = if delta_line == 0 {
+                    column - last_start
+                } else {
+                    column
+                };
+                data.extend([delta_line, delta_start, length, span.class as u32, 0]);
+                (last_line, last_start) = (line, column);
+            }
+            start += piece.len();
+            if piece.ends_with('\n') {
+                line += 1;
+                line_start = start;
+            }
+        }
+        scanned = span.end;
+    }
+    data
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.chars().map(|c| c.len_utf16() as u32).sum()
+}
+
+struct Scanner<'t> {
+    text: &'t str,
+    bytes: &'t [u8],
+    position: usize,
+    spans: Vec<Span>,
+}
+
+impl Scanner<'_> {
+    fn push(&mut self, class: Class, start: usize) {
+        self.spans.push(Span {
+            class,
+            start,
+            end: self.position,
+        });
+    }
+
+    fn run(&mut self) {
+        while let Some(&byte) = self.bytes.get(self.position) {
+            let start = self.position;
+            match byte {
+                b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                    self.position += 1;
+                    self.push(Class::Punctuation, start);
+                }
+                b'"' | b'\'' => {
+                    self.string(byte);
+                    let class = match self.followed_by_colon() {
+                        true => Class::Key,
+                        false => Class::String,
+                    };
+                    self.push(class, start);
+                }
+                b'/' if self.bytes.get(start + 1) == Some(&b'/') => {
+                    self.position = self.line_end();
+                    self.push(Class::Comment, start);
+                }
+                b'/' if self.bytes.get(start + 1) == Some(&b'*') => {
+                    self.position = match self.text[start + 2..].find("*/") {
+                        Some(end) => start + 2 + end + 2,
+                        None => self.text.len(),
+                    };
+                    self.push(Class::Comment, start);
+                }
+                b'-' | b'+' | b'.' | b'0'..=b'9' => {
+                    self.position += 1;
+                    while self.bytes.get(self.position).is_some_and(|&b| {
+                        b.is_ascii_alphanumeric() || b == b'.' || b == b'+' || b == b'-'
+                    }) {
+                        self.position += 1;
+                    }
+                    self.push(Class::Number, start);
+                }
+                b if b.is_ascii_alphabetic() || b == b'_' || b == b'$' => {
+                    while self
+                        .bytes
+                        .get(self.position)
+                        .is_some_and(|&b| b.is_ascii_alphanumeric() || b == b'_' || b == b'$')
+                    {
+                        self.position += 1;
+                    }
+                    match &self.text[start..self.position] {
+                        "true" | "false" | "null" => self.push(Class::Keyword, start),
+                        // NaN and Infinity, as JSON5 allows
+                        "NaN" | "Infinity" => self.push(Class::Number, start),
+                        _ if self.followed_by_colon() => self.push(Class::Key, start),
+                        _ => {}
+                    }
+                }
+                // whitespace, and anything that starts no token
+                _ => {
+                    let c = self.text[start..].chars().next().unwrap_or_default();
+                    self.position += c.len_utf8().max(1);
+                }
+            }
+        }
+    }
+
+    // up to and including the closing quote, or to the end of the line without one
+    fn string(&mut self, quote: u8) {
+        self.position += 1;
+        let end = self.line_end();
+        while self.position < end {
+            match self.bytes[self.position] {
+                b'\\' => self.position += 2,
+                b if b == quote => {
+                    self.position += 1;
+                    return;
+                }
+                _ => self.position += 1,
+            }
+        }
+        self.position = end;
+    }
+
+    fn line_end(&self) -> usize {
+        match self.text[self.position..].find(['\n', '\r']) {
+            Some(end) => self.position + end,
+            None => self.text.len(),
+        }
+    }
+
+    // whether the next thing after whitespace and comments is a colon
+    fn followed_by_colon(&self) -> bool {
+        let mut rest = &self.text[self.position..];
+        loop {
+            rest = rest.trim_start();
+            if let Some(comment) = rest.strip_prefix("//") {
+                rest = comment.find('\n').map_or("", |end| &comment[end..]);
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+            } else {
+                return rest.starts_with(':');
+            }
+        }
+    }
+}
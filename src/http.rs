@@ -0,0 +1,46 @@
+// fetch a document over HTTP(S) and parse the body as it arrives, without buffering it first
+use crate::{Error, Value, streaming};
+use std::io::{self, BufReader};
+
+// what we can decode: gzip and zstd go through `compression::Decoder` when their feature is on
+fn accept_encoding() -> Option<&'static str> {
+    match (cfg!(feature = "gzip"), cfg!(feature = "zstd")) {
+        (true, true) => Some("gzip, zstd"),
+        (true, false) => Some("gzip"),
+        (false, true) => Some("zstd"),
+        (false, false) => None, // the server then sends the body as is
+    }
+}
+
+pub fn parse_url(url: &str) -> Result<Value, Error> {
+    let mut request = ureq::get(url).set("Accept", "application/json");
+    if let Some(encodings) = accept_encoding() {
+        request = request.set("Accept-Encoding", encodings);
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            return Err(format!("HTTP {} {} from {}", code, response.status_text(), url).into());
+        }
+        Err(e) => return Err(io::Error::other(e).into()),
+    };
+
+    // the decoder recognises compressed bodies by their magic bytes, so the header only
+    // has to rule out encodings it doesn't know
+    match response.header("Content-Encoding").map(str::trim) {
+        None | Some("identity" | "gzip" | "x-gzip" | "zstd") => {}
+        Some(encoding) => {
+            return Err(format!("Unsupported Content-Encoding {:?} from {}", encoding, url).into());
+        }
+    }
+    streaming::parse_reader(BufReader::new(response.into_reader()))
+}
+
+// runs the blocking request on tokio's blocking pool; must be awaited inside a tokio runtime
+#[cfg(feature = "async")]
+pub async fn parse_url_async(url: &str) -> Result<Value, Error> {
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || parse_url(&url))
+        .await
+        .map_err(|e| Error::from(io::Error::other(e)))?
+}
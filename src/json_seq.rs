@@ -0,0 +1,112 @@
+// RFC 7464 `application/json-seq`: every record is RS <JSON text> LF
+use crate::sax;
+use crate::serializer;
+use crate::{Error, Value};
+use std::io::{self, BufRead, Write};
+
+pub const RECORD_SEPARATOR: u8 = 0x1E;
+
+pub struct Reader<R> {
+    reader: R,
+    record_number: usize,
+    buffer: Vec<u8>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Reader {
+            reader,
+            record_number: 0,
+            buffer: Vec::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    pub fn record_number(&self) -> usize {
+        self.record_number
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            if let Err(e) = self.reader.read_until(RECORD_SEPARATOR, &mut self.buffer) {
+                self.done = true;
+                return Some(Err(e.into()));
+            }
+            if self.buffer.last() == Some(&RECORD_SEPARATOR) {
+                self.buffer.pop();
+            } else {
+                self.done = true; // no separator left, this is the last record
+            }
+            let leading = !std::mem::replace(&mut self.started, true);
+            if self.buffer.iter().all(u8::is_ascii_whitespace) {
+                continue; // empty records and the gap before the first RS are skipped
+            }
+            self.record_number += 1;
+            let result = if leading {
+                Err("Missing record separator".into())
+            } else {
+                parse_record(&self.buffer)
+            };
+            return Some(result.map_err(|e| Error::Record(self.record_number, Box::new(e))));
+        }
+        None
+    }
+}
+
+pub(crate) fn parse_record(record: &[u8]) -> Result<Value, Error> {
+    let text = std::str::from_utf8(record).map_err(|_| "Invalid UTF-8 in input")?;
+    let value = sax::build(text)?;
+    // a top-level scalar that isn't followed by whitespace may have been cut short
+    let truncated = !text.ends_with(|c: char| c.is_whitespace());
+    match value {
+        Value::Number(_) | Value::Bool(_) | Value::Null if truncated => {
+            Err("Possibly truncated record".into())
+        }
+        _ => Ok(value),
+    }
+}
+
+pub struct Writer<W> {
+    writer: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(writer: W) -> Self {
+        Writer { writer }
+    }
+
+    // `json` must already be one JSON text; an RS inside it would split the record
+    pub fn write_record(&mut self, json: &str) -> io::Result<()> {
+        if json.as_bytes().contains(&RECORD_SEPARATOR) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "record contains a record separator",
+            ));
+        }
+        self.writer.write_all(&[RECORD_SEPARATOR])?;
+        self.writer.write_all(json.trim().as_bytes())?;
+        self.writer.write_all(b"\n")
+    }
+
+    // serialized compactly; control characters in strings are escaped, so an RS can't
+    // end up inside the record
+    pub fn write_value(&mut self, value: &Value) -> io::Result<()> {
+        self.write_record(&serializer::to_string(value))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
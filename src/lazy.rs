@@ -0,0 +1,281 @@
+// on-demand access: subtrees are only parsed when they are actually read
+use crate::tape::{StructuralIndex, Walker};
+use crate::{Error, RawValue, Value};
+
+pub struct LazyDocument<'a> {
+    input: &'a str,
+    index: StructuralIndex,
+    closing: Vec<usize>, // for each '{' / '[' the index of its matching bracket
+}
+
+impl<'a> LazyDocument<'a> {
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let index = StructuralIndex::build(input)?;
+        let closing = match_brackets(input, index.positions())?;
+        let document = LazyDocument {
+            input,
+            index,
+            closing,
+        };
+        let (next, offset) = document.root().end()?;
+        let rest = Walker::new(input, document.index.positions(), next, offset);
+        if next < document.index.positions().len() || !rest.gap().trim().is_empty() {
+            return Err("Unexpected trailing token".into());
+        }
+        Ok(document)
+    }
+
+    pub fn root(&self) -> LazyValue<'_> {
+        LazyValue {
+            input: self.input,
+            positions: self.index.positions(),
+            closing: &self.closing,
+            next: 0,
+            offset: 0,
+        }
+    }
+}
+
+fn match_brackets(input: &str, positions: &[usize]) -> Result<Vec<usize>, Error> {
+    let bytes = input.as_bytes();
+    let mut closing = vec![0; positions.len()];
+    let mut stack = Vec::new();
+    for (i, &position) in positions.iter().enumerate() {
+        match bytes[position] {
+            b'{' | b'[' => stack.push(i),
+            close @ (b'}' | b']') => {
+                let open = stack.pop().ok_or("Unexpected closing bracket")?;
+                if (bytes[positions[open]], close) != (b'{', b'}')
+                    && (bytes[positions[open]], close) != (b'[', b']')
+                {
+                    return Err("Mismatched brackets".into());
+                }
+                closing[open] = i;
+            }
+            _ => {}
+        }
+    }
+    match stack.is_empty() {
+        true => Ok(closing),
+        false => Err("Unexpected end of input".into()),
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct LazyValue<'a> {
+    input: &'a str,
+    positions: &'a [usize],
+    closing: &'a [usize],
+    next: usize,
+    offset: usize,
+}
+
+impl<'a> LazyValue<'a> {
+    fn walker(&self) -> Walker<'a, 'a> {
+        Walker::new(self.input, self.positions, self.next, self.offset)
+    }
+
+    fn first_byte(&self) -> Option<u8> {
+        let walker = self.walker();
+        match walker.gap().trim().as_bytes().first() {
+            Some(&byte) => Some(byte),
+            None => walker.peek(),
+        }
+    }
+
+    // position right after this value, found without looking inside it
+    fn end(&self) -> Result<(usize, usize), Error> {
+        let walker = self.walker();
+        let gap = walker.gap();
+        if !gap.trim().is_empty() {
+            return Ok((self.next, self.offset + gap.len()));
+        }
+        match walker.peek() {
+            Some(b'{' | b'[') => {
+                let close = self.closing[self.next];
+                Ok((close + 1, self.positions[close] + 1))
+            }
+            Some(b'"') => Ok((self.next + 2, self.positions[self.next + 1] + 1)),
+            Some(_) => Err("Invalid JSON token".into()),
+            None => Err("Unexpected end of input".into()),
+        }
+    }
+
+    pub fn raw(&self) -> Result<&'a str, Error> {
+        let (_, end) = self.end()?;
+        Ok(self.input[self.offset..end].trim())
+    }
+
+    // the subtree's source text, to forward without re-serializing it
+    pub fn to_raw_value(&self) -> Result<RawValue, Error> {
+        RawValue::from_string(self.raw()?)
+    }
+
+    pub fn parse(&self) -> Result<Value, Error> {
+        self.walker().parse()
+    }
+
+    pub fn is_object(&self) -> bool {
+        self.first_byte() == Some(b'{')
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.first_byte() == Some(b'[')
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        let mut walker = self.walker();
+        (walker.peek() == Some(b'"')).then(|| walker.string_slice())
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.first_byte()? {
+            b'0'..=b'9' => match self.parse() {
+                Ok(Value::Number(number)) => Some(number.as_f64()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.first_byte()? {
+            b't' | b'f' => match self.parse() {
+                Ok(Value::Bool(value)) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.first_byte() == Some(b'n') && matches!(self.parse(), Ok(Value::Null))
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<LazyValue<'a>>, Error> {
+        for entry in self.entries() {
+            let (name, value) = entry?;
+            if name == key {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn get_index(&self, index: usize) -> Result<Option<LazyValue<'a>>, Error> {
+        self.elements().nth(index).transpose()
+    }
+
+    // empty when the value is not an object
+    pub fn entries(&self) -> Entries<'a> {
+        Entries(self.children(b'{'))
+    }
+
+    // empty when the value is not an array
+    pub fn elements(&self) -> Elements<'a> {
+        Elements(self.children(b'['))
+    }
+
+    fn children(&self, opener: u8) -> Children<'a> {
+        let mut walker = self.walker();
+        let is_container = walker.peek() == Some(opener);
+        if is_container {
+            walker.bump();
+        }
+        Children {
+            value: *self,
+            walker,
+            keyed: opener == b'{',
+            first: true,
+            done: !is_container,
+        }
+    }
+}
+
+struct Children<'a> {
+    value: LazyValue<'a>, // the container, used as a template for its children
+    walker: Walker<'a, 'a>,
+    keyed: bool,
+    first: bool,
+    done: bool,
+}
+
+impl<'a> Children<'a> {
+    fn next_child(&mut self) -> Result<Option<(&'a str, LazyValue<'a>)>, Error> {
+        let (closer, container) = match self.keyed {
+            true => (b'}', "object"),
+            false => (b']', "array"),
+        };
+        // handle separator
+        match self.walker.peek() {
+            Some(byte) if byte == closer => return Ok(None),
+            Some(b',') if !self.first => {
+                self.walker.bump(); // consume comma
+                // check for trailing comma
+                if self.walker.peek() == Some(closer) {
+                    return Err(format!("Trailing comma in {}", container).into());
+                }
+            }
+            _ if self.first => {}
+            _ => {
+                return Err(
+                    format!("Expected ',' or '{}' in {}", closer as char, container).into(),
+                );
+            }
+        }
+        self.first = false;
+
+        // resolve "key": value
+        let mut key = "";
+        if self.keyed {
+            if self.walker.peek() != Some(b'"') {
+                return Err("Expected string".into());
+            }
+            key = self.walker.string_slice();
+            if self.walker.peek() != Some(b':') {
+                return Err("Expected Colon, found unexpected token".into());
+            }
+            self.walker.bump();
+        }
+        let value = LazyValue {
+            next: self.walker.next,
+            offset: self.walker.offset,
+            ..self.value
+        };
+        (self.walker.next, self.walker.offset) = value.end()?;
+        Ok(Some((key, value)))
+    }
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Result<(&'a str, LazyValue<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let child = self.next_child().transpose();
+        self.done = !matches!(child, Some(Ok(_))); // stop at the end or the first error
+        child
+    }
+}
+
+pub struct Entries<'a>(Children<'a>);
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = Result<(&'a str, LazyValue<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+pub struct Elements<'a>(Children<'a>);
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = Result<LazyValue<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|child| child.map(|(_, value)| value))
+    }
+}
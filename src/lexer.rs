@@ -0,0 +1,167 @@
+use crate::{Error, Token, TokenType};
+use std::iter::Peekable;
+#[cfg(all(feature = "serializer", feature = "schema"))]
+use std::ops::Range;
+use std::str::CharIndices;
+
+pub fn generate(input: &str) -> Result<Vec<Token>, Error> {
+    parse(&mut input.char_indices().peekable())
+}
+
+#[cfg(all(feature = "serializer", feature = "schema"))]
+type Located<T> = (T, Range<usize>);
+
+// like generate, with the byte range each token came from; a failure comes with the
+// range of what couldn't be read, or an empty range at the end for a cut-off input.
+// Only the diagnostic module needs it, hence its features
+#[cfg(all(feature = "serializer", feature = "schema"))]
+pub(crate) fn generate_located(input: &str) -> Result<Vec<Located<Token>>, Located<Error>> {
+    let mut iter = input.char_indices().peekable();
+    let mut tokens = Vec::new();
+    loop {
+        skip_whitespace(&mut iter);
+        let start = offset(&mut iter, input);
+        match next_token(&mut iter) {
+            Some(Ok(token)) => tokens.push((token, start..offset(&mut iter, input))),
+            Some(Err(error)) => {
+                let mut end = offset(&mut iter, input);
+                if end == start {
+                    // the character it stopped at is still unread
+                    end += input[start..].chars().next().map_or(0, char::len_utf8);
+                }
+                return Err((error, start..end));
+            }
+            None => return Ok(tokens),
+        }
+    }
+}
+
+// the byte offset of the next unread character
+#[cfg(all(feature = "serializer", feature = "schema"))]
+fn offset(iter: &mut Peekable<CharIndices>, input: &str) -> usize {
+    iter.peek().map_or(input.len(), |&(i, _)| i)
+}
+
+// lazily yields tokens instead of collecting them up front
+pub fn tokens(input: &str) -> Tokens<'_> {
+    Tokens {
+        iter: input.char_indices().peekable(),
+        failed: false,
+    }
+}
+
+pub struct Tokens<'a> {
+    iter: Peekable<CharIndices<'a>>,
+    failed: bool,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Result<Token, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let token = next_token(&mut self.iter)?;
+        self.failed = token.is_err(); // stop after the first error
+        Some(token)
+    }
+}
+
+fn parse(iter: &mut Peekable<CharIndices>) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    while let Some(token) = next_token(iter) {
+        tokens.push(token?);
+    }
+    Ok(tokens)
+}
+
+fn next_token(iter: &mut Peekable<CharIndices>) -> Option<Result<Token, Error>> {
+    skip_whitespace(iter);
+    let &(_, c) = iter.peek()?;
+    Some(match c {
+        '{' | '}' | '[' | ']' | ':' | ',' => parse_simple_token(iter),
+        '"' => parse_string(iter),
+        '0'..='9' => parse_number(iter),
+        'a'..='z' | 'A'..='Z' => parse_keyword(iter),
+        _ => Err(format!("Unexpected character: '{}'", c).into()),
+    })
+}
+
+fn skip_whitespace(iter: &mut Peekable<CharIndices>) {
+    while iter.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+}
+
+// consumes characters while they match, leaving the first one that doesn't unread
+fn take_while(iter: &mut Peekable<CharIndices>, predicate: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some((_, c)) = iter.next_if(|&(_, c)| predicate(c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+fn parse_simple_token(iter: &mut Peekable<CharIndices>) -> Result<Token, Error> {
+    let (_, character) = iter.next().unwrap(); // consume the character
+    simple_token(character)
+}
+
+pub(crate) fn simple_token(character: char) -> Result<Token, Error> {
+    let token_type = match character {
+        '{' => TokenType::OpenObject,
+        '}' => TokenType::CloseObject,
+        '[' => TokenType::OpenArray,
+        ']' => TokenType::CloseArray,
+        ':' => TokenType::Colon,
+        ',' => TokenType::Comma,
+        _ => return Err(format!("Unexpected simple token: '{}'", character).into()),
+    };
+    Ok(Token {
+        token_type,
+        value: character.to_string(),
+    })
+}
+
+fn parse_string(iter: &mut Peekable<CharIndices>) -> Result<Token, Error> {
+    consume_char(iter, '"')?; // consume opening quote
+    let string = take_while(iter, |c| c != '"');
+    consume_char(iter, '"')?; // consume closing quote
+    Ok(Token {
+        token_type: TokenType::String,
+        value: string,
+    })
+}
+
+fn parse_number(iter: &mut Peekable<CharIndices>) -> Result<Token, Error> {
+    let number_str = take_while(iter, |c| c.is_ascii_digit() || c == '.');
+    Ok(Token {
+        token_type: TokenType::Number,
+        value: number_str,
+    })
+}
+
+fn parse_keyword(iter: &mut Peekable<CharIndices>) -> Result<Token, Error> {
+    let keyword = take_while(iter, char::is_alphabetic);
+    keyword_token(keyword)
+}
+
+pub(crate) fn keyword_token(keyword: String) -> Result<Token, Error> {
+    let token_type = match keyword.as_str() {
+        "true" => TokenType::True,
+        "false" => TokenType::False,
+        "null" => TokenType::Null,
+        _ => return Err(format!("Unexpected keyword: '{}'", keyword).into()),
+    };
+    Ok(Token {
+        token_type,
+        value: keyword,
+    })
+}
+
+fn consume_char(iter: &mut Peekable<CharIndices>, expected: char) -> Result<char, Error> {
+    match iter.next() {
+        Some((_, c)) if c == expected => Ok(c),
+        Some((_, c)) => Err(format!("Expected '{}', but found '{}'", expected, c).into()),
+        None => Err("Unexpected end of input".into()),
+    }
+}
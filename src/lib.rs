@@ -0,0 +1,154 @@
+// the subsystems behind the lexer, parser, serializer, pointer, schema, codegen and query
+// features; Value, Map, Number and the tree utilities are always built
+mod compare;
+mod convert;
+mod error;
+#[cfg(all(feature = "parser", feature = "serializer"))]
+mod file;
+mod number;
+#[cfg(feature = "pointer")]
+mod pointer;
+mod raw_value;
+mod redact;
+mod size;
+mod sort;
+mod value;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "parser")]
+pub mod array_index;
+#[cfg(feature = "async")]
+pub mod async_io;
+#[cfg(feature = "avro")]
+pub mod avro;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+#[cfg(feature = "parser")]
+pub mod compression;
+#[cfg(all(feature = "parser", feature = "serializer", feature = "pointer"))]
+pub mod cst;
+#[cfg(feature = "serializer")]
+pub mod csv;
+pub mod cursor;
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(all(
+    feature = "parser",
+    feature = "serializer",
+    feature = "pointer",
+    feature = "schema"
+))]
+pub mod diagnostic;
+#[cfg(all(feature = "parser", feature = "serializer"))]
+pub mod diff;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "query")]
+pub mod filter;
+pub mod flatten;
+#[cfg(all(feature = "parser", feature = "serializer"))]
+pub mod format;
+#[cfg(feature = "lexer")]
+pub mod highlight;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(all(feature = "parser", feature = "serializer"))]
+pub mod json_seq;
+#[cfg(feature = "parser")]
+pub mod lazy;
+#[cfg(feature = "lexer")]
+pub mod lexer;
+pub mod map;
+#[cfg(feature = "serializer")]
+pub mod merge;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "parser")]
+pub mod ndjson;
+#[cfg(feature = "parser")]
+pub mod parser;
+#[cfg(feature = "pointer")]
+pub mod patch;
+pub mod path;
+#[cfg(feature = "pointer")]
+pub mod refs;
+#[cfg(all(feature = "parser", feature = "serializer"))]
+pub mod render;
+#[cfg(feature = "parser")]
+pub mod sax;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "serializer")]
+pub mod serializer;
+pub mod shape;
+#[cfg(feature = "parser")]
+pub mod shared;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(all(feature = "parser", feature = "serializer"))]
+pub mod stats;
+#[cfg(feature = "parser")]
+pub mod streaming;
+#[cfg(feature = "serializer")]
+pub mod substitute;
+#[cfg(feature = "parser")]
+pub mod tape;
+#[cfg(feature = "toml")]
+pub mod toml;
+pub mod visit;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+pub use convert::{DecodeError, FromJson, ToJson};
+pub use error::Error;
+pub use map::Map;
+pub use number::Number;
+#[cfg(feature = "arbitrary_precision")]
+pub use number::{BigInt, Decimal};
+pub use raw_value::RawValue;
+#[cfg(feature = "derive")]
+pub use rust_practice_json_parser_derive::{FromJson, ToJson};
+pub use value::{Array, JsonIndex, Value};
+
+#[cfg(feature = "lexer")]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TokenType {
+    OpenObject,
+    CloseObject,
+    OpenArray,
+    CloseArray,
+    String,
+    Number,
+    True,
+    False,
+    Null,
+    Colon,
+    Comma,
+}
+
+#[cfg(feature = "lexer")]
+#[derive(Debug)]
+pub struct Token {
+    pub token_type: TokenType,
+    pub value: String,
+}
+
+// JSON string → Lexer → Token stream → Parser → AST
+#[cfg(feature = "parser")]
+pub fn parse_str(input: &str) -> Result<Value, Error> {
+    let tokens = lexer::generate(input)?;
+    parser::generate(&tokens)
+}
+
+#[cfg(feature = "parser")]
+pub fn parse_str_with(input: &str, options: &parser::ParseOptions) -> Result<Value, Error> {
+    let tokens = lexer::generate(input)?;
+    parser::generate_with(&tokens, options)
+}
@@ -1,150 +1,144 @@
-mod libs;
-
-use libs::{lexer, parser};
-
-fn main() {
-    println!("=== JSON Parser Testing ===\n");
-
-    // 测试用例
-    let test_cases = vec![
-        r#"{"name": "John", "age": 30}"#,
-        r#"[1, 2, 3, "hello"]"#,
-        r#"{"active": true, "data": null}"#,
-        r#"{"nested": {"inner": "value"}, "array": [1, 2, 3]}"#,
-        r#"[]"#,
-        r#"{}"#,
-        r#"false"#,
-        r#"42"#,
-        r#""simple string""#,
-    ];
-
-    for (i, json_str) in test_cases.iter().enumerate() {
-        println!("--- Test Case {} ---", i + 1);
-        println!("Input: {}", json_str);
-
-        test_json_parsing(json_str);
-        println!();
+use rust_practice_json_parser::{compression, diagnostic};
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal, Read};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: json-parser [OPTIONS] [FILE]
+       json-parser validate FILE...
+
+Parses FILE, or standard input when FILE is `-` or missing, and prints the
+document, or a diagnostic pointing at the error. gzip and zstd input is
+decompressed when the crate is built with those features.
+
+`validate` only checks each FILE, printing whether it passed and where it
+failed; it exits with 1 when any of them is not valid JSON, and with 2 when
+one can't be read.
+
+Options:
+  -c, --compact  print the document on one line
+  -h, --help     print this help
+";
+
+// 退出码：0 成功，1 输入不是合法的 JSON，2 用法错误或读取失败
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
     }
-
-    // 测试错误情况
-    println!("--- Error Cases ---");
-    let error_cases = vec![
-        r#"{"name": "John",}"#,   // 多余的逗号
-        r#"{"name" "John"}"#,     // 缺少冒号
-        r#"{name: "John"}"#,      // 键没有引号
-        r#"{"name": undefined}"#, // 未知关键字
-    ];
-
-    for (i, json_str) in error_cases.iter().enumerate() {
-        println!("Error Case {}: {}", i + 1, json_str);
-        test_json_parsing(json_str);
-        println!();
+    match args.split_first() {
+        Some((command, paths)) if command == "validate" => validate(paths),
+        _ => print_document(&args),
     }
 }
 
-fn test_json_parsing(input: &str) {
-    // 步骤1: 词法分析
-    println!("  Step 1: Lexical Analysis");
-    match lexer::generate(input) {
-        Ok(tokens) => {
-            println!("  ✓ Tokens generated successfully:");
-            for (i, token) in tokens.iter().enumerate() {
-                println!("    {}. {:?}", i + 1, token);
-            }
-
-            // 步骤2: 语法分析
-            println!("  Step 2: Syntax Analysis");
-            match parser::generate(&tokens) {
-                Ok(ast) => {
-                    println!("  ✓ AST generated successfully:");
-                    println!("    {:?}", ast);
-                }
-                Err(parse_error) => {
-                    println!("  ✗ Parse Error: {}", parse_error);
-                }
+fn print_document(args: &[String]) -> ExitCode {
+    let mut compact = false;
+    let mut path = None;
+    for arg in args {
+        match arg.as_str() {
+            "-c" | "--compact" => compact = true,
+            option if is_option(option) => {
+                return usage_error(&format!("unknown option `{}`", option));
             }
+            _ if path.is_some() => return usage_error("expected a single file"),
+            _ => path = Some(arg.clone()),
+        }
+    }
+    // 没有参数又没有管道输入时，等待终端输入只会让人困惑
+    let path = match path {
+        Some(path) => path,
+        None if io::stdin().is_terminal() => return usage_error("no input"),
+        None => "-".to_string(),
+    };
+
+    let source = match read_source(&path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("json-parser: {}: {}", path, e);
+            return ExitCode::from(2);
         }
-        Err(lex_error) => {
-            println!("  ✗ Lexer Error: {}", lex_error);
+    };
+    match diagnostic::parse(&source) {
+        Ok(value) if compact => println!("{}", value),
+        Ok(value) => println!("{:#}", value),
+        Err(d) => {
+            eprint!(
+                "{}",
+                diagnostic::to_text(&[d], &source, display_name(&path))
+            );
+            return ExitCode::FAILURE;
         }
     }
+    ExitCode::SUCCESS
 }
 
-// 演示单独测试 lexer
-#[allow(dead_code)]
-fn test_lexer_only() {
-    let input = r#"{"hello": "world"}"#;
-    println!("Testing lexer with: {}", input);
-
-    match lexer::generate(input) {
-        Ok(tokens) => {
-            println!("Generated {} tokens:", tokens.len());
-            for token in tokens {
-                println!("  {:?}", token);
+// 每个文件输出一行 PASS / FAIL，失败的文件后面跟着诊断信息；所有文件都会检查，
+// 而不是遇到第一个错误就停下。无法读取的文件同样算失败，退出码为 2
+fn validate(paths: &[String]) -> ExitCode {
+    if paths.is_empty() {
+        return usage_error("validate expects at least one file");
+    }
+    if let Some(option) = paths.iter().find(|path| is_option(path)) {
+        return usage_error(&format!("unknown option `{}`", option));
+    }
+    let (mut invalid, mut unreadable) = (0, 0);
+    for path in paths {
+        let name = display_name(path);
+        let source = match read_source(path) {
+            Ok(source) => source,
+            Err(e) => {
+                println!("FAIL {}: {}", name, e);
+                unreadable += 1;
+                continue;
+            }
+        };
+        match diagnostic::parse(&source) {
+            Ok(_) => println!("PASS {}", name),
+            Err(d) => {
+                println!("FAIL {}", name);
+                print!("{}", diagnostic::to_text(&[d], &source, name));
+                invalid += 1;
             }
         }
-        Err(e) => println!("Lexer error: {}", e),
+    }
+    if paths.len() > 1 {
+        println!(
+            "\n{} of {} files passed",
+            paths.len() - invalid - unreadable,
+            paths.len()
+        );
+    }
+    if unreadable > 0 {
+        ExitCode::from(2)
+    } else if invalid > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
     }
 }
 
-// 演示单独测试 parser
-#[allow(dead_code)]
-fn test_parser_only() {
-    use libs::{Token, TokenType};
-
-    // 手动创建一些 tokens 来测试 parser
-    let tokens = vec![
-        Token {
-            token_type: TokenType::OpenObject,
-            value: "{".to_string(),
-        },
-        Token {
-            token_type: TokenType::String,
-            value: "key".to_string(),
-        },
-        Token {
-            token_type: TokenType::Colon,
-            value: ":".to_string(),
-        },
-        Token {
-            token_type: TokenType::String,
-            value: "value".to_string(),
-        },
-        Token {
-            token_type: TokenType::CloseObject,
-            value: "}".to_string(),
-        },
-    ];
-
-    println!("Testing parser with manual tokens");
-    match parser::generate(&tokens) {
-        Ok(ast) => println!("AST: {:?}", ast),
-        Err(e) => println!("Parser error: {}", e),
+// `-` 表示标准输入；压缩的输入按魔数识别后解压
+fn read_source(path: &str) -> io::Result<String> {
+    let mut source = String::new();
+    if path == "-" {
+        compression::Decoder::new(io::stdin().lock()).read_to_string(&mut source)?;
+    } else {
+        compression::Decoder::new(BufReader::new(File::open(path)?)).read_to_string(&mut source)?;
     }
+    Ok(source)
 }
 
-// 完整的 JSON 解析流水线
-fn parse_json_complete(input: &str) -> Result<libs::ASTNode, String> {
-    let tokens = lexer::generate(input)?;
-    let ast = parser::generate(&tokens)?;
-    Ok(ast)
+fn is_option(arg: &str) -> bool {
+    arg.starts_with('-') && arg != "-"
 }
 
-// 演示完整流水线
-#[allow(dead_code)]
-fn demo_complete_pipeline() {
-    let json = r#"{"users": [{"name": "Alice", "age": 25}, {"name": "Bob", "age": 30}]}"#;
-
-    println!("Complete JSON parsing demo:");
-    println!("Input: {}", json);
+fn display_name(path: &str) -> &str {
+    if path == "-" { "<stdin>" } else { path }
+}
 
-    match parse_json_complete(json) {
-        Ok(ast) => {
-            println!("Success! Final AST:");
-            println!("{:#?}", ast);
-        }
-        Err(e) => {
-            println!("Failed: {}", e);
-        }
-    }
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("json-parser: {}\n\n{}", message, USAGE);
+    ExitCode::from(2)
 }
@@ -0,0 +1,518 @@
+use crate::Value;
+use std::hash::{Hash, Hasher};
+
+// the backing store is picked at compile time; both expose the same BTreeMap-like surface
+#[cfg(not(feature = "sorted_keys"))]
+use ordered as store;
+#[cfg(feature = "sorted_keys")]
+use sorted as store;
+
+// JSON object storage: keys keep insertion order, or sorted order with the
+// `sorted_keys` feature
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: store::Store,
+}
+
+impl Map {
+    pub fn new() -> Map {
+        Map::default()
+    }
+
+    // a no-op with `sorted_keys`, where keys are always in order
+    pub fn sort_keys(&mut self) {
+        store::sort_keys(&mut self.entries);
+    }
+
+    // bytes the map itself allocates, not counting what its values allocate
+    pub(crate) fn heap_bytes(&self) -> usize {
+        store::heap_bytes(&self.entries)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries.get_mut(key)
+    }
+
+    // an existing key keeps its place in the order and the old value is returned
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        self.entries.insert(key, value)
+    }
+
+    // keeps the order of the remaining keys
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.entries.remove(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn retain<F: FnMut(&String, &mut Value) -> bool>(&mut self, keep: F) {
+        self.entries.retain(keep);
+    }
+
+    // a single lookup that can then read, update or insert
+    pub fn entry<S: Into<String>>(&mut self, key: S) -> Entry<'_> {
+        match self.entries.entry(key.into()) {
+            store::Entry::Vacant(entry) => Entry::Vacant(VacantEntry(entry)),
+            store::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry(entry)),
+        }
+    }
+
+    pub fn get_or_insert_mut<S: Into<String>>(&mut self, key: S, default: Value) -> &mut Value {
+        self.entry(key).or_insert(default)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(self.entries.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut(self.entries.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.iter_mut().map(|(_, value)| value)
+    }
+}
+
+pub enum Entry<'a> {
+    Vacant(VacantEntry<'a>),
+    Occupied(OccupiedEntry<'a>),
+}
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &String {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, default: F) -> &'a mut Value {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut Value {
+        self.or_insert_with(Value::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut Value)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+pub struct VacantEntry<'a>(store::VacantEntry<'a>);
+
+impl<'a> VacantEntry<'a> {
+    pub fn key(&self) -> &String {
+        self.0.key()
+    }
+
+    pub fn insert(self, value: Value) -> &'a mut Value {
+        self.0.insert(value)
+    }
+}
+
+pub struct OccupiedEntry<'a>(store::OccupiedEntry<'a>);
+
+impl<'a> OccupiedEntry<'a> {
+    pub fn key(&self) -> &String {
+        self.0.key()
+    }
+
+    pub fn get(&self) -> &Value {
+        self.0.get()
+    }
+
+    pub fn get_mut(&mut self) -> &mut Value {
+        self.0.get_mut()
+    }
+
+    pub fn into_mut(self) -> &'a mut Value {
+        self.0.into_mut()
+    }
+
+    pub fn insert(&mut self, value: Value) -> Value {
+        self.0.insert(value)
+    }
+
+    pub fn remove(self) -> Value {
+        self.0.remove()
+    }
+}
+
+pub struct Iter<'a>(store::Iter<'a>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+pub struct IterMut<'a>(store::IterMut<'a>);
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+pub struct IntoIter(store::IntoIter);
+
+impl Iterator for IntoIter {
+    type Item = (String, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter(self.entries.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type Item = (&'a String, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+// with duplicate keys the last value wins, at the position of the first
+impl FromIterator<(String, Value)> for Map {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, Value)> for Map {
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+// the same keys with equal values, in any order
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl Eq for Map {}
+
+// hashes in key order so that it agrees with the order-insensitive equality
+impl Hash for Map {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries.hash(state);
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "sorted_keys")]
+mod sorted {
+    use crate::Value;
+    use std::collections::btree_map;
+
+    pub type Store = btree_map::BTreeMap<String, Value>;
+    pub type Iter<'a> = btree_map::Iter<'a, String, Value>;
+    pub type IterMut<'a> = btree_map::IterMut<'a, String, Value>;
+    pub type IntoIter = btree_map::IntoIter<String, Value>;
+    pub type Entry<'a> = btree_map::Entry<'a, String, Value>;
+    pub type VacantEntry<'a> = btree_map::VacantEntry<'a, String, Value>;
+    pub type OccupiedEntry<'a> = btree_map::OccupiedEntry<'a, String, Value>;
+
+    pub fn sort_keys(_store: &mut Store) {}
+
+    // B-tree nodes run about two-thirds full, so allow half again per entry
+    pub fn heap_bytes(store: &Store) -> usize {
+        let nodes = store.len() * size_of::<(String, Value)>() * 3 / 2;
+        nodes + store.keys().map(String::capacity).sum::<usize>()
+    }
+}
+
+#[cfg(not(feature = "sorted_keys"))]
+mod ordered {
+    use crate::Value;
+    use std::collections::HashMap;
+
+    // entries in insertion order, plus a hash index from key to position
+    #[derive(Clone, Default)]
+    pub struct Store {
+        entries: Vec<(String, Value)>,
+        index: HashMap<String, usize>,
+    }
+
+    impl Store {
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        pub fn contains_key(&self, key: &str) -> bool {
+            self.index.contains_key(key)
+        }
+
+        pub fn get(&self, key: &str) -> Option<&Value> {
+            let position = *self.index.get(key)?;
+            Some(&self.entries[position].1)
+        }
+
+        pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+            let position = *self.index.get(key)?;
+            Some(&mut self.entries[position].1)
+        }
+
+        pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+            if let Some(&position) = self.index.get(&key) {
+                return Some(std::mem::replace(&mut self.entries[position].1, value));
+            }
+            self.index.insert(key.clone(), self.entries.len());
+            self.entries.push((key, value));
+            None
+        }
+
+        // shifts the later entries down, so this is O(n)
+        pub fn remove(&mut self, key: &str) -> Option<Value> {
+            let position = self.index.remove(key)?;
+            let (_, value) = self.entries.remove(position);
+            for (key, _) in &self.entries[position..] {
+                *self.index.get_mut(key.as_str()).unwrap() -= 1;
+            }
+            Some(value)
+        }
+
+        pub fn clear(&mut self) {
+            self.entries.clear();
+            self.index.clear();
+        }
+
+        pub fn retain<F: FnMut(&String, &mut Value) -> bool>(&mut self, mut keep: F) {
+            self.entries.retain_mut(|(key, value)| keep(key, value));
+            self.reindex();
+        }
+
+        pub fn sort_keys(&mut self) {
+            self.entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            self.reindex();
+        }
+
+        fn reindex(&mut self) {
+            self.index.clear();
+            for (position, (key, _)) in self.entries.iter().enumerate() {
+                self.index.insert(key.clone(), position);
+            }
+        }
+
+        pub fn entry(&mut self, key: String) -> Entry<'_> {
+            match self.index.get(&key) {
+                Some(&position) => Entry::Occupied(OccupiedEntry {
+                    store: self,
+                    position,
+                }),
+                None => Entry::Vacant(VacantEntry { store: self, key }),
+            }
+        }
+
+        pub fn iter(&self) -> Iter<'_> {
+            Iter(self.entries.iter())
+        }
+
+        pub fn iter_mut(&mut self) -> IterMut<'_> {
+            IterMut(self.entries.iter_mut())
+        }
+    }
+
+    pub fn sort_keys(store: &mut Store) {
+        store.sort_keys();
+    }
+
+    // the entry buffer, the index table (one control byte per slot) and both copies of each key
+    pub fn heap_bytes(store: &Store) -> usize {
+        let entries = store.entries.capacity() * size_of::<(String, Value)>();
+        let index = store.index.capacity() * (size_of::<(String, usize)>() + 1);
+        let keys: usize = store
+            .entries
+            .iter()
+            .map(|(key, _)| key.capacity() + key.len())
+            .sum();
+        entries + index + keys
+    }
+
+    impl IntoIterator for Store {
+        type Item = (String, Value);
+        type IntoIter = IntoIter;
+
+        fn into_iter(self) -> IntoIter {
+            self.entries.into_iter()
+        }
+    }
+
+    pub enum Entry<'a> {
+        Vacant(VacantEntry<'a>),
+        Occupied(OccupiedEntry<'a>),
+    }
+
+    pub struct VacantEntry<'a> {
+        store: &'a mut Store,
+        key: String,
+    }
+
+    impl<'a> VacantEntry<'a> {
+        pub fn key(&self) -> &String {
+            &self.key
+        }
+
+        pub fn insert(self, value: Value) -> &'a mut Value {
+            let position = self.store.entries.len();
+            self.store.index.insert(self.key.clone(), position);
+            self.store.entries.push((self.key, value));
+            &mut self.store.entries[position].1
+        }
+    }
+
+    pub struct OccupiedEntry<'a> {
+        store: &'a mut Store,
+        position: usize,
+    }
+
+    impl<'a> OccupiedEntry<'a> {
+        pub fn key(&self) -> &String {
+            &self.store.entries[self.position].0
+        }
+
+        pub fn get(&self) -> &Value {
+            &self.store.entries[self.position].1
+        }
+
+        pub fn get_mut(&mut self) -> &mut Value {
+            &mut self.store.entries[self.position].1
+        }
+
+        pub fn into_mut(self) -> &'a mut Value {
+            &mut self.store.entries[self.position].1
+        }
+
+        pub fn insert(&mut self, value: Value) -> Value {
+            std::mem::replace(self.get_mut(), value)
+        }
+
+        pub fn remove(self) -> Value {
+            let key = self.store.entries[self.position].0.clone();
+            self.store.remove(&key).unwrap()
+        }
+    }
+
+    pub struct Iter<'a>(std::slice::Iter<'a, (String, Value)>);
+
+    impl<'a> Iterator for Iter<'a> {
+        type Item = (&'a String, &'a Value);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next().map(|(key, value)| (key, value))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.0.size_hint()
+        }
+    }
+
+    pub struct IterMut<'a>(std::slice::IterMut<'a, (String, Value)>);
+
+    impl<'a> Iterator for IterMut<'a> {
+        type Item = (&'a String, &'a mut Value);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next().map(|(key, value)| (&*key, value))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.0.size_hint()
+        }
+    }
+
+    pub type IntoIter = std::vec::IntoIter<(String, Value)>;
+}
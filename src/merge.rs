@@ -0,0 +1,119 @@
+// three-way merge: combines two edits of a document made from a common base, the way
+// git merges text, but node by node
+use crate::Value;
+use crate::path::{JsonPath, PathSegment};
+use std::fmt;
+
+// a node that both sides changed, differently; None means absent on that side
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub path: JsonPath,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "removed".to_string(),
+        };
+        match self.path.segments() {
+            [] => f.write_str("$")?,
+            _ => write!(f, "{}", self.path)?,
+        }
+        write!(
+            f,
+            ": ours {}, theirs {}",
+            side(&self.ours),
+            side(&self.theirs)
+        )
+    }
+}
+
+// a change on one side is taken as is; changes on both sides are merged inside objects
+// key by key, and inside arrays element by element when neither side changed the length.
+// Anything else that both sides changed differently is a conflict, and nothing is
+// merged silently: the result is only returned when there are none.
+pub fn merge3(base: &Value, ours: &Value, theirs: &Value) -> Result<Value, Vec<Conflict>> {
+    let mut conflicts = Vec::new();
+    let merged = merge_at(
+        Some(base),
+        Some(ours),
+        Some(theirs),
+        &mut JsonPath::root(),
+        &mut conflicts,
+    );
+    match conflicts.is_empty() {
+        true => Ok(merged.unwrap_or_default()),
+        false => Err(conflicts),
+    }
+}
+
+fn merge_at(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    path: &mut JsonPath,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<Value> {
+    if same(ours, theirs) || same(base, theirs) {
+        return ours.cloned();
+    }
+    if same(base, ours) {
+        return theirs.cloned();
+    }
+    match (base, ours, theirs) {
+        // both added an object, or both edited one: an absent base merges like {}
+        (None | Some(Value::Object(_)), Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let base = base.and_then(Value::as_object);
+            let mut merged = Value::object();
+            let keys = a.keys().chain(b.keys().filter(|key| !a.contains_key(key)));
+            for key in keys {
+                path.push(PathSegment::Key(key.clone()));
+                let value = merge_at(
+                    base.and_then(|base| base.get(key)),
+                    a.get(key),
+                    b.get(key),
+                    path,
+                    conflicts,
+                );
+                path.pop();
+                if let Some(value) = value {
+                    merged[key.as_str()] = value;
+                }
+            }
+            Some(merged)
+        }
+        (Some(Value::Array(base)), Some(Value::Array(a)), Some(Value::Array(b)))
+            if base.len() == a.len() && base.len() == b.len() =>
+        {
+            let mut merged = Vec::with_capacity(base.len());
+            for i in 0..base.len() {
+                path.push(PathSegment::Index(i));
+                let value = merge_at(Some(&base[i]), Some(&a[i]), Some(&b[i]), path, conflicts);
+                path.pop();
+                merged.push(value.unwrap_or_default());
+            }
+            Some(Value::Array(merged))
+        }
+        _ => {
+            conflicts.push(Conflict {
+                path: path.clone(),
+                base: base.cloned(),
+                ours: ours.cloned(),
+                theirs: theirs.cloned(),
+            });
+            ours.cloned()
+        }
+    }
+}
+
+fn same(a: Option<&Value>, b: Option<&Value>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.deep_eq_unordered(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
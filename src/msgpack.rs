@@ -0,0 +1,284 @@
+// MessagePack encoding of Value, for exchanging documents with services that speak it.
+// Every value is written in the shortest form that holds it; floats are written as
+// float 32 when that is exact. Strings, arrays and objects hold at most 2^32 - 1 items.
+use crate::{Error, Map, Number, Value};
+
+const NIL: u8 = 0xc0;
+const FALSE: u8 = 0xc2;
+const TRUE: u8 = 0xc3;
+const FLOAT32: u8 = 0xca;
+const FLOAT64: u8 = 0xcb;
+const UINT8: u8 = 0xcc;
+const UINT16: u8 = 0xcd;
+const UINT32: u8 = 0xce;
+const UINT64: u8 = 0xcf;
+const INT8: u8 = 0xd0;
+const INT16: u8 = 0xd1;
+const INT32: u8 = 0xd2;
+const INT64: u8 = 0xd3;
+const STR8: u8 = 0xd9;
+const STR16: u8 = 0xda;
+const STR32: u8 = 0xdb;
+const ARRAY16: u8 = 0xdc;
+const ARRAY32: u8 = 0xdd;
+const MAP16: u8 = 0xde;
+const MAP32: u8 = 0xdf;
+
+pub fn to_msgpack(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode(value, &mut out);
+    out
+}
+
+// binary and extension types have no JSON equivalent and fail, as do non-string keys
+pub fn from_msgpack(bytes: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder { bytes, position: 0 };
+    let value = decoder.value()?;
+    if decoder.position < bytes.len() {
+        return Err(decoder.error("trailing bytes after the value"));
+    }
+    Ok(value)
+}
+
+fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(NIL),
+        Value::Bool(b) => out.push(if *b { TRUE } else { FALSE }),
+        Value::Number(number) => encode_number(number, out),
+        Value::String(s) => encode_str(s, out),
+        Value::Array(elements) => {
+            head(0x90, ARRAY16, ARRAY32, elements.len(), out);
+            for element in elements {
+                encode(element, out);
+            }
+        }
+        Value::Object(properties) => {
+            head(0x80, MAP16, MAP32, properties.len(), out);
+            for (key, value) in properties {
+                encode_str(key, out);
+                encode(value, out);
+            }
+        }
+        // validated when it was made, so parsing can't fail
+        Value::Raw(raw) => encode(&raw.parse().unwrap_or_default(), out),
+    }
+}
+
+fn encode_str(s: &str, out: &mut Vec<u8>) {
+    match s.len() {
+        n @ 0..=31 => out.push(0xa0 | n as u8),
+        n @ 32..=0xff => out.extend_from_slice(&[STR8, n as u8]),
+        n @ 0x100..=0xffff => {
+            out.push(STR16);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            out.push(STR32);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(s.as_bytes());
+}
+
+// fix form for up to 15 items, then 16 and 32 bit lengths
+fn head(fix: u8, marker16: u8, marker32: u8, length: usize, out: &mut Vec<u8>) {
+    match length {
+        0..=15 => out.push(fix | length as u8),
+        16..=0xffff => {
+            out.push(marker16);
+            out.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        _ => {
+            out.push(marker32);
+            out.extend_from_slice(&(length as u32).to_be_bytes());
+        }
+    }
+}
+
+// numbers that don't fit i64, u64 or f64 exactly go through f64, as in ser
+fn encode_number(number: &Number, out: &mut Vec<u8>) {
+    match *number {
+        Number::U64(n) => encode_unsigned(n, out),
+        Number::I64(n) if n >= 0 => encode_unsigned(n as u64, out),
+        Number::I64(n) => encode_negative(n, out),
+        Number::F64(n) => encode_float(n, out),
+        ref number => match number.to_string().parse::<Number>() {
+            Ok(number @ (Number::I64(_) | Number::U64(_))) => encode_number(&number, out),
+            _ => encode_float(number.as_f64(), out),
+        },
+    }
+}
+
+fn encode_unsigned(n: u64, out: &mut Vec<u8>) {
+    match n {
+        0..=0x7f => out.push(n as u8),
+        0x80..=0xff => out.extend_from_slice(&[UINT8, n as u8]),
+        0x100..=0xffff => {
+            out.push(UINT16);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(UINT32);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(UINT64);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+fn encode_negative(n: i64, out: &mut Vec<u8>) {
+    if n >= -32 {
+        out.push(n as u8); // negative fixint
+    } else if let Ok(n) = i8::try_from(n) {
+        out.extend_from_slice(&[INT8, n as u8]);
+    } else if let Ok(n) = i16::try_from(n) {
+        out.push(INT16);
+        out.extend_from_slice(&n.to_be_bytes());
+    } else if let Ok(n) = i32::try_from(n) {
+        out.push(INT32);
+        out.extend_from_slice(&n.to_be_bytes());
+    } else {
+        out.push(INT64);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+fn encode_float(n: f64, out: &mut Vec<u8>) {
+    if n as f32 as f64 == n || n.is_nan() {
+        out.push(FLOAT32);
+        out.extend_from_slice(&(n as f32).to_be_bytes());
+    } else {
+        out.push(FLOAT64);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &str) -> Error {
+        format!("Invalid MessagePack at byte {}: {}", self.position, message).into()
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<usize, Error> {
+        Ok(self.array::<1>()?[0] as usize)
+    }
+
+    fn u16(&mut self) -> Result<usize, Error> {
+        Ok(u16::from_be_bytes(self.array()?) as usize)
+    }
+
+    fn u32(&mut self) -> Result<usize, Error> {
+        Ok(u32::from_be_bytes(self.array()?) as usize)
+    }
+
+    // an item count, no larger than what the rest of the input could possibly hold
+    fn count(&self, count: usize) -> Result<usize, Error> {
+        match count <= self.bytes.len() - self.position {
+            true => Ok(count),
+            false => Err(self.error("length runs past the end of input")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let marker = self.u8()? as u8;
+        let number = |number| Ok(Value::Number(number));
+        match marker {
+            0x00..=0x7f => number(Number::U64(marker as u64)),
+            0x80..=0x8f => self.map(marker as usize & 0x0f),
+            0x90..=0x9f => self.elements(marker as usize & 0x0f),
+            0xa0..=0xbf => self.str(marker as usize & 0x1f).map(Value::String),
+            NIL => Ok(Value::Null),
+            FALSE => Ok(Value::Bool(false)),
+            TRUE => Ok(Value::Bool(true)),
+            FLOAT32 => number(Number::F64(f32::from_be_bytes(self.array()?) as f64)),
+            FLOAT64 => number(Number::F64(f64::from_be_bytes(self.array()?))),
+            UINT8 => number(Number::U64(self.u8()? as u64)),
+            UINT16 => number(Number::U64(self.u16()? as u64)),
+            UINT32 => number(Number::U64(self.u32()? as u64)),
+            UINT64 => number(Number::U64(u64::from_be_bytes(self.array()?))),
+            INT8 => number(Number::I64(i8::from_be_bytes(self.array()?) as i64)),
+            INT16 => number(Number::I64(i16::from_be_bytes(self.array()?) as i64)),
+            INT32 => number(Number::I64(i32::from_be_bytes(self.array()?) as i64)),
+            INT64 => number(Number::I64(i64::from_be_bytes(self.array()?))),
+            STR8 | STR16 | STR32 => {
+                let length = self.length(marker - STR8)?;
+                self.str(length).map(Value::String)
+            }
+            ARRAY16 => {
+                let count = self.u16()?;
+                self.elements(count)
+            }
+            ARRAY32 => {
+                let count = self.u32()?;
+                self.elements(count)
+            }
+            MAP16 => {
+                let count = self.u16()?;
+                self.map(count)
+            }
+            MAP32 => {
+                let count = self.u32()?;
+                self.map(count)
+            }
+            0xe0..=0xff => number(Number::I64(marker as i8 as i64)),
+            0xc4..=0xc6 => Err(self.error("binary data has no JSON equivalent")),
+            0xc7..=0xc9 | 0xd4..=0xd8 => Err(self.error("extension types have no JSON equivalent")),
+            _ => Err(self.error("unused marker byte")),
+        }
+    }
+
+    // the 8, 16 or 32 bit length of a str, by its marker's distance from str 8
+    fn length(&mut self, width: u8) -> Result<usize, Error> {
+        match width {
+            0 => self.u8(),
+            1 => self.u16(),
+            _ => self.u32(),
+        }
+    }
+
+    fn str(&mut self, length: usize) -> Result<String, Error> {
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("invalid UTF-8"))
+    }
+
+    fn elements(&mut self, count: usize) -> Result<Value, Error> {
+        let mut elements = Vec::with_capacity(self.count(count)?);
+        for _ in 0..count {
+            elements.push(self.value()?);
+        }
+        Ok(Value::Array(elements))
+    }
+
+    fn map(&mut self, count: usize) -> Result<Value, Error> {
+        let mut properties = Map::new();
+        for _ in 0..self.count(count)? {
+            let key = match self.value()? {
+                Value::String(key) => key,
+                _ => return Err(self.error("object keys must be strings")),
+            };
+            properties.insert(key, self.value()?);
+        }
+        Ok(Value::Object(properties))
+    }
+}
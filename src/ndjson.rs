@@ -0,0 +1,62 @@
+use crate::compression::Decoder;
+use crate::sax;
+use crate::{Error, Value};
+use std::io::BufRead;
+
+// yields one value per line; a malformed line doesn't stop the following ones.
+// Compressed input is decoded transparently, as in `streaming::parse_reader`
+pub struct Reader<R> {
+    reader: Decoder<R>,
+    line_number: usize,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Reader {
+            reader: Decoder::new(reader),
+            line_number: 0,
+            buffer: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line_number += 1;
+                    let Some(result) = parse_line(&self.buffer) else {
+                        continue; // blank lines are allowed
+                    };
+                    return Some(result.map_err(|e| Error::Line(self.line_number, Box::new(e))));
+                }
+                Err(e) => {
+                    self.done = true; // the underlying reader can't be trusted anymore
+                    return Some(Err(Error::Line(self.line_number + 1, Box::new(e.into()))));
+                }
+            }
+        }
+        None
+    }
+}
+
+// None for a blank line; shared with the async stream in `async_io`
+pub(crate) fn parse_line(line: &[u8]) -> Option<Result<Value, Error>> {
+    match std::str::from_utf8(line) {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(sax::build(line)),
+        Err(_) => Some(Err("Invalid UTF-8 in input".into())),
+    }
+}
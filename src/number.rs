@@ -0,0 +1,383 @@
+use crate::Error;
+#[cfg(feature = "serializer")]
+use crate::serializer::{SerializeOptions, write_f64};
+#[cfg(feature = "arbitrary_precision")]
+pub use decimal::{BigInt, Decimal};
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+// integers are kept exactly instead of going through f64, which only holds 2^53 exactly
+#[derive(Clone)]
+pub enum Number {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    // the source lexeme of a number that none of the above holds exactly
+    #[cfg(feature = "arbitrary_precision")]
+    Arbitrary(Box<str>),
+    // the exact source text, kept when parsing with raw numbers enabled
+    Raw(Box<str>),
+}
+
+impl Number {
+    // validates the lexeme but keeps it as written, e.g. "0.1000" or "1e2"
+    pub fn from_raw(text: &str) -> Result<Number, Error> {
+        text.parse::<Number>()?;
+        Ok(Number::Raw(text.into()))
+    }
+
+    pub fn is_raw(&self) -> bool {
+        matches!(self, Number::Raw(_))
+    }
+
+    pub fn as_raw(&self) -> Option<&str> {
+        match self {
+            Number::Raw(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    // the parsed value behind a raw lexeme, so raw numbers act like any other number
+    fn resolve(&self) -> Cow<'_, Number> {
+        match self {
+            Number::Raw(text) => Cow::Owned(text.parse().unwrap_or(Number::F64(f64::NAN))),
+            number => Cow::Borrowed(number),
+        }
+    }
+
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    pub fn is_f64(&self) -> bool {
+        matches!(*self.resolve(), Number::F64(_))
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn is_arbitrary(&self) -> bool {
+        matches!(*self.resolve(), Number::Arbitrary(_))
+    }
+
+    // a whole float that fits counts too, e.g. 3.0 but not 3.5
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Number::I64(n) => Some(n),
+            Number::U64(n) => i64::try_from(n).ok(),
+            Number::F64(n) => {
+                let in_range = n >= i64::MIN as f64 && n < i64::MAX as f64;
+                (n.fract() == 0.0 && in_range).then_some(n as i64)
+            }
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(_) => None,
+            Number::Raw(_) => self.resolve().as_i64(),
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Number::I64(n) => u64::try_from(n).ok(),
+            Number::U64(n) => Some(n),
+            Number::F64(n) => {
+                let in_range = n >= 0.0 && n < u64::MAX as f64;
+                (n.fract() == 0.0 && in_range).then_some(n as u64)
+            }
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(_) => None,
+            Number::Raw(_) => self.resolve().as_u64(),
+        }
+    }
+
+    // may round for integers beyond 2^53
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::I64(n) => n as f64,
+            Number::U64(n) => n as f64,
+            Number::F64(n) => n,
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(ref text) => text.parse().unwrap_or(f64::NAN),
+            Number::Raw(_) => self.resolve().as_f64(),
+        }
+    }
+
+    // the exact decimal value; None for NaN and infinity
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match *self {
+            Number::I64(n) => n.to_string().parse().ok(),
+            Number::U64(n) => n.to_string().parse().ok(),
+            Number::F64(n) => format!("{:e}", n).parse().ok(),
+            Number::Arbitrary(ref text) | Number::Raw(ref text) => text.parse().ok(),
+        }
+    }
+
+    // any whole number, however large; None when there is a fractional part
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_bigint(&self) -> Option<BigInt> {
+        self.as_decimal()?.to_bigint()
+    }
+
+    // one representation per numeric value, so 1, 1.0 and -0.0 vs 0 compare and hash alike
+    fn key(&self) -> Key {
+        match *self {
+            Number::I64(n) => Key::Int(n as i128),
+            Number::U64(n) => Key::Int(n as i128),
+            Number::F64(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(64) => Key::Int(n as i128),
+            Number::F64(n) if n.is_nan() => Key::Float(f64::NAN.to_bits()),
+            Number::F64(n) => Key::Float(n.to_bits()),
+            // never equal to the other variants, since it only holds values they can't
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(ref text) => Key::Decimal(text.parse().ok()),
+            Number::Raw(_) => self.resolve().key(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Key {
+    Int(i128),
+    Float(u64),
+    #[cfg(feature = "arbitrary_precision")]
+    Decimal(Option<Decimal>),
+}
+
+// the lexeme picks the variant: plain digits become U64 (I64 when negative),
+// anything else, or an integer too large for 64 bits, becomes F64
+impl FromStr for Number {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let is_integer = text
+            .strip_prefix('-')
+            .unwrap_or(text)
+            .bytes()
+            .all(|b| b.is_ascii_digit());
+        if is_integer {
+            if let Ok(n) = text.parse::<u64>() {
+                return Ok(Number::U64(n));
+            }
+            if let Ok(n) = text.parse::<i64>() {
+                return Ok(Number::I64(n));
+            }
+        }
+        let float = text
+            .parse::<f64>()
+            .map_err(|_| Error::from("Invalid number"))?;
+        #[cfg(feature = "arbitrary_precision")]
+        {
+            // keep the lexeme when the f64 would round or overflow
+            let exact: Decimal = text.parse()?;
+            if format!("{:e}", float).parse().ok() != Some(exact) {
+                return Ok(Number::Arbitrary(text.into()));
+            }
+        }
+        Ok(Number::F64(float))
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Number {}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::I64(n) => write!(f, "{}", n),
+            Number::U64(n) => write!(f, "{}", n),
+            #[cfg(feature = "serializer")]
+            Number::F64(n) => write_f64(f, *n, &SerializeOptions::default()),
+            // without the serializer, Rust's own shortest form, e.g. 1e21 isn't abbreviated
+            #[cfg(not(feature = "serializer"))]
+            Number::F64(n) => write!(f, "{}", n),
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(text) => f.write_str(text),
+            Number::Raw(text) => f.write_str(text),
+        }
+    }
+}
+
+impl fmt::Debug for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+macro_rules! from_signed {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(n: $ty) -> Self {
+                    Number::I64(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_unsigned {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(n: $ty) -> Self {
+                    Number::U64(n as u64)
+                }
+            }
+        )*
+    };
+}
+
+from_signed!(i8 i16 i32 i64 isize);
+from_unsigned!(u8 u16 u32 u64 usize);
+
+impl From<f32> for Number {
+    fn from(n: f32) -> Self {
+        Number::F64(n as f64)
+    }
+}
+
+impl From<f64> for Number {
+    fn from(n: f64) -> Self {
+        Number::F64(n)
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+mod decimal {
+    use crate::Error;
+    use std::fmt::{self, Write};
+    use std::str::FromStr;
+
+    // sign × digits × 10^exponent, with no leading or trailing zeros in `digits`,
+    // so equal values are always equal structs; zero is "0" with exponent 0
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Decimal {
+        negative: bool,
+        digits: String,
+        exponent: i64,
+    }
+
+    impl Decimal {
+        pub fn is_negative(&self) -> bool {
+            self.negative
+        }
+
+        pub fn digits(&self) -> &str {
+            &self.digits
+        }
+
+        pub fn exponent(&self) -> i64 {
+            self.exponent
+        }
+
+        pub fn is_integer(&self) -> bool {
+            self.exponent >= 0
+        }
+
+        pub fn to_bigint(&self) -> Option<BigInt> {
+            let zeros = usize::try_from(self.exponent).ok()?;
+            Some(BigInt {
+                negative: self.negative,
+                digits: format!("{}{}", self.digits, "0".repeat(zeros)),
+            })
+        }
+    }
+
+    // accepts JSON number syntax plus the `{:e}` output of f64, e.g. "1.5e-7"
+    impl FromStr for Decimal {
+        type Err = Error;
+
+        fn from_str(text: &str) -> Result<Self, Error> {
+            let invalid = || Error::from(format!("Invalid number '{}'", text));
+            let (negative, rest) = match text.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, text),
+            };
+            let (mantissa, exponent) = match rest.split_once(['e', 'E']) {
+                Some((mantissa, exponent)) => {
+                    let exponent = exponent.strip_prefix('+').unwrap_or(exponent);
+                    (mantissa, exponent.parse::<i64>().map_err(|_| invalid())?)
+                }
+                None => (rest, 0),
+            };
+            let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+            let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+            if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+                return Err(invalid());
+            }
+
+            let all = format!("{}{}", whole, fraction);
+            let significant = all.trim_start_matches('0');
+            let digits = significant.trim_end_matches('0');
+            if digits.is_empty() {
+                return Ok(Decimal {
+                    negative: false,
+                    digits: "0".to_string(),
+                    exponent: 0,
+                });
+            }
+            let trailing = (significant.len() - digits.len()) as i64;
+            let exponent = exponent
+                .checked_sub(fraction.len() as i64)
+                .and_then(|e| e.checked_add(trailing))
+                .ok_or_else(invalid)?;
+            Ok(Decimal {
+                negative,
+                digits: digits.to_string(),
+                exponent,
+            })
+        }
+    }
+
+    impl fmt::Display for Decimal {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            if self.negative {
+                f.write_char('-')?;
+            }
+            if self.exponent == 0 {
+                return f.write_str(&self.digits);
+            }
+            write!(f, "{}e{}", self.digits, self.exponent)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct BigInt {
+        negative: bool,
+        digits: String,
+    }
+
+    impl BigInt {
+        pub fn is_negative(&self) -> bool {
+            self.negative
+        }
+
+        // base-10 digits without the sign
+        pub fn digits(&self) -> &str {
+            &self.digits
+        }
+    }
+
+    impl fmt::Display for BigInt {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            if self.negative {
+                f.write_char('-')?;
+            }
+            f.write_str(&self.digits)
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{Array, Error, Map, Number, Token, TokenType, Value};
+use std::iter::Peekable;
+use std::slice::Iter;
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    raw_numbers: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // keep every number's source text as Number::Raw instead of parsing it
+    pub fn with_raw_numbers(mut self, raw_numbers: bool) -> Self {
+        self.raw_numbers = raw_numbers;
+        self
+    }
+}
+
+pub fn generate(tokens: &[Token]) -> Result<Value, Error> {
+    generate_with(tokens, &ParseOptions::default())
+}
+
+pub fn generate_with(tokens: &[Token], options: &ParseOptions) -> Result<Value, Error> {
+    generate_located(tokens, options).map_err(|(error, _)| error)
+}
+
+// like generate_with, but a failure comes with the index of the token it's about, or
+// tokens.len() when the input ended too soon. Tokens are only consumed once accepted,
+// so the offending one is always the next unread one.
+pub(crate) fn generate_located(
+    tokens: &[Token],
+    options: &ParseOptions,
+) -> Result<Value, (Error, usize)> {
+    let mut iter = tokens.iter().peekable();
+    let located = |error, iter: &Peekable<Iter<Token>>| (error, tokens.len() - iter.len());
+    let ast = parse(&mut iter, options).map_err(|error| located(error, &iter))?;
+    match iter.peek() {
+        Some(token) => {
+            let error = format!("Unexpected trailing token: '{}'", token.value).into();
+            Err(located(error, &iter))
+        }
+        None => Ok(ast),
+    }
+}
+
+fn parse(iter: &mut Peekable<Iter<Token>>, options: &ParseOptions) -> Result<Value, Error> {
+    let token = iter.peek().ok_or("Unexpected end of input")?;
+    match token.token_type {
+        TokenType::OpenObject => Ok(Value::Object(parse_object(iter, options)?)),
+        TokenType::OpenArray => Ok(Value::Array(parse_array(iter, options)?)),
+        TokenType::True
+        | TokenType::False
+        | TokenType::Null
+        | TokenType::Number
+        | TokenType::String => parse_basic(iter, options),
+        _ => Err("Invalid JSON token".into()),
+    }
+}
+
+fn parse_basic(iter: &mut Peekable<Iter<Token>>, options: &ParseOptions) -> Result<Value, Error> {
+    let token = *iter.peek().ok_or("Unexpected end of input")?;
+    let value = match token.token_type {
+        TokenType::True => Value::Bool(true),
+        TokenType::False => Value::Bool(false),
+        TokenType::Null => Value::Null,
+        TokenType::Number if options.raw_numbers => Value::Number(Number::from_raw(&token.value)?),
+        TokenType::Number => {
+            let number = token.value.parse::<Number>()?;
+            Value::Number(number)
+        }
+        TokenType::String => Value::String(token.value.clone()),
+        _ => return Err("Invalid token".into()),
+    };
+    iter.next();
+    Ok(value)
+}
+
+fn parse_object(iter: &mut Peekable<Iter<Token>>, options: &ParseOptions) -> Result<Map, Error> {
+    consume_token(iter, TokenType::OpenObject)?;
+    let mut properties = Map::new();
+    while let Some(token) = iter.peek() {
+        if token.token_type == TokenType::CloseObject {
+            break;
+        }
+        // resolve "key": value
+        let key = consume_string(iter)?;
+        consume_token(iter, TokenType::Colon)?;
+        let value = parse(iter, options)?;
+        properties.insert(key, value);
+
+        // check separator
+        match iter.peek().map(|t| t.token_type) {
+            Some(TokenType::Comma) => {
+                // check for trailing comma, before consuming the comma it's about
+                let mut ahead = iter.clone();
+                ahead.next();
+                if ahead.peek().map(|t| t.token_type) == Some(TokenType::CloseObject) {
+                    return Err("Trailing comma in object".into());
+                }
+                iter.next(); // consume comma
+            }
+            Some(TokenType::CloseObject) => break,
+            _ => return Err("Expected ',' or '}' in object".into()),
+        }
+    }
+    consume_token(iter, TokenType::CloseObject)?;
+    Ok(properties)
+}
+
+fn parse_array(iter: &mut Peekable<Iter<Token>>, options: &ParseOptions) -> Result<Array, Error> {
+    consume_token(iter, TokenType::OpenArray)?;
+    let mut elements = Vec::new();
+
+    while let Some(token) = iter.peek() {
+        if token.token_type == TokenType::CloseArray {
+            break;
+        }
+        let element = parse(iter, options)?;
+        elements.push(element);
+        // handle separator
+        match iter.peek().map(|t| t.token_type) {
+            Some(TokenType::Comma) => {
+                // check for trailing comma, before consuming the comma it's about
+                let mut ahead = iter.clone();
+                ahead.next();
+                if ahead.peek().map(|t| t.token_type) == Some(TokenType::CloseArray) {
+                    return Err("Trailing comma in array".into());
+                }
+                iter.next(); // consume comma
+            }
+            Some(TokenType::CloseArray) => break, // end of array parsing
+            _ => return Err("Expected ',' or ']' in array".into()),
+        }
+    }
+    consume_token(iter, TokenType::CloseArray)?;
+    Ok(elements)
+}
+
+fn consume_string(iter: &mut Peekable<Iter<Token>>) -> Result<String, Error> {
+    match iter.peek() {
+        Some(token) if token.token_type == TokenType::String => {
+            let key = token.value.clone();
+            iter.next();
+            Ok(key)
+        }
+        Some(_) => Err("Expected string".into()),
+        None => Err("Unexpected end of input".into()),
+    }
+}
+
+fn consume_token(iter: &mut Peekable<Iter<Token>>, expected: TokenType) -> Result<(), Error> {
+    match iter.peek() {
+        Some(token) if token.token_type == expected => {
+            iter.next();
+            Ok(())
+        }
+        Some(_) => Err(format!("Expected {:?}, found unexpected token", expected).into()),
+        None => Err("Unexpected end of input".into()),
+    }
+}
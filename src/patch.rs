@@ -0,0 +1,481 @@
+// JSON Patch (RFC 6902): applying a list of add/remove/replace/move/copy/test
+// operations, and computing a patch that turns one document into another
+use crate::pointer::{array_index, escape, tokens};
+use crate::{Error, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Patch {
+    operations: Vec<Operation>,
+}
+
+// paths are JSON Pointers, e.g. "/users/0/name"; "-" as the last array token means the end
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+impl Patch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, operation: Operation) {
+        self.operations.push(operation);
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    // reads the wire format: [{"op": "add", "path": "/a", "value": 1}, ...]
+    pub fn from_value(value: &Value) -> Result<Patch, Error> {
+        let elements = value.as_array().ok_or_else(|| {
+            Error::Conversion(format!(
+                "expected an array of operations, found {}",
+                value.type_name()
+            ))
+        })?;
+        let operations = elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                Operation::from_value(element).map_err(|error| Error::Patch(i, Box::new(error)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Patch { operations })
+    }
+
+    pub fn to_value(&self) -> Value {
+        self.operations.iter().map(Operation::to_value).collect()
+    }
+}
+
+impl Operation {
+    pub fn from_value(value: &Value) -> Result<Operation, Error> {
+        if !value.is_object() {
+            return Err(Error::Conversion(format!(
+                "expected an operation object, found {}",
+                value.type_name()
+            )));
+        }
+        let member = |name: &str| -> Result<String, Error> {
+            match value.get(name) {
+                Some(Value::String(text)) => Ok(text.clone()),
+                Some(other) => Err(Error::Conversion(format!(
+                    "expected a string for \"{}\", found {}",
+                    name,
+                    other.type_name()
+                ))),
+                None => Err(Error::Conversion(format!("missing \"{}\"", name))),
+            }
+        };
+        let operand = || -> Result<Value, Error> {
+            value
+                .get("value")
+                .cloned()
+                .ok_or_else(|| Error::Conversion("missing \"value\"".to_string()))
+        };
+        let path = member("path")?;
+        Ok(match member("op")?.as_str() {
+            "add" => Operation::Add {
+                path,
+                value: operand()?,
+            },
+            "remove" => Operation::Remove { path },
+            "replace" => Operation::Replace {
+                path,
+                value: operand()?,
+            },
+            "move" => Operation::Move {
+                from: member("from")?,
+                path,
+            },
+            "copy" => Operation::Copy {
+                from: member("from")?,
+                path,
+            },
+            "test" => Operation::Test {
+                path,
+                value: operand()?,
+            },
+            op => return Err(Error::Conversion(format!("unknown op '{}'", op))),
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        let (op, path) = match self {
+            Operation::Add { path, .. } => ("add", path),
+            Operation::Remove { path } => ("remove", path),
+            Operation::Replace { path, .. } => ("replace", path),
+            Operation::Move { path, .. } => ("move", path),
+            Operation::Copy { path, .. } => ("copy", path),
+            Operation::Test { path, .. } => ("test", path),
+        };
+        let mut object = Value::object();
+        object["op"] = op.into();
+        object["path"] = path.as_str().into();
+        match self {
+            Operation::Add { value, .. }
+            | Operation::Replace { value, .. }
+            | Operation::Test { value, .. } => object["value"] = value.clone(),
+            Operation::Move { from, .. } | Operation::Copy { from, .. } => {
+                object["from"] = from.as_str().into()
+            }
+            Operation::Remove { .. } => {}
+        }
+        object
+    }
+}
+
+impl FromIterator<Operation> for Patch {
+    fn from_iter<I: IntoIterator<Item = Operation>>(iter: I) -> Self {
+        Patch {
+            operations: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Patch {
+    type Item = Operation;
+    type IntoIter = std::vec::IntoIter<Operation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.operations.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Patch {
+    type Item = &'a Operation;
+    type IntoIter = std::slice::Iter<'a, Operation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.operations.iter()
+    }
+}
+
+// the wire format, as compact JSON
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_value(), f)
+    }
+}
+
+// all or nothing: if any operation fails (a "test" included), the document is left
+// as it was and the error says which operation it was
+pub fn apply(document: &mut Value, patch: &Patch) -> Result<(), Error> {
+    let mut patched = document.clone();
+    for (i, operation) in patch.operations.iter().enumerate() {
+        apply_operation(&mut patched, operation)
+            .map_err(|error| Error::Patch(i, Box::new(error)))?;
+    }
+    *document = patched;
+    Ok(())
+}
+
+fn apply_operation(document: &mut Value, operation: &Operation) -> Result<(), Error> {
+    match operation {
+        Operation::Add { path, value } => add(document, path, value.clone()),
+        Operation::Remove { path } => remove(document, path).map(drop),
+        Operation::Replace { path, value } => {
+            let target = lookup_mut(document, path)?;
+            *target = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            if from == path {
+                return lookup(document, from).map(drop);
+            }
+            if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                return Err(failed(format!(
+                    "cannot move {} into one of its own children",
+                    from
+                )));
+            }
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+        Operation::Copy { from, path } => {
+            let value = lookup(document, from)?.clone();
+            add(document, path, value)
+        }
+        Operation::Test { path, value } => {
+            let actual = lookup(document, path)?;
+            match actual.deep_eq_unordered(value) {
+                true => Ok(()),
+                false => Err(failed(format!(
+                    "test failed at {}: expected {}, found {}",
+                    location(path),
+                    value,
+                    actual
+                ))),
+            }
+        }
+    }
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), Error> {
+    let Some((parent, last)) = parent_mut(document, path)? else {
+        *document = value;
+        return Ok(());
+    };
+    match parent {
+        Value::Object(properties) => {
+            properties.insert(last, value);
+        }
+        Value::Array(elements) => {
+            let index = match last.as_str() {
+                "-" => elements.len(),
+                token => array_index(token)
+                    .filter(|&index| index <= elements.len())
+                    .ok_or_else(|| out_of_bounds(path))?,
+            };
+            elements.insert(index, value);
+        }
+        other => {
+            return Err(failed(format!(
+                "cannot add {} to {}",
+                path,
+                other.type_name()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, Error> {
+    let Some((parent, last)) = parent_mut(document, path)? else {
+        return Err(failed("cannot remove the root".to_string()));
+    };
+    match parent {
+        Value::Object(properties) => properties.remove(&last).ok_or_else(|| missing(path)),
+        Value::Array(elements) => {
+            let index = array_index(&last)
+                .filter(|&index| index < elements.len())
+                .ok_or_else(|| out_of_bounds(path))?;
+            Ok(elements.remove(index))
+        }
+        _ => Err(missing(path)),
+    }
+}
+
+fn lookup<'a>(document: &'a Value, path: &str) -> Result<&'a Value, Error> {
+    tokens(path).ok_or_else(|| invalid_pointer(path))?;
+    document.pointer(path).ok_or_else(|| missing(path))
+}
+
+// unlike Value::pointer_mut, a final "-" is not a valid target here
+fn lookup_mut<'a>(document: &'a mut Value, path: &str) -> Result<&'a mut Value, Error> {
+    if path.is_empty() {
+        return Ok(document);
+    }
+    match parent_mut(document, path)? {
+        None => Err(missing(path)),
+        Some((Value::Object(properties), last)) => {
+            properties.get_mut(&last).ok_or_else(|| missing(path))
+        }
+        Some((Value::Array(elements), last)) => array_index(&last)
+            .and_then(|index| elements.get_mut(index))
+            .ok_or_else(|| out_of_bounds(path)),
+        Some(_) => Err(missing(path)),
+    }
+}
+
+// the container the last token refers into, and that token; None for the root pointer ""
+fn parent_mut<'a>(
+    document: &'a mut Value,
+    path: &str,
+) -> Result<Option<(&'a mut Value, String)>, Error> {
+    let mut tokens = tokens(path).ok_or_else(|| invalid_pointer(path))?;
+    let Some(last) = tokens.pop() else {
+        return Ok(None);
+    };
+    let mut node = document;
+    for token in &tokens {
+        node = match node {
+            Value::Object(properties) => properties.get_mut(token),
+            Value::Array(elements) => array_index(token).and_then(|index| elements.get_mut(index)),
+            _ => None,
+        }
+        .ok_or_else(|| missing(path))?;
+    }
+    Ok(Some((node, last)))
+}
+
+fn failed(message: String) -> Error {
+    Error::Conversion(message)
+}
+
+fn invalid_pointer(path: &str) -> Error {
+    failed(format!("invalid JSON Pointer '{}'", path))
+}
+
+fn missing(path: &str) -> Error {
+    failed(format!("no value at {}", location(path)))
+}
+
+fn out_of_bounds(path: &str) -> Error {
+    failed(format!("array index out of bounds at {}", path))
+}
+
+fn location(path: &str) -> &str {
+    if path.is_empty() { "the root" } else { path }
+}
+
+// a patch that turns `from` into `to`: objects are compared key by key and only the
+// changed leaves are replaced; arrays keep their longest common subsequence in place, so
+// inserting or removing elements anywhere costs one operation per element
+pub fn diff(from: &Value, to: &Value) -> Patch {
+    let mut patch = Patch::new();
+    diff_at(from, to, &mut String::new(), &mut patch.operations);
+    patch
+}
+
+fn diff_at(from: &Value, to: &Value, path: &mut String, out: &mut Vec<Operation>) {
+    match (from, to) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, _) in old {
+                if !new.contains_key(key) {
+                    out.push(Operation::Remove {
+                        path: child(path, key),
+                    });
+                }
+            }
+            for (key, value) in new {
+                match old.get(key) {
+                    Some(previous) => {
+                        let length = path.len();
+                        path.push('/');
+                        path.push_str(&escape(key));
+                        diff_at(previous, value, path, out);
+                        path.truncate(length);
+                    }
+                    None => out.push(Operation::Add {
+                        path: child(path, key),
+                        value: value.clone(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => diff_arrays(old, new, path, out),
+        _ if from.deep_eq_unordered(to) => {}
+        _ => out.push(Operation::Replace {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+    }
+}
+
+fn diff_arrays(old: &[Value], new: &[Value], path: &mut String, out: &mut Vec<Operation>) {
+    let same = |(a, b): (&Value, &Value)| a.deep_eq_unordered(b);
+    let prefix = old.iter().zip(new).take_while(|&pair| same(pair)).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&pair| same(pair))
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    // between two kept elements, old and new elements are paired up and diffed in place;
+    // whatever is left over is removed or added. `index` is where the next element sits
+    // in the array as patched so far
+    let mut index = prefix;
+    let (mut i, mut j) = (0, 0);
+    let mut kept = common_subsequence(old, new);
+    kept.push((old.len(), new.len()));
+    // each gap costs at least as many operations as its longer side; when the kept
+    // elements don't save anything over comparing position by position (e.g. a reversed
+    // array), they are dropped
+    let mut gaps = 0;
+    let (mut last_i, mut last_j) = (0, 0);
+    for &(next_i, next_j) in &kept {
+        gaps += (next_i - last_i).max(next_j - last_j);
+        (last_i, last_j) = (next_i + 1, next_j + 1);
+    }
+    let positional =
+        old.iter().zip(new).filter(|&pair| !same(pair)).count() + old.len().abs_diff(new.len());
+    if positional <= gaps {
+        kept = vec![(old.len(), new.len())];
+    }
+    for (next_i, next_j) in kept {
+        let paired = (next_i - i).min(next_j - j);
+        for k in 0..paired {
+            let length = path.len();
+            path.push('/');
+            path.push_str(&(index + k).to_string());
+            diff_at(&old[i + k], &new[j + k], path, out);
+            path.truncate(length);
+        }
+        index += paired;
+        for _ in paired..next_i - i {
+            out.push(Operation::Remove {
+                path: child(path, &index.to_string()),
+            });
+        }
+        for value in &new[j + paired..next_j] {
+            out.push(Operation::Add {
+                path: child(path, &index.to_string()),
+                value: value.clone(),
+            });
+            index += 1;
+        }
+        // step over the kept element itself
+        index += 1;
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+}
+
+// index pairs of a longest common subsequence; above MAX_LCS_CELLS the table would be too
+// big, and the arrays are then just compared position by position
+const MAX_LCS_CELLS: usize = 1 << 20;
+
+fn common_subsequence(old: &[Value], new: &[Value]) -> Vec<(usize, usize)> {
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_LCS_CELLS {
+        return Vec::new();
+    }
+    // lengths[i][j] is the LCS length of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i].deep_eq_unordered(&new[j]) {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i].deep_eq_unordered(&new[j]) {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn child(path: &str, token: &str) -> String {
+    format!("{}/{}", path, escape(token))
+}
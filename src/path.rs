@@ -0,0 +1,290 @@
+// jq/JS-style paths: users[0].name, or ["odd.key"] for keys with dots or brackets
+use crate::value::mismatch;
+use crate::{Error, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl From<&str> for PathSegment {
+    fn from(key: &str) -> Self {
+        PathSegment::Key(key.to_string())
+    }
+}
+
+impl From<String> for PathSegment {
+    fn from(key: String) -> Self {
+        PathSegment::Key(key)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    // "" is the root itself
+    pub fn parse(path: &str) -> Result<Self, Error> {
+        let invalid =
+            |reason: &str| -> Error { format!("Invalid path '{}': {}", path, reason).into() };
+        let mut segments = Vec::new();
+        let mut rest = path;
+        let mut first = true;
+        while !rest.is_empty() {
+            if let Some(bracketed) = rest.strip_prefix('[') {
+                let close = bracketed.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+                let inner = &bracketed[..close];
+                let segment = match inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    Some(key) => PathSegment::Key(key.to_string()),
+                    None if !inner.is_empty() && inner.bytes().all(|b| b.is_ascii_digit()) => {
+                        PathSegment::Index(inner.parse().map_err(|_| invalid("index too large"))?)
+                    }
+                    None => return Err(invalid("expected an index or a quoted key in brackets")),
+                };
+                segments.push(segment);
+                rest = &bracketed[close + 1..];
+            } else {
+                if !first {
+                    rest = rest
+                        .strip_prefix('.')
+                        .ok_or_else(|| invalid("expected '.' or '['"))?;
+                }
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                if end == 0 {
+                    return Err(invalid("empty key"));
+                }
+                segments.push(PathSegment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+            first = false;
+        }
+        Ok(JsonPath { segments })
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+}
+
+// writes the same syntax parse() reads, quoting keys that would not survive bare
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Key(key) if key.is_empty() || key.contains(['.', '[', ']', '"']) => {
+                    write!(f, "[\"{}\"]", key)?
+                }
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// a path with wildcards, for matching rather than addressing: "$.*.password",
+// "users[*].token", "$..secret"; `*` is any one key or index, `..` any number of levels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPattern {
+    segments: Vec<PatternSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternSegment {
+    Key(String),
+    Index(usize),
+    AnyChild,
+    AnyDepth,
+}
+
+impl PathPattern {
+    // the leading "$" is optional
+    pub fn parse(pattern: &str) -> Result<Self, Error> {
+        let invalid =
+            |reason: &str| -> Error { format!("Invalid pattern '{}': {}", pattern, reason).into() };
+        let mut segments = Vec::new();
+        let mut rest = pattern.strip_prefix('$').unwrap_or(pattern);
+        let mut first = rest.len() == pattern.len(); // a bare first key needs no '.'
+        while !rest.is_empty() {
+            if let Some(bracketed) = rest.strip_prefix('[') {
+                let close = bracketed.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+                let inner = &bracketed[..close];
+                let segment = match inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    Some(key) => PatternSegment::Key(key.to_string()),
+                    None if inner == "*" => PatternSegment::AnyChild,
+                    None if !inner.is_empty() && inner.bytes().all(|b| b.is_ascii_digit()) => {
+                        PatternSegment::Index(
+                            inner.parse().map_err(|_| invalid("index too large"))?,
+                        )
+                    }
+                    None => {
+                        return Err(invalid(
+                            "expected an index, '*' or a quoted key in brackets",
+                        ));
+                    }
+                };
+                segments.push(segment);
+                rest = &bracketed[close + 1..];
+            } else {
+                if let Some(after) = rest.strip_prefix("..") {
+                    segments.push(PatternSegment::AnyDepth);
+                    rest = after;
+                    if rest.starts_with('[') {
+                        continue;
+                    }
+                } else if !first {
+                    rest = rest
+                        .strip_prefix('.')
+                        .ok_or_else(|| invalid("expected '.', '..' or '['"))?;
+                }
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let segment = match &rest[..end] {
+                    "" => return Err(invalid("empty key")),
+                    "*" => PatternSegment::AnyChild,
+                    key => PatternSegment::Key(key.to_string()),
+                };
+                segments.push(segment);
+                rest = &rest[end..];
+            }
+            first = false;
+        }
+        Ok(PathPattern { segments })
+    }
+
+    pub fn matches(&self, path: &JsonPath) -> bool {
+        matches_from(&self.segments, path.segments())
+    }
+}
+
+fn matches_from(pattern: &[PatternSegment], path: &[PathSegment]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((PatternSegment::AnyDepth, rest)) => {
+            (0..=path.len()).any(|skip| matches_from(rest, &path[skip..]))
+        }
+        Some((expected, rest)) => path.split_first().is_some_and(|(segment, tail)| {
+            let accepted = match (expected, segment) {
+                (PatternSegment::AnyChild, _) => true,
+                (PatternSegment::Key(a), PathSegment::Key(b)) => a == b,
+                (PatternSegment::Index(a), PathSegment::Index(b)) => a == b,
+                _ => false,
+            };
+            accepted && matches_from(rest, tail)
+        }),
+    }
+}
+
+impl Value {
+    // None for a missing node as well as for a malformed path
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        let path = JsonPath::parse(path).ok()?;
+        let mut node = self;
+        for segment in path.segments() {
+            node = match segment {
+                PathSegment::Key(key) => node.get(key)?,
+                PathSegment::Index(index) => node.get(*index)?,
+            };
+        }
+        Some(node)
+    }
+
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Value> {
+        let path = JsonPath::parse(path).ok()?;
+        let mut node = self;
+        for segment in path.segments() {
+            node = match segment {
+                PathSegment::Key(key) => node.get_mut(key)?,
+                PathSegment::Index(index) => node.get_mut(*index)?,
+            };
+        }
+        Some(node)
+    }
+
+    // creates missing objects and arrays on the way, padding arrays with null;
+    // returns the replaced value, or None if the slot was empty or null
+    pub fn set_path<V: Into<Value>>(
+        &mut self,
+        path: &str,
+        value: V,
+    ) -> Result<Option<Value>, Error> {
+        let path = JsonPath::parse(path)?;
+        let mut node = self;
+        for segment in path.segments() {
+            node = step(node, segment)?;
+        }
+        let old = std::mem::replace(node, value.into());
+        Ok((!old.is_null()).then_some(old))
+    }
+
+    pub fn remove_path(&mut self, path: &str) -> Option<Value> {
+        let path = JsonPath::parse(path).ok()?;
+        let (last, parents) = path.segments().split_last()?;
+        let mut node = self;
+        for segment in parents {
+            node = match segment {
+                PathSegment::Key(key) => node.get_mut(key)?,
+                PathSegment::Index(index) => node.get_mut(*index)?,
+            };
+        }
+        match last {
+            PathSegment::Key(key) => node.remove(key),
+            PathSegment::Index(index) => node.remove(*index),
+        }
+    }
+}
+
+// moves one level down, creating the container if this is the first path through it
+pub(crate) fn step<'v>(node: &'v mut Value, segment: &PathSegment) -> Result<&'v mut Value, Error> {
+    match segment {
+        PathSegment::Key(key) => {
+            if node.is_null() {
+                *node = Value::object();
+            }
+            match node {
+                Value::Object(properties) => Ok(properties.entry(key.as_str()).or_default()),
+                _ => Err(mismatch("object", node)),
+            }
+        }
+        PathSegment::Index(index) => {
+            if node.is_null() {
+                *node = Value::array();
+            }
+            match node {
+                Value::Array(elements) => {
+                    if elements.len() <= *index {
+                        elements.resize(index + 1, Value::Null);
+                    }
+                    Ok(&mut elements[*index])
+                }
+                _ => Err(mismatch("array", node)),
+            }
+        }
+    }
+}
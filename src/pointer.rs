@@ -0,0 +1,124 @@
+// JSON Pointer (RFC 6901): "/users/0/name", with "~1" for '/' and "~0" for '~'
+use crate::{Array, Error, Map, Value};
+
+impl Value {
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        let mut node = self;
+        for token in tokens(pointer)? {
+            node = match node {
+                Value::Object(properties) => properties.get(&token)?,
+                Value::Array(elements) => elements.get(array_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    // a final "-" on an array appends a null element and returns it
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        let tokens = tokens(pointer)?;
+        let last = tokens.len().checked_sub(1);
+        let mut node = self;
+        for (i, token) in tokens.iter().enumerate() {
+            node = match node {
+                Value::Object(properties) => properties.get_mut(token)?,
+                Value::Array(elements) => {
+                    let index = if token == "-" && Some(i) == last {
+                        elements.push(Value::Null);
+                        elements.len() - 1
+                    } else {
+                        array_index(token)?
+                    };
+                    elements.get_mut(index)?
+                }
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+}
+
+// typed lookups for loading config, with errors such as
+// "expected string at /user/name, found number"
+macro_rules! typed_getters {
+    ($($required:ident $optional:ident $as:ident -> $ty:ty, $expected:literal;)*) => {
+        impl Value {
+            $(
+                pub fn $required(&self, pointer: &str) -> Result<$ty, Error> {
+                    let value = self.pointer(pointer).ok_or_else(|| {
+                        Error::Conversion(format!("missing {} at {}", $expected, location(pointer)))
+                    })?;
+                    value.$as().ok_or_else(|| mismatch_at($expected, pointer, value))
+                }
+
+                // missing and null both give None; any other type is still an error
+                pub fn $optional(&self, pointer: &str) -> Result<Option<$ty>, Error> {
+                    match self.pointer(pointer) {
+                        None | Some(Value::Null) => Ok(None),
+                        Some(value) => value
+                            .$as()
+                            .map(Some)
+                            .ok_or_else(|| mismatch_at($expected, pointer, value)),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+typed_getters! {
+    required_str optional_str as_str -> &str, "string";
+    required_i64 optional_i64 as_i64 -> i64, "integer";
+    required_u64 optional_u64 as_u64 -> u64, "non-negative integer";
+    required_f64 optional_f64 as_f64 -> f64, "number";
+    required_bool optional_bool as_bool -> bool, "boolean";
+    required_array optional_array as_array -> &Array, "array";
+    required_object optional_object as_object -> &Map, "object";
+}
+
+fn mismatch_at(expected: &str, pointer: &str, value: &Value) -> Error {
+    Error::Conversion(format!(
+        "expected {} at {}, found {}",
+        expected,
+        location(pointer),
+        value.type_name()
+    ))
+}
+
+fn location(pointer: &str) -> &str {
+    if pointer.is_empty() {
+        "the root"
+    } else {
+        pointer
+    }
+}
+
+// None when the pointer is neither empty nor starts with '/'
+pub(crate) fn tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/')?;
+    Some(rest.split('/').map(unescape).collect())
+}
+
+// the reverse of unescape, for building pointers
+pub(crate) fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+// "~01" must become "~1", so '~1' is replaced before '~0'
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+// digits only, and no leading zeros
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty() || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok()
+}
@@ -0,0 +1,60 @@
+// validated JSON text that is carried through a Value untouched
+use crate::Value;
+#[cfg(feature = "serializer")]
+use crate::serializer;
+#[cfg(feature = "parser")]
+use crate::{Error, parse_str};
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RawValue {
+    json: Box<str>,
+}
+
+impl RawValue {
+    // surrounding whitespace is dropped, everything inside is kept byte for byte
+    #[cfg(feature = "parser")]
+    pub fn from_string<S: Into<String>>(json: S) -> Result<RawValue, Error> {
+        let json = json.into();
+        parse_str(&json)?;
+        Ok(RawValue {
+            json: json.trim().into(),
+        })
+    }
+
+    pub fn get(&self) -> &str {
+        &self.json
+    }
+
+    #[cfg(feature = "parser")]
+    pub fn parse(&self) -> Result<Value, Error> {
+        parse_str(&self.json)
+    }
+}
+
+#[cfg(feature = "serializer")]
+impl Value {
+    pub fn to_raw_value(&self) -> RawValue {
+        RawValue {
+            json: serializer::to_string(self).into(),
+        }
+    }
+}
+
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.json)
+    }
+}
+
+impl fmt::Debug for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RawValue({})", self.json)
+    }
+}
+
+impl From<RawValue> for Value {
+    fn from(raw: RawValue) -> Self {
+        Value::Raw(raw)
+    }
+}
@@ -0,0 +1,42 @@
+// masking sensitive fields before a document is logged
+use crate::Value;
+use crate::path::{JsonPath, PathPattern, PathSegment};
+
+impl Value {
+    // replaces every node whose path matches one of the patterns and returns how many
+    // were replaced; a matched object or array is replaced whole, without looking inside
+    pub fn redact<V: Into<Value>>(&mut self, patterns: &[PathPattern], replacement: V) -> usize {
+        redact_at(self, &mut JsonPath::root(), patterns, &replacement.into())
+    }
+}
+
+fn redact_at(
+    node: &mut Value,
+    path: &mut JsonPath,
+    patterns: &[PathPattern],
+    replacement: &Value,
+) -> usize {
+    if patterns.iter().any(|pattern| pattern.matches(path)) {
+        *node = replacement.clone();
+        return 1;
+    }
+    let mut count = 0;
+    match node {
+        Value::Object(properties) => {
+            for (key, child) in properties.iter_mut() {
+                path.push(PathSegment::Key(key.clone()));
+                count += redact_at(child, path, patterns, replacement);
+                path.pop();
+            }
+        }
+        Value::Array(elements) => {
+            for (i, child) in elements.iter_mut().enumerate() {
+                path.push(PathSegment::Index(i));
+                count += redact_at(child, path, patterns, replacement);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    count
+}
@@ -0,0 +1,124 @@
+// inlining intra-document references: {"$ref": "#/definitions/address"} is replaced by
+// a copy of the subtree it points to, as in JSON Schema and OpenAPI documents
+use crate::pointer::escape;
+use crate::{Error, Value};
+
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    max_expansions: usize,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        ResolveOptions {
+            max_expansions: 10_000,
+        }
+    }
+}
+
+impl ResolveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // every inlined $ref counts, nested ones too; refs that fan out can otherwise
+    // blow a small document up exponentially
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+}
+
+impl Value {
+    pub fn resolve_refs(&self) -> Result<Value, Error> {
+        self.resolve_refs_with(&ResolveOptions::default())
+    }
+
+    // refs are looked up in this document as JSON Pointers after the '#'; refs to other
+    // documents are left as they are, and keys next to a "$ref" are dropped with it.
+    // A ref that leads back into itself can't be inlined and is an error.
+    pub fn resolve_refs_with(&self, options: &ResolveOptions) -> Result<Value, Error> {
+        let mut resolver = Resolver {
+            root: self,
+            options,
+            expansions: 0,
+            active: Vec::new(),
+        };
+        resolver.resolve(self, &mut String::new())
+    }
+}
+
+struct Resolver<'a> {
+    root: &'a Value,
+    options: &'a ResolveOptions,
+    expansions: usize,
+    active: Vec<&'a str>, // the refs being inlined right now, outermost first
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve(&mut self, node: &'a Value, path: &mut String) -> Result<Value, Error> {
+        match node {
+            Value::Object(properties) => {
+                if let Some(Value::String(reference)) = properties.get("$ref")
+                    && let Some(pointer) = reference.strip_prefix('#')
+                {
+                    return self.inline(reference, pointer, path);
+                }
+                let mut resolved = Value::object();
+                for (key, child) in properties {
+                    let length = path.len();
+                    path.push('/');
+                    path.push_str(&escape(key));
+                    resolved[key.as_str()] = self.resolve(child, path)?;
+                    path.truncate(length);
+                }
+                Ok(resolved)
+            }
+            Value::Array(elements) => {
+                let mut resolved = Vec::with_capacity(elements.len());
+                for (i, child) in elements.iter().enumerate() {
+                    let length = path.len();
+                    path.push('/');
+                    path.push_str(&i.to_string());
+                    resolved.push(self.resolve(child, path)?);
+                    path.truncate(length);
+                }
+                Ok(Value::Array(resolved))
+            }
+            value => Ok(value.clone()),
+        }
+    }
+
+    fn inline(
+        &mut self,
+        reference: &'a str,
+        pointer: &str,
+        path: &mut String,
+    ) -> Result<Value, Error> {
+        let at = if path.is_empty() { "/" } else { path.as_str() };
+        if self.active.contains(&reference) {
+            let mut chain = self.active.clone();
+            chain.push(reference);
+            let start = chain.iter().position(|r| *r == reference).unwrap_or(0);
+            return Err(Error::Conversion(format!(
+                "circular $ref at {}: {}",
+                at,
+                chain[start..].join(" -> ")
+            )));
+        }
+        self.expansions += 1;
+        if self.expansions > self.options.max_expansions {
+            return Err(Error::Conversion(format!(
+                "more than {} $ref expansions, stopped at {}",
+                self.options.max_expansions, at
+            )));
+        }
+        let target = self.root.pointer(pointer).ok_or_else(|| {
+            Error::Conversion(format!("cannot resolve $ref '{}' at {}", reference, at))
+        })?;
+        self.active.push(reference);
+        let resolved = self.resolve(target, path);
+        self.active.pop();
+        resolved
+    }
+}
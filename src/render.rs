@@ -0,0 +1,364 @@
+// ways to look at a document other than as JSON text: a collapsible HTML tree for
+// dashboards and reports, a Graphviz graph of its structure, and a `tree`-style outline
+// for the terminal
+use crate::Value;
+use crate::serializer::{SerializeOptions, write_string, write_value};
+use std::fmt::Write;
+
+// the stylesheet to_html includes unless told not to; the classes are stable, so a page
+// can leave it out and style them itself
+pub const HTML_STYLE: &str = "\
+.json-tree { font-family: ui-monospace, monospace; font-size: 13px; line-height: 1.5; }
+.json-tree ul { list-style: none; margin: 0; padding-left: 1.5em; }
+.json-tree summary { cursor: pointer; }
+.json-key { color: #881391; }
+.json-index { color: #888; }
+.json-string { color: #c41a16; }
+.json-number { color: #1c00cf; }
+.json-bool, .json-null { color: #0d22aa; font-weight: bold; }
+.json-punct { color: #444; }
+.json-count { color: #888; font-style: italic; margin-left: 0.5em; }
+.json-tree details[open] > summary > .json-count { display: none; }
+";
+
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    open_depth: Option<usize>,
+    style: bool,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        HtmlOptions {
+            open_depth: None,
+            style: true,
+        }
+    }
+}
+
+impl HtmlOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // objects and arrays deeper than this start collapsed (the root is at depth 0);
+    // None, the default, opens everything
+    pub fn with_open_depth(mut self, open_depth: Option<usize>) -> Self {
+        self.open_depth = open_depth;
+        self
+    }
+
+    // whether a <style> block with HTML_STYLE comes first
+    pub fn with_style(mut self, style: bool) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+pub fn to_html(value: &Value) -> String {
+    to_html_with(value, &HtmlOptions::default())
+}
+
+// an HTML fragment: every object and array is a <details> element whose <summary> shows
+// its key and size, and every scalar a <span> classed by type, e.g. "json-string"
+pub fn to_html_with(value: &Value, options: &HtmlOptions) -> String {
+    let mut out = String::new();
+    if options.style {
+        out.push_str("<style>\n");
+        out.push_str(HTML_STYLE);
+        out.push_str("</style>\n");
+    }
+    out.push_str("<div class=\"json-tree\">\n");
+    html_node(&mut out, "", value, 0, options);
+    out.push_str("\n</div>\n");
+    out
+}
+
+// `label` is the already escaped key or index markup in front of the value; what's
+// written ends without a line break, so a closing </li> can follow it directly
+fn html_node(out: &mut String, label: &str, value: &Value, depth: usize, options: &HtmlOptions) {
+    let (open, close, count, noun) = match value {
+        Value::Object(properties) => ('{', '}', properties.len(), "member"),
+        Value::Array(elements) => ('[', ']', elements.len(), "item"),
+        Value::Raw(raw) => {
+            let value = raw.parse().unwrap_or_default();
+            return html_node(out, label, &value, depth, options);
+        }
+        scalar => {
+            let _ = write!(out, "{}", label);
+            html_scalar(out, scalar);
+            return;
+        }
+    };
+    if count == 0 {
+        let _ = write!(
+            out,
+            "{}<span class=\"json-punct\">{}{}</span>",
+            label, open, close
+        );
+        return;
+    }
+    let is_open = options
+        .open_depth
+        .is_none_or(|open_depth| depth < open_depth);
+    let plural = if count == 1 { "" } else { "s" };
+    let _ = writeln!(
+        out,
+        "<details{}><summary>{}<span class=\"json-punct\">{}</span>\
+         <span class=\"json-count\">{} {}{}</span></summary>",
+        if is_open { " open" } else { "" },
+        label,
+        open,
+        count,
+        noun,
+        plural
+    );
+    out.push_str("<ul>\n");
+    let mut label = String::new();
+    match value {
+        Value::Object(properties) => {
+            for (key, child) in properties {
+                let mut quoted = String::new();
+                let _ = write_string(&mut quoted, key, &SerializeOptions::default());
+                label.clear();
+                let _ = write!(
+                    label,
+                    "<span class=\"json-key\">{}</span><span class=\"json-punct\">: </span>",
+                    escape(&quoted)
+                );
+                out.push_str("<li>");
+                html_node(out, &label, child, depth + 1, options);
+                out.push_str("</li>\n");
+            }
+        }
+        Value::Array(elements) => {
+            for (i, child) in elements.iter().enumerate() {
+                label.clear();
+                let _ = write!(
+                    label,
+                    "<span class=\"json-index\">{}</span><span class=\"json-punct\">: </span>",
+                    i
+                );
+                out.push_str("<li>");
+                html_node(out, &label, child, depth + 1, options);
+                out.push_str("</li>\n");
+            }
+        }
+        _ => unreachable!("only containers get this far"),
+    }
+    let _ = write!(
+        out,
+        "</ul><span class=\"json-punct\">{}</span></details>",
+        close
+    );
+}
+
+fn html_scalar(out: &mut String, value: &Value) {
+    let class = match value {
+        Value::String(_) => "json-string",
+        Value::Number(_) => "json-number",
+        Value::Bool(_) => "json-bool",
+        _ => "json-null",
+    };
+    let mut text = String::new();
+    let _ = write_value(&mut text, value, &SerializeOptions::default(), 0);
+    let _ = write!(out, "<span class=\"{}\">{}</span>", class, escape(&text));
+}
+
+// a DOT digraph with a node per value, in document order: objects are drawn as "{}" and
+// arrays as "[]" ellipses, scalars as boxes holding their JSON, and each edge is labelled
+// with the key or index it follows. Render with e.g. `dot -Tsvg tree.dot > tree.svg`.
+pub fn to_dot(value: &Value) -> String {
+    let mut out = String::from("digraph json {\n");
+    out.push_str("  node [fontname=\"monospace\"];\n");
+    out.push_str("  edge [fontname=\"monospace\", fontsize=10];\n");
+    dot_node(&mut out, value, &mut 0);
+    out.push_str("}\n");
+    out
+}
+
+// writes the node and everything below it, returning its id
+fn dot_node(out: &mut String, value: &Value, next_id: &mut usize) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    match value {
+        Value::Object(properties) => {
+            let _ = writeln!(out, "  n{} [label=\"{{}}\", shape=ellipse];", id);
+            for (key, child) in properties {
+                let child_id = dot_node(out, child, next_id);
+                let _ = writeln!(
+                    out,
+                    "  n{} -> n{} [label=\"{}\"];",
+                    id,
+                    child_id,
+                    dot_escape(key)
+                );
+            }
+        }
+        Value::Array(elements) => {
+            let _ = writeln!(out, "  n{} [label=\"[]\", shape=ellipse];", id);
+            for (i, child) in elements.iter().enumerate() {
+                let child_id = dot_node(out, child, next_id);
+                let _ = writeln!(out, "  n{} -> n{} [label=\"[{}]\"];", id, child_id, i);
+            }
+        }
+        Value::Raw(raw) => {
+            *next_id -= 1;
+            return dot_node(out, &raw.parse().unwrap_or_default(), next_id);
+        }
+        scalar => {
+            let mut text = String::new();
+            let _ = write_value(&mut text, scalar, &SerializeOptions::default(), 0);
+            let _ = writeln!(
+                out,
+                "  n{} [label=\"{}\", shape=box];",
+                id,
+                dot_escape(&text)
+            );
+        }
+    }
+    id
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    max_depth: Option<usize>,
+    max_value_chars: Option<usize>,
+}
+
+impl TreeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // how many levels below the root to show; deeper containers are summarized with a
+    // trailing "…"
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // scalars whose JSON is longer than this many characters are cut short with "…"
+    pub fn with_max_value_chars(mut self, max_value_chars: Option<usize>) -> Self {
+        self.max_value_chars = max_value_chars;
+        self
+    }
+}
+
+pub fn to_tree_string(value: &Value) -> String {
+    to_tree_string_with(value, &TreeOptions::default())
+}
+
+// one line per value, drawn like the `tree` command:
+//
+//   object (2)
+//   ├── name: "John"
+//   └── tags: array (2)
+//       ├── [0]: "a"
+//       └── [1]: "b"
+//
+// keys are shown as they are, unless they're empty or hold control characters, which
+// would break the layout; then they're quoted
+pub fn to_tree_string_with(value: &Value, options: &TreeOptions) -> String {
+    let mut out = String::new();
+    tree_node(&mut out, value, &mut String::new(), 0, options);
+    out
+}
+
+// the value's own text, then a line per child, each under `prefix`
+fn tree_node(
+    out: &mut String,
+    value: &Value,
+    prefix: &mut String,
+    depth: usize,
+    options: &TreeOptions,
+) {
+    let (kind, count) = match value {
+        Value::Object(properties) => ("object", properties.len()),
+        Value::Array(elements) => ("array", elements.len()),
+        Value::Raw(raw) => {
+            let value = raw.parse().unwrap_or_default();
+            return tree_node(out, &value, prefix, depth, options);
+        }
+        scalar => {
+            let mut text = String::new();
+            let _ = write_value(&mut text, scalar, &SerializeOptions::default(), 0);
+            match options.max_value_chars {
+                Some(max) if text.chars().count() > max => {
+                    out.extend(text.chars().take(max));
+                    out.push('…');
+                }
+                _ => out.push_str(&text),
+            }
+            out.push('\n');
+            return;
+        }
+    };
+    match count {
+        0 if kind == "object" => out.push_str("{}\n"),
+        0 => out.push_str("[]\n"),
+        _ if options.max_depth.is_some_and(|max| depth >= max) => {
+            let _ = writeln!(out, "{} ({}) …", kind, count);
+        }
+        _ => {
+            let _ = writeln!(out, "{} ({})", kind, count);
+            let children: Vec<(String, &Value)> = match value {
+                Value::Object(properties) => properties
+                    .iter()
+                    .map(|(key, child)| (tree_key(key), child))
+                    .collect(),
+                Value::Array(elements) => elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, child)| (format!("[{}]", i), child))
+                    .collect(),
+                _ => unreachable!("only containers get this far"),
+            };
+            let last = children.len() - 1;
+            for (i, (label, child)) in children.into_iter().enumerate() {
+                let (branch, indent) = match i == last {
+                    true => ("└── ", "    "),
+                    false => ("├── ", "│   "),
+                };
+                let _ = write!(out, "{}{}{}: ", prefix, branch, label);
+                let length = prefix.len();
+                prefix.push_str(indent);
+                tree_node(out, child, prefix, depth + 1, options);
+                prefix.truncate(length);
+            }
+        }
+    }
+}
+
+fn tree_key(key: &str) -> String {
+    match key.is_empty() || key.chars().any(char::is_control) {
+        true => {
+            let mut quoted = String::new();
+            let _ = write_string(&mut quoted, key, &SerializeOptions::default());
+            quoted
+        }
+        false => key.to_string(),
+    }
+}
+
+// for inside a quoted DOT string, where a backslash would start an escape like \N
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
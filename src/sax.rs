@@ -0,0 +1,278 @@
+use crate::{Array, Error, Map, Number, Token, TokenType, Value, lexer};
+use std::collections::VecDeque;
+use std::iter::Peekable;
+
+pub trait ContentHandler {
+    fn on_object_start(&mut self) {}
+    fn on_object_end(&mut self) {}
+    fn on_array_start(&mut self) {}
+    fn on_array_end(&mut self) {}
+    fn on_key(&mut self, _key: &str) {}
+    fn on_string(&mut self, _value: &str) {}
+    fn on_number(&mut self, _value: Number) {}
+    fn on_bool(&mut self, _value: bool) {}
+    fn on_null(&mut self) {}
+}
+
+// owned form of the handler callbacks, for pull-style consumers
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    Key(String),
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+impl ContentHandler for VecDeque<Event> {
+    fn on_object_start(&mut self) {
+        self.push_back(Event::ObjectStart);
+    }
+    fn on_object_end(&mut self) {
+        self.push_back(Event::ObjectEnd);
+    }
+    fn on_array_start(&mut self) {
+        self.push_back(Event::ArrayStart);
+    }
+    fn on_array_end(&mut self) {
+        self.push_back(Event::ArrayEnd);
+    }
+    fn on_key(&mut self, key: &str) {
+        self.push_back(Event::Key(key.to_string()));
+    }
+    fn on_string(&mut self, value: &str) {
+        self.push_back(Event::String(value.to_string()));
+    }
+    fn on_number(&mut self, value: Number) {
+        self.push_back(Event::Number(value));
+    }
+    fn on_bool(&mut self, value: bool) {
+        self.push_back(Event::Bool(value));
+    }
+    fn on_null(&mut self) {
+        self.push_back(Event::Null);
+    }
+}
+
+// collects handler events back into an AST
+#[derive(Default)]
+pub struct TreeBuilder {
+    stack: Vec<Container>,
+    root: Option<Value>,
+}
+
+enum Container {
+    Object(Map, Option<String>),
+    Array(Array),
+}
+
+impl TreeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_value(self) -> Option<Value> {
+        self.root
+    }
+
+    fn push_value(&mut self, value: Value) {
+        match self.stack.last_mut() {
+            Some(Container::Object(properties, key)) => {
+                properties.insert(key.take().unwrap_or_default(), value);
+            }
+            Some(Container::Array(elements)) => elements.push(value),
+            None => self.root = Some(value),
+        }
+    }
+}
+
+impl ContentHandler for TreeBuilder {
+    fn on_object_start(&mut self) {
+        self.stack.push(Container::Object(Map::new(), None));
+    }
+    fn on_object_end(&mut self) {
+        if let Some(Container::Object(properties, _)) = self.stack.pop() {
+            self.push_value(Value::Object(properties));
+        }
+    }
+    fn on_array_start(&mut self) {
+        self.stack.push(Container::Array(Vec::new()));
+    }
+    fn on_array_end(&mut self) {
+        if let Some(Container::Array(elements)) = self.stack.pop() {
+            self.push_value(Value::Array(elements));
+        }
+    }
+    fn on_key(&mut self, key: &str) {
+        if let Some(Container::Object(_, pending)) = self.stack.last_mut() {
+            *pending = Some(key.to_string());
+        }
+    }
+    fn on_string(&mut self, value: &str) {
+        self.push_value(Value::String(value.to_string()));
+    }
+    fn on_number(&mut self, value: Number) {
+        self.push_value(Value::Number(value));
+    }
+    fn on_bool(&mut self, value: bool) {
+        self.push_value(Value::Bool(value));
+    }
+    fn on_null(&mut self) {
+        self.push_value(Value::Null);
+    }
+}
+
+type TokenIter<'a> = Peekable<lexer::Tokens<'a>>;
+
+pub fn generate<H: ContentHandler>(input: &str, handler: &mut H) -> Result<(), Error> {
+    let mut iter = lexer::tokens(input).peekable();
+    parse(&mut iter, handler)?;
+    match iter.next() {
+        Some(token) => Err(format!("Unexpected trailing token: '{}'", token?.value).into()),
+        None => Ok(()),
+    }
+}
+
+// parses a single complete document into an AST without going through a token vector
+pub(crate) fn build(input: &str) -> Result<Value, Error> {
+    let mut builder = TreeBuilder::new();
+    generate(input, &mut builder)?;
+    Ok(builder.into_value().unwrap())
+}
+
+// back-to-back documents such as `{"a":1}{"a":2} 3 "x"`, stopping at the first error
+pub fn parse_many(input: &str) -> impl Iterator<Item = Result<Value, Error>> + '_ {
+    let mut iter = lexer::tokens(input).peekable();
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        iter.peek()?;
+        let mut builder = TreeBuilder::new();
+        // a successfully parsed value always leaves a root behind
+        let result = parse(&mut iter, &mut builder).map(|()| builder.into_value().unwrap());
+        failed = result.is_err();
+        Some(result)
+    })
+}
+
+fn parse<H: ContentHandler>(iter: &mut TokenIter, handler: &mut H) -> Result<(), Error> {
+    match peek_type(iter)?.ok_or("Unexpected end of input")? {
+        TokenType::OpenObject => parse_object(iter, handler),
+        TokenType::OpenArray => parse_array(iter, handler),
+        TokenType::True
+        | TokenType::False
+        | TokenType::Null
+        | TokenType::Number
+        | TokenType::String => parse_basic(iter, handler),
+        _ => Err("Invalid JSON token".into()),
+    }
+}
+
+fn parse_basic<H: ContentHandler>(iter: &mut TokenIter, handler: &mut H) -> Result<(), Error> {
+    let token = next_token(iter)?;
+    match token.token_type {
+        TokenType::True => handler.on_bool(true),
+        TokenType::False => handler.on_bool(false),
+        TokenType::Null => handler.on_null(),
+        TokenType::Number => {
+            let number = token.value.parse::<Number>()?;
+            handler.on_number(number);
+        }
+        TokenType::String => handler.on_string(&token.value),
+        _ => return Err("Invalid token".into()),
+    }
+    Ok(())
+}
+
+fn parse_object<H: ContentHandler>(iter: &mut TokenIter, handler: &mut H) -> Result<(), Error> {
+    consume_token(iter, TokenType::OpenObject)?;
+    handler.on_object_start();
+    while let Some(token_type) = peek_type(iter)? {
+        if token_type == TokenType::CloseObject {
+            break;
+        }
+        // resolve "key": value
+        let key = consume_string(iter)?;
+        handler.on_key(&key);
+        consume_token(iter, TokenType::Colon)?;
+        parse(iter, handler)?;
+
+        // check separator
+        match peek_type(iter)? {
+            Some(TokenType::Comma) => {
+                iter.next(); // consume comma
+                // check for trailing comma
+                if peek_type(iter)? == Some(TokenType::CloseObject) {
+                    return Err("Trailing comma in object".into());
+                }
+            }
+            Some(TokenType::CloseObject) => break,
+            _ => return Err("Expected ',' or '}' in object".into()),
+        }
+    }
+    consume_token(iter, TokenType::CloseObject)?;
+    handler.on_object_end();
+    Ok(())
+}
+
+fn parse_array<H: ContentHandler>(iter: &mut TokenIter, handler: &mut H) -> Result<(), Error> {
+    consume_token(iter, TokenType::OpenArray)?;
+    handler.on_array_start();
+    while let Some(token_type) = peek_type(iter)? {
+        if token_type == TokenType::CloseArray {
+            break;
+        }
+        parse(iter, handler)?;
+        // handle separator
+        match peek_type(iter)? {
+            Some(TokenType::Comma) => {
+                iter.next(); // consume comma
+                // check for trailing comma
+                if peek_type(iter)? == Some(TokenType::CloseArray) {
+                    return Err("Trailing comma in array".into());
+                }
+            }
+            Some(TokenType::CloseArray) => break, // end of array parsing
+            _ => return Err("Expected ',' or ']' in array".into()),
+        }
+    }
+    consume_token(iter, TokenType::CloseArray)?;
+    handler.on_array_end();
+    Ok(())
+}
+
+// surfaces a pending lexer error instead of hiding it behind peek()
+fn peek_type(iter: &mut TokenIter) -> Result<Option<TokenType>, Error> {
+    match iter.peek() {
+        Some(Ok(token)) => Ok(Some(token.token_type)),
+        Some(Err(_)) => next_token(iter).map(|_| None),
+        None => Ok(None),
+    }
+}
+
+fn next_token(iter: &mut TokenIter) -> Result<Token, Error> {
+    iter.next()
+        .unwrap_or_else(|| Err("Unexpected end of input".into()))
+}
+
+fn consume_string(iter: &mut TokenIter) -> Result<String, Error> {
+    match iter.next().transpose()? {
+        Some(token) if token.token_type == TokenType::String => Ok(token.value),
+        Some(_) => Err("Expected string".into()),
+        None => Err("Unexpected end of input".into()),
+    }
+}
+
+fn consume_token(iter: &mut TokenIter, expected: TokenType) -> Result<(), Error> {
+    match iter.next().transpose()? {
+        Some(token) if token.token_type == expected => Ok(()),
+        Some(_) => Err(format!("Expected {:?}, found unexpected token", expected).into()),
+        None => Err("Unexpected end of input".into()),
+    }
+}
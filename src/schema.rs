@@ -0,0 +1,186 @@
+// JSON Schema: validating documents against one, and inferring one from samples
+use crate::Value;
+use std::collections::BTreeMap;
+
+mod regex;
+mod validate;
+
+pub use validate::{Schema, Violation};
+
+// everything seen at one position across the samples; types are tracked as a union,
+// so a field that is sometimes a string and sometimes null is a nullable string
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InferredSchema {
+    seen: usize,
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    number: bool, // numbers with a fractional part
+    string: bool,
+    items: Option<Box<InferredSchema>>, // merged over the elements of every array seen
+    object: Option<ObjectShape>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ObjectShape {
+    seen: usize,
+    properties: BTreeMap<String, InferredSchema>,
+}
+
+pub fn infer(samples: &[Value]) -> InferredSchema {
+    let mut schema = InferredSchema::default();
+    for sample in samples {
+        schema.add(sample);
+    }
+    schema
+}
+
+impl InferredSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // folds one more sample in
+    pub fn add(&mut self, value: &Value) {
+        self.seen += 1;
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(number) if number.as_i64().is_some() || number.as_u64().is_some() => {
+                self.integer = true
+            }
+            Value::Number(_) => self.number = true,
+            Value::String(_) => self.string = true,
+            Value::Array(elements) => {
+                let items = self.items.get_or_insert_with(Box::default);
+                for element in elements {
+                    items.add(element);
+                }
+            }
+            Value::Object(properties) => {
+                let shape = self.object.get_or_insert_with(ObjectShape::default);
+                shape.seen += 1;
+                for (key, value) in properties {
+                    shape.properties.entry(key.clone()).or_default().add(value);
+                }
+            }
+            Value::Raw(raw) => {
+                self.seen -= 1;
+                self.add(&raw.parse().unwrap_or_default());
+            }
+        }
+    }
+
+    // folds in everything another schema has seen, as if its samples had been added here
+    pub fn merge(&mut self, other: &InferredSchema) {
+        self.seen += other.seen;
+        self.null |= other.null;
+        self.boolean |= other.boolean;
+        self.integer |= other.integer;
+        self.number |= other.number;
+        self.string |= other.string;
+        if let Some(items) = &other.items {
+            self.items.get_or_insert_with(Box::default).merge(items);
+        }
+        if let Some(other) = &other.object {
+            let shape = self.object.get_or_insert_with(ObjectShape::default);
+            shape.seen += other.seen;
+            for (key, property) in &other.properties {
+                shape
+                    .properties
+                    .entry(key.clone())
+                    .or_default()
+                    .merge(property);
+            }
+        }
+    }
+
+    // JSON Schema type names, e.g. ["object", "null"]; integer is dropped once
+    // non-integer numbers have been seen as well
+    pub fn types(&self) -> Vec<&'static str> {
+        let candidates = [
+            (self.object.is_some(), "object"),
+            (self.items.is_some(), "array"),
+            (self.string, "string"),
+            (self.number, "number"),
+            (self.integer && !self.number, "integer"),
+            (self.boolean, "boolean"),
+            (self.null, "null"),
+        ];
+        candidates
+            .into_iter()
+            .filter(|(seen, _)| *seen)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        self.null
+    }
+
+    // the element schema, if any arrays were seen here
+    pub fn items(&self) -> Option<&InferredSchema> {
+        self.items.as_deref()
+    }
+
+    pub fn property(&self, key: &str) -> Option<&InferredSchema> {
+        self.object.as_ref()?.properties.get(key)
+    }
+
+    pub fn properties(&self) -> impl Iterator<Item = (&str, &InferredSchema)> {
+        self.object
+            .iter()
+            .flat_map(|shape| shape.properties.iter())
+            .map(|(key, schema)| (key.as_str(), schema))
+    }
+
+    // present in every object seen here
+    pub fn is_required(&self, key: &str) -> bool {
+        match &self.object {
+            Some(shape) => shape
+                .properties
+                .get(key)
+                .is_some_and(|property| property.seen == shape.seen),
+            None => false,
+        }
+    }
+
+    // a draft 2020-12 JSON Schema document
+    pub fn to_json_schema(&self) -> Value {
+        let mut schema = Value::object();
+        schema["$schema"] = "https://json-schema.org/draft/2020-12/schema".into();
+        if let Value::Object(properties) = self.schema_value() {
+            for (key, value) in properties {
+                schema[key] = value;
+            }
+        }
+        schema
+    }
+
+    fn schema_value(&self) -> Value {
+        let mut schema = Value::object();
+        match self.types().as_slice() {
+            [] => return schema, // nothing seen, e.g. the items of arrays that were all empty
+            [single] => schema["type"] = (*single).into(),
+            types => schema["type"] = types.to_vec().into(),
+        }
+        if let Some(shape) = &self.object {
+            let mut properties = Value::object();
+            let mut required = Vec::new();
+            for (key, property) in &shape.properties {
+                properties[key] = property.schema_value();
+                if property.seen == shape.seen {
+                    required.push(key.as_str());
+                }
+            }
+            schema["properties"] = properties;
+            if !required.is_empty() {
+                schema["required"] = required.into();
+            }
+        }
+        if let Some(items) = &self.items {
+            schema["items"] = items.schema_value();
+        }
+        schema
+    }
+}
@@ -0,0 +1,341 @@
+// a small backtracking regex for the `pattern` keyword: literals, `.`, classes such as
+// [a-z] and \d \w \s, groups, `|`, anchors and the usual quantifiers (lazy ones too);
+// like ECMA-262 `test`, a pattern matches anywhere unless anchored
+use crate::Error;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    branches: Vec<Vec<Node>>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool), // the flag is true for the negated \D form
+    Word(bool),
+    Space(bool),
+}
+
+impl Regex {
+    pub(crate) fn new(pattern: &str) -> Result<Regex, Error> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut parser = Parser {
+            pattern,
+            chars: &chars,
+            position: 0,
+        };
+        let branches = parser.alternation()?;
+        if parser.position < chars.len() {
+            return Err(parser.invalid("unmatched ')'"));
+        }
+        Ok(Regex { branches })
+    }
+
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+        (0..=chars.len()).any(|start| {
+            self.branches
+                .iter()
+                .any(|branch| match_sequence(branch, &chars, start, &mut |_| true))
+        })
+    }
+}
+
+fn match_sequence(
+    nodes: &[Node],
+    text: &[char],
+    position: usize,
+    k: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match nodes.split_first() {
+        None => k(position),
+        Some((node, rest)) => match_node(node, text, position, &mut |next| {
+            match_sequence(rest, text, next, k)
+        }),
+    }
+}
+
+fn match_node(
+    node: &Node,
+    text: &[char],
+    position: usize,
+    k: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match node {
+        Node::Char(c) => text.get(position) == Some(c) && k(position + 1),
+        Node::Any => text.get(position).is_some_and(|&c| c != '\n') && k(position + 1),
+        Node::Class(class) => {
+            text.get(position).is_some_and(|&c| class.contains(c)) && k(position + 1)
+        }
+        Node::Start => position == 0 && k(position),
+        Node::End => position == text.len() && k(position),
+        Node::Group(branches) => branches
+            .iter()
+            .any(|branch| match_sequence(branch, text, position, k)),
+        Node::Repeat {
+            node,
+            min,
+            max,
+            greedy,
+        } => match_repeat(node, *min, *max, *greedy, 0, text, position, k),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn match_repeat(
+    node: &Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+    count: usize,
+    text: &[char],
+    position: usize,
+    k: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let can_stop = count >= min;
+    let can_continue = max.is_none_or(|max| count < max);
+    let once_more = |k: &mut dyn FnMut(usize) -> bool| {
+        can_continue
+            && match_node(node, text, position, &mut |next| {
+                // an empty match would loop forever without consuming anything
+                next != position && match_repeat(node, min, max, greedy, count + 1, text, next, k)
+            })
+    };
+    // a lazy repeat tries stopping first, a greedy one tries one more first
+    if !greedy && can_stop && k(position) {
+        return true;
+    }
+    once_more(k) || (greedy && can_stop && k(position))
+}
+
+impl Class {
+    fn contains(&self, c: char) -> bool {
+        let found = self.items.iter().any(|item| match *item {
+            ClassItem::Range(low, high) => (low..=high).contains(&c),
+            ClassItem::Digit(negated) => c.is_ascii_digit() != negated,
+            ClassItem::Word(negated) => (c.is_ascii_alphanumeric() || c == '_') != negated,
+            ClassItem::Space(negated) => c.is_whitespace() != negated,
+        });
+        found != self.negated
+    }
+}
+
+struct Parser<'a> {
+    pattern: &'a str,
+    chars: &'a [char],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn invalid(&self, reason: &str) -> Error {
+        format!("Invalid pattern '{}': {}", self.pattern, reason).into()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn alternation(&mut self) -> Result<Vec<Vec<Node>>, Error> {
+        let mut branches = vec![self.sequence()?];
+        while self.eat('|') {
+            branches.push(self.sequence()?);
+        }
+        Ok(branches)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, Error> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, Error> {
+        let c = self.next().ok_or_else(|| self.invalid("unexpected end"))?;
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                if self.eat('?') && !self.eat(':') {
+                    return Err(self.invalid("only (?:...) groups are supported"));
+                }
+                let branches = self.alternation()?;
+                if !self.eat(')') {
+                    return Err(self.invalid("unclosed '('"));
+                }
+                Node::Group(branches)
+            }
+            '[' => Node::Class(self.class()?),
+            '\\' => match self.escape()? {
+                Ok(item) => Node::Class(Class {
+                    negated: false,
+                    items: vec![item],
+                }),
+                Err(c) => Node::Char(c),
+            },
+            '*' | '+' | '?' => return Err(self.invalid("nothing to repeat")),
+            c => Node::Char(c),
+        })
+    }
+
+    fn quantified(&mut self, node: Node) -> Result<Node, Error> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => return self.braces(node),
+            _ => return Ok(node),
+        };
+        self.position += 1;
+        Ok(self.repeat(node, min, max))
+    }
+
+    // {n}, {n,} or {n,m}; anything else is a literal '{', as in ECMA-262
+    fn braces(&mut self, node: Node) -> Result<Node, Error> {
+        let start = self.position;
+        self.position += 1;
+        let min = self.number();
+        // None only for the open-ended {n,}
+        let max = match self.eat(',') {
+            true => self.number(),
+            false => min,
+        };
+        match (min, self.eat('}')) {
+            (Some(min), true) => {
+                if max.is_some_and(|max| max < min) {
+                    return Err(self.invalid("numbers out of order in {}"));
+                }
+                Ok(self.repeat(node, min, max))
+            }
+            _ => {
+                self.position = start;
+                Ok(node)
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.chars[start..self.position]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    fn repeat(&mut self, node: Node, min: usize, max: Option<usize>) -> Node {
+        let greedy = !self.eat('?');
+        Node::Repeat {
+            node: Box::new(node),
+            min,
+            max,
+            greedy,
+        }
+    }
+
+    fn class(&mut self) -> Result<Class, Error> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self.next().ok_or_else(|| self.invalid("unclosed '['"))?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let low = match c {
+                '\\' => match self.escape()? {
+                    Ok(item) => {
+                        items.push(item);
+                        continue;
+                    }
+                    Err(c) => c,
+                },
+                c => c,
+            };
+            // a '-' right before ']' is a literal dash
+            if self.peek() == Some('-')
+                && self.chars.get(self.position + 1).is_some_and(|&c| c != ']')
+            {
+                self.position += 1;
+                let high = match self.next() {
+                    Some('\\') => match self.escape()? {
+                        Err(c) => c,
+                        Ok(_) => return Err(self.invalid("bad range in class")),
+                    },
+                    Some(c) => c,
+                    None => return Err(self.invalid("unclosed '['")),
+                };
+                if high < low {
+                    return Err(self.invalid("range out of order in class"));
+                }
+                items.push(ClassItem::Range(low, high));
+            } else {
+                items.push(ClassItem::Range(low, low));
+            }
+        }
+        Ok(Class { negated, items })
+    }
+
+    // Ok for a class shorthand such as \d, Err for a single escaped character
+    fn escape(&mut self) -> Result<Result<ClassItem, char>, Error> {
+        let c = self.next().ok_or_else(|| self.invalid("trailing '\\'"))?;
+        Ok(match c {
+            'd' => Ok(ClassItem::Digit(false)),
+            'D' => Ok(ClassItem::Digit(true)),
+            'w' => Ok(ClassItem::Word(false)),
+            'W' => Ok(ClassItem::Word(true)),
+            's' => Ok(ClassItem::Space(false)),
+            'S' => Ok(ClassItem::Space(true)),
+            'n' => Err('\n'),
+            't' => Err('\t'),
+            'r' => Err('\r'),
+            c => Err(c),
+        })
+    }
+}
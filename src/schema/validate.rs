@@ -0,0 +1,411 @@
+// validation against a JSON Schema (a draft 2020-12 core subset): type, enum, const,
+// required, properties, additionalProperties, items, the min/max keywords, pattern,
+// and $ref within the same document; other keywords are ignored
+use super::regex::Regex;
+use crate::pointer::escape;
+use crate::{Error, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct Schema {
+    nodes: Vec<Node>, // the root is nodes[0]; $ref and subschemas point into this list
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub instance_path: String, // a JSON Pointer into the instance, "" for the root
+    pub schema_path: String,   // where the failing keyword is, e.g. "#/properties/age/minimum"
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = if self.instance_path.is_empty() {
+            "/"
+        } else {
+            &self.instance_path
+        };
+        write!(f, "{}: {} ({})", at, self.message, self.schema_path)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    location: String,
+    always: Option<bool>, // `true` and `false` schemas
+    types: Option<Vec<String>>,
+    enumeration: Option<Vec<Value>>,
+    constant: Option<Value>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<(String, Regex)>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    items: Option<usize>,
+    required: Vec<String>,
+    properties: Vec<(String, usize)>,
+    additional_properties: Option<usize>,
+    reference: Option<usize>,
+}
+
+impl Schema {
+    pub fn compile(schema: &Value) -> Result<Schema, Error> {
+        let mut compiler = Compiler {
+            root: schema,
+            nodes: Vec::new(),
+            compiled: HashMap::new(),
+        };
+        compiler.compile(schema, "#".to_string())?;
+        Ok(Schema {
+            nodes: compiler.nodes,
+        })
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validate(instance).is_ok()
+    }
+
+    // reports every violation, not just the first
+    pub fn validate(&self, instance: &Value) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.check(0, instance, &mut String::new(), &mut violations);
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+
+    fn check(&self, node: usize, instance: &Value, path: &mut String, out: &mut Vec<Violation>) {
+        let node = &self.nodes[node];
+        let mut fail = |keyword: &str, message: String| {
+            out.push(Violation {
+                instance_path: path.clone(),
+                schema_path: match keyword {
+                    "" => node.location.clone(),
+                    keyword => format!("{}/{}", node.location, keyword),
+                },
+                message,
+            })
+        };
+
+        if node.always == Some(false) {
+            fail("", "no value is allowed here".to_string());
+            return;
+        }
+        if let Some(types) = &node.types
+            && !types.iter().any(|name| has_type(instance, name))
+        {
+            fail(
+                "type",
+                format!(
+                    "expected {}, found {}",
+                    types.join(" or "),
+                    instance.type_name()
+                ),
+            );
+        }
+        if let Some(values) = &node.enumeration
+            && !values.contains(instance)
+        {
+            fail(
+                "enum",
+                format!("{} is not one of the allowed values", instance),
+            );
+        }
+        if let Some(constant) = &node.constant
+            && instance != constant
+        {
+            fail(
+                "const",
+                format!("expected {}, found {}", constant, instance),
+            );
+        }
+
+        match instance {
+            Value::Number(number) => {
+                let n = number.as_f64();
+                if let Some(min) = node.minimum.filter(|&min| n < min) {
+                    fail("minimum", format!("{} is less than {}", number, min));
+                }
+                if let Some(max) = node.maximum.filter(|&max| n > max) {
+                    fail("maximum", format!("{} is greater than {}", number, max));
+                }
+                if let Some(min) = node.exclusive_minimum.filter(|&min| n <= min) {
+                    fail(
+                        "exclusiveMinimum",
+                        format!("{} is not greater than {}", number, min),
+                    );
+                }
+                if let Some(max) = node.exclusive_maximum.filter(|&max| n >= max) {
+                    fail(
+                        "exclusiveMaximum",
+                        format!("{} is not less than {}", number, max),
+                    );
+                }
+            }
+            Value::String(string) => {
+                let length = string.chars().count();
+                if let Some(min) = node.min_length.filter(|&min| length < min) {
+                    fail(
+                        "minLength",
+                        format!("expected at least {} characters, found {}", min, length),
+                    );
+                }
+                if let Some(max) = node.max_length.filter(|&max| length > max) {
+                    fail(
+                        "maxLength",
+                        format!("expected at most {} characters, found {}", max, length),
+                    );
+                }
+                if let Some((source, regex)) = &node.pattern
+                    && !regex.is_match(string)
+                {
+                    fail(
+                        "pattern",
+                        format!("\"{}\" does not match '{}'", string, source),
+                    );
+                }
+            }
+            Value::Array(elements) => {
+                let length = elements.len();
+                if let Some(min) = node.min_items.filter(|&min| length < min) {
+                    fail(
+                        "minItems",
+                        format!("expected at least {} items, found {}", min, length),
+                    );
+                }
+                if let Some(max) = node.max_items.filter(|&max| length > max) {
+                    fail(
+                        "maxItems",
+                        format!("expected at most {} items, found {}", max, length),
+                    );
+                }
+            }
+            Value::Object(properties) => {
+                for key in &node.required {
+                    if !properties.contains_key(key) {
+                        fail("required", format!("missing required property '{}'", key));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // subschemas, each under the instance path of the child it applies to
+        match instance {
+            Value::Array(elements) => {
+                if let Some(items) = node.items {
+                    for (i, element) in elements.iter().enumerate() {
+                        self.check_child(items, element, path, &i.to_string(), out);
+                    }
+                }
+            }
+            Value::Object(properties) => {
+                for (key, value) in properties {
+                    match node.properties.iter().find(|(name, _)| name == key) {
+                        Some(&(_, schema)) => self.check_child(schema, value, path, key, out),
+                        None => match node.additional_properties {
+                            Some(schema) if self.nodes[schema].always == Some(false) => {
+                                out.push(Violation {
+                                    instance_path: path.clone(),
+                                    schema_path: format!("{}/additionalProperties", node.location),
+                                    message: format!("property '{}' is not allowed", key),
+                                })
+                            }
+                            Some(schema) => self.check_child(schema, value, path, key, out),
+                            None => {}
+                        },
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(reference) = node.reference {
+            self.check(reference, instance, path, out);
+        }
+    }
+
+    fn check_child(
+        &self,
+        node: usize,
+        child: &Value,
+        path: &mut String,
+        token: &str,
+        out: &mut Vec<Violation>,
+    ) {
+        let length = path.len();
+        path.push('/');
+        path.push_str(&escape(token));
+        self.check(node, child, path, out);
+        path.truncate(length);
+    }
+}
+
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "integer" => instance.as_number().is_some_and(|n| {
+            n.as_i64().is_some() || n.as_u64().is_some() || n.as_f64().fract() == 0.0
+        }),
+        "number" => instance.is_number(),
+        name => instance.type_name() == name,
+    }
+}
+
+struct Compiler<'a> {
+    root: &'a Value,
+    nodes: Vec<Node>,
+    compiled: HashMap<String, usize>, // by location, so recursive $refs terminate
+}
+
+impl Compiler<'_> {
+    fn compile(&mut self, schema: &Value, location: String) -> Result<usize, Error> {
+        if let Some(&index) = self.compiled.get(&location) {
+            return Ok(index);
+        }
+        let index = self.nodes.len();
+        self.nodes.push(Node::default());
+        self.compiled.insert(location.clone(), index);
+
+        let invalid = |keyword: &str, reason: &str| -> Error {
+            format!("Invalid schema at {}/{}: {}", location, keyword, reason).into()
+        };
+        let mut node = Node {
+            location: location.clone(),
+            ..Node::default()
+        };
+        let keywords = match schema {
+            Value::Bool(value) => {
+                node.always = Some(*value);
+                self.nodes[index] = node;
+                return Ok(index);
+            }
+            Value::Object(keywords) => keywords,
+            _ => {
+                return Err(format!(
+                    "Invalid schema at {}: expected an object or a boolean",
+                    location
+                )
+                .into());
+            }
+        };
+
+        for (keyword, value) in keywords {
+            match keyword.as_str() {
+                "type" => {
+                    let names: Vec<String> = match value {
+                        Value::String(name) => vec![name.clone()],
+                        Value::Array(names) => names
+                            .iter()
+                            .map(|name| name.as_str().map(str::to_string))
+                            .collect::<Option<_>>()
+                            .ok_or_else(|| invalid(keyword, "expected type names"))?,
+                        _ => return Err(invalid(keyword, "expected a string or an array")),
+                    };
+                    node.types = Some(names);
+                }
+                "enum" => {
+                    let values = value
+                        .as_array()
+                        .ok_or_else(|| invalid(keyword, "expected an array"))?;
+                    node.enumeration = Some(values.clone());
+                }
+                "const" => node.constant = Some(value.clone()),
+                "minimum" => {
+                    node.minimum =
+                        Some(number(value).ok_or_else(|| invalid(keyword, "expected a number"))?)
+                }
+                "maximum" => {
+                    node.maximum =
+                        Some(number(value).ok_or_else(|| invalid(keyword, "expected a number"))?)
+                }
+                "exclusiveMinimum" => {
+                    node.exclusive_minimum =
+                        Some(number(value).ok_or_else(|| invalid(keyword, "expected a number"))?)
+                }
+                "exclusiveMaximum" => {
+                    node.exclusive_maximum =
+                        Some(number(value).ok_or_else(|| invalid(keyword, "expected a number"))?)
+                }
+                "minLength" => {
+                    node.min_length =
+                        Some(count(value).ok_or_else(|| invalid(keyword, "expected a count"))?)
+                }
+                "maxLength" => {
+                    node.max_length =
+                        Some(count(value).ok_or_else(|| invalid(keyword, "expected a count"))?)
+                }
+                "minItems" => {
+                    node.min_items =
+                        Some(count(value).ok_or_else(|| invalid(keyword, "expected a count"))?)
+                }
+                "maxItems" => {
+                    node.max_items =
+                        Some(count(value).ok_or_else(|| invalid(keyword, "expected a count"))?)
+                }
+                "pattern" => {
+                    let source = value
+                        .as_str()
+                        .ok_or_else(|| invalid(keyword, "expected a string"))?;
+                    node.pattern = Some((source.to_string(), Regex::new(source)?));
+                }
+                "required" => {
+                    node.required = value
+                        .as_array()
+                        .and_then(|names| {
+                            names
+                                .iter()
+                                .map(|name| name.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .ok_or_else(|| invalid(keyword, "expected an array of strings"))?;
+                }
+                "properties" => {
+                    let properties = value
+                        .as_object()
+                        .ok_or_else(|| invalid(keyword, "expected an object"))?;
+                    for (name, subschema) in properties {
+                        let child = format!("{}/properties/{}", location, escape(name));
+                        let child = self.compile(subschema, child)?;
+                        node.properties.push((name.clone(), child));
+                    }
+                }
+                "additionalProperties" => {
+                    node.additional_properties =
+                        Some(self.compile(value, format!("{}/additionalProperties", location))?);
+                }
+                "items" => node.items = Some(self.compile(value, format!("{}/items", location))?),
+                "$ref" => {
+                    let reference = value
+                        .as_str()
+                        .ok_or_else(|| invalid(keyword, "expected a string"))?;
+                    let fragment = reference.strip_prefix('#').ok_or_else(|| {
+                        invalid(
+                            keyword,
+                            "only references within the document (\"#...\") are supported",
+                        )
+                    })?;
+                    let target = self.root.pointer(fragment).ok_or_else(|| {
+                        invalid(keyword, &format!("cannot resolve '{}'", reference))
+                    })?;
+                    node.reference = Some(self.compile(target, reference.to_string())?);
+                }
+                _ => {}
+            }
+        }
+        self.nodes[index] = node;
+        Ok(index)
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64()
+}
+
+fn count(value: &Value) -> Option<usize> {
+    value.as_u64().and_then(|n| usize::try_from(n).ok())
+}
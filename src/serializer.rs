@@ -0,0 +1,623 @@
+use crate::path::{JsonPath, PathSegment};
+use crate::{Error, Number, Value};
+use std::fmt::{self, Write};
+use std::io;
+
+mod hook;
+mod style;
+
+use hook::NoHook;
+pub use hook::StringHook;
+#[cfg(feature = "parser")]
+pub(crate) use style::is_identifier;
+pub use style::{Indent, KeyQuoting, Newline, Style};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SerializeOptions {
+    style: Style,
+    ascii_only: bool,
+    html_safe: bool,
+    float_decimals: Option<usize>,
+    exponent_below: f64,
+    exponent_from: f64,
+    float_point: bool,
+    max_width: Option<usize>,
+    single_line: bool, // set internally while trying a container on one line
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        SerializeOptions {
+            style: Style::compact(),
+            ascii_only: false,
+            html_safe: false,
+            float_decimals: None,
+            // the thresholds JavaScript uses
+            exponent_below: 1e-7,
+            exponent_from: 1e21,
+            float_point: false,
+            max_width: None,
+            single_line: false,
+        }
+    }
+}
+
+impl SerializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // shorthand for Style::pretty() or Style::compact()
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.style = if pretty {
+            Style::pretty()
+        } else {
+            Style::compact()
+        };
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    // every non-ASCII character in strings and keys is written as \uXXXX, characters
+    // outside the BMP as a surrogate pair, for consumers that mangle UTF-8
+    pub fn with_ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    // <, >, & and the JavaScript line terminators U+2028 and U+2029 are written as
+    // \uXXXX, so the output can go straight into a <script> tag
+    pub fn with_html_safe(mut self, html_safe: bool) -> Self {
+        self.html_safe = html_safe;
+        self
+    }
+
+    // f64s get exactly this many digits after the point (rounded), instead of the
+    // shortest digits that parse back to the same value
+    pub fn with_float_decimals(mut self, decimals: Option<usize>) -> Self {
+        self.float_decimals = decimals;
+        self
+    }
+
+    // f64s whose magnitude is below `small` or at least `large` are written with an
+    // exponent, e.g. 1.5e-8 or 1e+300; zero never is
+    pub fn with_exponent_thresholds(mut self, small: f64, large: f64) -> Self {
+        self.exponent_below = small;
+        self.exponent_from = large;
+        self
+    }
+
+    // when indenting, an object or array that fits on one line within this many
+    // columns (indentation and key included) is written as [1, 2, 3] instead of one
+    // element per line
+    pub fn with_max_width(mut self, max_width: Option<usize>) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    // writes integral f64s as 1.0 rather than 1, so they still read back as floats
+    // in languages that tell the two apart
+    pub fn with_float_point(mut self, float_point: bool) -> Self {
+        self.float_point = float_point;
+        self
+    }
+}
+
+// AST → JSON text, the reverse of lexer + parser
+pub fn to_string(value: &Value) -> String {
+    value.to_string()
+}
+
+pub fn to_string_pretty(value: &Value) -> String {
+    format!("{:#}", value)
+}
+
+pub fn to_string_with(value: &Value, options: &SerializeOptions) -> String {
+    let mut out = String::new();
+    let _ = to_fmt_writer(&mut out, value, options);
+    out
+}
+
+pub fn to_string_with_hook(
+    value: &Value,
+    options: &SerializeOptions,
+    hook: &dyn StringHook,
+) -> String {
+    let mut out = String::new();
+    let mut hooked = Hooked {
+        hook,
+        path: Some(JsonPath::root()),
+    };
+    let _ = write_document(&mut out, value, options, &mut hooked);
+    out
+}
+
+// the *_into functions append to the buffer instead of allocating a new one, so a loop
+// can clear and reuse one buffer for every document
+pub fn to_string_into(value: &Value, out: &mut String) {
+    let _ = write_value(out, value, &SerializeOptions::default(), 0);
+}
+
+pub fn to_vec_into(value: &Value, out: &mut Vec<u8>) {
+    let _ = write_value(&mut Bytes(out), value, &SerializeOptions::default(), 0);
+}
+
+// into any fmt::Write, e.g. a Formatter inside another Display impl; the error is the
+// writer's own
+pub fn to_fmt_writer<W: Write>(
+    out: &mut W,
+    value: &Value,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    let mut hooked = Hooked {
+        hook: &NoHook,
+        path: None,
+    };
+    write_document(out, value, options, &mut hooked)
+}
+
+fn write_document<W: Write>(
+    out: &mut W,
+    value: &Value,
+    options: &SerializeOptions,
+    hooked: &mut Hooked,
+) -> fmt::Result {
+    write_node(out, value, options, hooked, 0, 0)?;
+    if options.style.final_newline {
+        out.write_str(options.style.newline_str())?;
+    }
+    Ok(())
+}
+
+// lets the fmt::Write-based writers fill a byte buffer directly
+struct Bytes<'b>(&'b mut Vec<u8>);
+
+impl Write for Bytes<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+pub(crate) fn write_value<W: Write>(
+    out: &mut W,
+    value: &Value,
+    options: &SerializeOptions,
+    depth: usize,
+) -> fmt::Result {
+    let mut hooked = Hooked {
+        hook: &NoHook,
+        path: None,
+    };
+    write_node(
+        out,
+        value,
+        options,
+        &mut hooked,
+        depth,
+        depth * indent_width(&options.style),
+    )
+}
+
+// the hook, and where in the value the writer is, for StringHook::replace; without a
+// hook the path isn't tracked, so plain serialization doesn't pay for it
+struct Hooked<'h> {
+    hook: &'h dyn StringHook,
+    path: Option<JsonPath>,
+}
+
+impl Hooked<'_> {
+    fn child<W: Write>(
+        &mut self,
+        segment: impl FnOnce() -> PathSegment,
+        write: impl FnOnce(&mut W, &mut Self) -> fmt::Result,
+        out: &mut W,
+    ) -> fmt::Result {
+        let Some(path) = &mut self.path else {
+            return write(out, self);
+        };
+        path.push(segment());
+        let written = write(out, self);
+        if let Some(path) = &mut self.path {
+            path.pop();
+        }
+        written
+    }
+
+    fn replace(&self, string: &str) -> Option<String> {
+        self.hook.replace(self.path.as_ref()?, string)
+    }
+}
+
+// `column` is where the value starts on its line, for the max_width check
+fn write_node<W: Write>(
+    out: &mut W,
+    value: &Value,
+    options: &SerializeOptions,
+    hooked: &mut Hooked,
+    depth: usize,
+    column: usize,
+) -> fmt::Result {
+    let style = &options.style;
+    let multi_line = style.indent.is_some() && !options.single_line;
+    let is_container = matches!(value, Value::Object(_) | Value::Array(_));
+    if let Some(max_width) = options.max_width
+        && multi_line
+        && is_container
+    {
+        // one column is kept free for a trailing comma
+        let single_line = SerializeOptions {
+            single_line: true,
+            ..*options
+        };
+        let mut fitted = Fitted {
+            text: String::new(),
+            width: 0,
+            limit: max_width.saturating_sub(column + 1),
+        };
+        if write_node(&mut fitted, value, &single_line, hooked, depth, column).is_ok() {
+            return out.write_str(&fitted.text);
+        }
+    }
+    let separator = if style.space_after_comma && !multi_line {
+        ", "
+    } else {
+        ","
+    };
+    let colon = if style.space_after_colon { ": " } else { ":" };
+    let child_column = (depth + 1) * indent_width(style);
+    match value {
+        Value::Object(properties) if properties.is_empty() => out.write_str("{}"),
+        Value::Object(properties) => {
+            let mut members: Vec<_> = properties.iter().collect();
+            if style.sort_keys {
+                members.sort_by(|a, b| a.0.cmp(b.0));
+            }
+            out.write_char('{')?;
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.write_str(separator)?;
+                }
+                write_newline(out, style, multi_line, depth + 1)?;
+                write_key(out, key, options, hooked.hook)?;
+                out.write_str(colon)?;
+                let column = match options.max_width {
+                    Some(_) => child_column + key_width(key, options, hooked.hook) + colon.len(),
+                    None => 0,
+                };
+                hooked.child(
+                    || PathSegment::Key(key.to_string()),
+                    |out, hooked| write_node(out, value, options, hooked, depth + 1, column),
+                    out,
+                )?;
+            }
+            write_newline(out, style, multi_line, depth)?;
+            out.write_char('}')
+        }
+        Value::Array(elements) if elements.is_empty() => out.write_str("[]"),
+        Value::Array(elements) => {
+            out.write_char('[')?;
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    out.write_str(separator)?;
+                }
+                write_newline(out, style, multi_line, depth + 1)?;
+                hooked.child(
+                    || PathSegment::Index(i),
+                    |out, hooked| {
+                        write_node(out, element, options, hooked, depth + 1, child_column)
+                    },
+                    out,
+                )?;
+            }
+            write_newline(out, style, multi_line, depth)?;
+            out.write_char(']')
+        }
+        Value::String(string) => match hooked.replace(string) {
+            Some(replacement) => write_hooked_string(out, &replacement, options, hooked.hook),
+            None => write_hooked_string(out, string, options, hooked.hook),
+        },
+        Value::Number(number) => write_number(out, number, options),
+        Value::Bool(value) => write!(out, "{}", value),
+        Value::Null => out.write_str("null"),
+        Value::Raw(raw) => out.write_str(raw.get()),
+    }
+}
+
+fn write_newline<W: Write>(
+    out: &mut W,
+    style: &Style,
+    multi_line: bool,
+    depth: usize,
+) -> fmt::Result {
+    if !multi_line {
+        return Ok(());
+    }
+    out.write_str(style.newline_str())?;
+    for _ in 0..depth {
+        out.write_str(style.indent_unit())?;
+    }
+    Ok(())
+}
+
+// a tab is counted as four columns
+fn indent_width(style: &Style) -> usize {
+    match style.indent {
+        Some(Indent::Tab) => 4,
+        _ => style.indent_unit().len(),
+    }
+}
+
+fn write_key<W: Write>(
+    out: &mut W,
+    key: &str,
+    options: &SerializeOptions,
+    hook: &dyn StringHook,
+) -> fmt::Result {
+    match options.style.key_quoting {
+        KeyQuoting::WhenNeeded
+            if style::is_identifier(key) && !key.chars().any(|c| hook.escape(c)) =>
+        {
+            out.write_str(key)
+        }
+        _ => write_hooked_string(out, key, options, hook),
+    }
+}
+
+// the width of a key as written
+fn key_width(key: &str, options: &SerializeOptions, hook: &dyn StringHook) -> usize {
+    let mut fitted = Fitted {
+        text: String::new(),
+        width: 0,
+        limit: usize::MAX,
+    };
+    let _ = write_key(&mut fitted, key, options, hook);
+    fitted.width
+}
+
+// collects a single-line rendering and gives up as soon as it grows past the limit
+struct Fitted {
+    text: String,
+    width: usize,
+    limit: usize,
+}
+
+impl Write for Fitted {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.width += s.chars().count();
+        if self.width > self.limit || s.contains('\n') {
+            return Err(fmt::Error);
+        }
+        self.text.push_str(s);
+        Ok(())
+    }
+}
+
+// JSON has no NaN or infinity, so those become null
+fn write_number<W: Write>(out: &mut W, number: &Number, options: &SerializeOptions) -> fmt::Result {
+    match number {
+        Number::F64(n) if !n.is_finite() => out.write_str("null"),
+        Number::F64(n) => write_f64(out, *n, options),
+        _ => write!(out, "{}", number),
+    }
+}
+
+// std's float formatting already gives the shortest digits that parse back to the same
+// f64 (0.1 stays "0.1"), but never switches to an exponent on its own, so 1e300 would
+// come out as 301 digits
+pub(crate) fn write_f64<W: Write>(out: &mut W, n: f64, options: &SerializeOptions) -> fmt::Result {
+    let magnitude = n.abs();
+    let positional = n == 0.0
+        || !n.is_finite()
+        || (magnitude >= options.exponent_below && magnitude < options.exponent_from);
+    if positional {
+        let text = match options.float_decimals {
+            Some(decimals) => format!("{:.*}", decimals, n),
+            None => n.to_string(),
+        };
+        out.write_str(&text)?;
+        if options.float_point && n.is_finite() && !text.contains('.') {
+            out.write_str(".0")?;
+        }
+        return Ok(());
+    }
+    let text = match options.float_decimals {
+        Some(decimals) => format!("{:.*e}", decimals, n),
+        None => format!("{:e}", n),
+    };
+    match text.split_once('e') {
+        Some((digits, exponent)) if !exponent.starts_with('-') => {
+            write!(out, "{}e+{}", digits, exponent)
+        }
+        _ => out.write_str(&text),
+    }
+}
+
+pub(crate) fn write_string<W: Write>(
+    out: &mut W,
+    string: &str,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    write_hooked_string(out, string, options, &NoHook)
+}
+
+fn write_hooked_string<W: Write>(
+    out: &mut W,
+    string: &str,
+    options: &SerializeOptions,
+    hook: &dyn StringHook,
+) -> fmt::Result {
+    out.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{08}' => out.write_str("\\b")?,
+            '\u{0C}' => out.write_str("\\f")?,
+            c if c < ' ' => write!(out, "\\u{:04x}", c as u32)?,
+            '<' | '>' | '&' | '\u{2028}' | '\u{2029}' if options.html_safe => {
+                write!(out, "\\u{:04x}", c as u32)?
+            }
+            c if (options.ascii_only && !c.is_ascii()) || hook.escape(c) => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(out, "\\u{:04x}", unit)?;
+                }
+            }
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+// writes JSON as it is produced, one event at a time, without building a Value first;
+// nesting is checked as it goes, so a key outside an object or an unbalanced end() is an
+// error instead of broken output
+pub struct JsonWriter<W> {
+    writer: W,
+    stack: Vec<Frame>,
+    done: bool,     // the top-level value is complete
+    buffer: String, // reused to format each piece before it is written
+}
+
+struct Frame {
+    object: bool,
+    count: usize,    // members or elements written so far
+    after_key: bool, // in an object, a key was written and its value is due
+}
+
+impl<W: io::Write> JsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        JsonWriter {
+            writer,
+            stack: Vec::new(),
+            done: false,
+            buffer: String::new(),
+        }
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), Error> {
+        self.before_value()?;
+        self.stack.push(Frame {
+            object: true,
+            count: 0,
+            after_key: false,
+        });
+        self.emit("{")
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), Error> {
+        self.before_value()?;
+        self.stack.push(Frame {
+            object: false,
+            count: 0,
+            after_key: false,
+        });
+        self.emit("[")
+    }
+
+    pub fn key(&mut self, key: &str) -> Result<(), Error> {
+        let frame = match self.stack.last_mut() {
+            Some(frame) if frame.object && !frame.after_key => frame,
+            Some(frame) if frame.object => return Err("Expected a value after the key".into()),
+            _ => return Err("A key can only be written inside an object".into()),
+        };
+        frame.after_key = true;
+        self.buffer.clear();
+        if frame.count > 0 {
+            self.buffer.push(',');
+        }
+        let _ = write_string(&mut self.buffer, key, &SerializeOptions::default());
+        self.buffer.push(':');
+        self.flush_buffer()
+    }
+
+    // a whole value at once, e.g. a string, a number, or an already-built subtree
+    pub fn value<V: Into<Value>>(&mut self, value: V) -> Result<(), Error> {
+        self.before_value()?;
+        self.buffer.clear();
+        let _ = write_value(
+            &mut self.buffer,
+            &value.into(),
+            &SerializeOptions::default(),
+            0,
+        );
+        self.flush_buffer()?;
+        self.after_value();
+        Ok(())
+    }
+
+    // closes the innermost open object or array
+    pub fn end(&mut self) -> Result<(), Error> {
+        let frame = match self.stack.pop() {
+            Some(frame) if frame.after_key => {
+                return Err("Expected a value after the key".into());
+            }
+            Some(frame) => frame,
+            None => return Err("Nothing to end".into()),
+        };
+        self.emit(if frame.object { "}" } else { "]" })?;
+        self.after_value();
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    // checks that exactly one complete value was written, then flushes
+    pub fn finish(mut self) -> Result<W, Error> {
+        if !self.stack.is_empty() {
+            return Err(format!("{} unclosed object(s) or array(s)", self.stack.len()).into());
+        }
+        if !self.done {
+            return Err("Nothing was written".into());
+        }
+        self.flush()?;
+        Ok(self.writer)
+    }
+
+    fn before_value(&mut self) -> Result<(), Error> {
+        match self.stack.last() {
+            None if self.done => Err("Only one top-level value can be written".into()),
+            None => Ok(()),
+            Some(frame) if frame.object && !frame.after_key => {
+                Err("Expected a key before a value inside an object".into())
+            }
+            Some(frame) if !frame.object && frame.count > 0 => self.emit(","),
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn after_value(&mut self) {
+        match self.stack.last_mut() {
+            Some(frame) => {
+                frame.count += 1;
+                frame.after_key = false;
+            }
+            None => self.done = true,
+        }
+    }
+
+    fn emit(&mut self, text: &str) -> Result<(), Error> {
+        Ok(self.writer.write_all(text.as_bytes())?)
+    }
+
+    fn flush_buffer(&mut self) -> Result<(), Error> {
+        Ok(self.writer.write_all(self.buffer.as_bytes())?)
+    }
+}
+
+impl fmt::Display for Value {
+    // `{}` is compact, `{:#}` is pretty-printed
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = SerializeOptions::new().with_pretty(f.alternate());
+        write_value(f, self, &options, 0)
+    }
+}
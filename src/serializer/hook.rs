@@ -0,0 +1,23 @@
+// lets the caller decide how individual strings come out, without touching the Value:
+// e.g. masking secrets or escaping characters a downstream consumer chokes on
+use crate::path::JsonPath;
+
+pub trait StringHook {
+    // called for every string value (not keys) with where it sits; returning Some
+    // writes that text instead, escaped as usual
+    fn replace(&self, path: &JsonPath, string: &str) -> Option<String> {
+        let _ = (path, string);
+        None
+    }
+
+    // characters for which this is true are written as \uXXXX, in keys too
+    fn escape(&self, c: char) -> bool {
+        let _ = c;
+        false
+    }
+}
+
+// the hook that changes nothing, used when none is given
+pub(super) struct NoHook;
+
+impl StringHook for NoHook {}
@@ -0,0 +1,133 @@
+// how the output is laid out, independent of what is written: indentation, spacing,
+// key quoting and line endings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize), // at most 16
+    Tab,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Newline {
+    Lf,
+    CrLf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyQuoting {
+    Always,
+    // keys that are plain identifiers, such as `name` or `_id`, are left bare as JSON5
+    // allows; the result is no longer JSON
+    WhenNeeded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub(crate) indent: Option<Indent>, // None puts everything on one line
+    pub(crate) space_after_colon: bool,
+    pub(crate) space_after_comma: bool, // only where no line break follows the comma
+    pub(crate) key_quoting: KeyQuoting,
+    pub(crate) newline: Newline,
+    pub(crate) final_newline: bool,
+    pub(crate) sort_keys: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style::compact()
+    }
+}
+
+impl Style {
+    // {"a":1,"b":[1,2]}, what `{}` writes
+    pub fn compact() -> Self {
+        Style {
+            indent: None,
+            space_after_colon: false,
+            space_after_comma: false,
+            key_quoting: KeyQuoting::Always,
+            newline: Newline::Lf,
+            final_newline: false,
+            sort_keys: false,
+        }
+    }
+
+    // two-space indentation, what `{:#}` writes
+    pub fn pretty() -> Self {
+        Style {
+            indent: Some(Indent::Spaces(2)),
+            space_after_colon: true,
+            space_after_comma: true,
+            ..Style::compact()
+        }
+    }
+
+    // for files kept in version control: one element per line, keys sorted so that
+    // reordering doesn't show up as a change, and a newline at the end
+    pub fn diff_friendly() -> Self {
+        Style {
+            final_newline: true,
+            sort_keys: true,
+            ..Style::pretty()
+        }
+    }
+
+    pub fn with_indent(mut self, indent: Option<Indent>) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_space_after_colon(mut self, space: bool) -> Self {
+        self.space_after_colon = space;
+        self
+    }
+
+    pub fn with_space_after_comma(mut self, space: bool) -> Self {
+        self.space_after_comma = space;
+        self
+    }
+
+    pub fn with_key_quoting(mut self, key_quoting: KeyQuoting) -> Self {
+        self.key_quoting = key_quoting;
+        self
+    }
+
+    pub fn with_newline(mut self, newline: Newline) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    pub fn with_final_newline(mut self, final_newline: bool) -> Self {
+        self.final_newline = final_newline;
+        self
+    }
+
+    pub fn with_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    pub(crate) fn indent_unit(&self) -> &'static str {
+        const SPACES: &str = "                ";
+        match self.indent {
+            Some(Indent::Spaces(n)) => &SPACES[..n.min(SPACES.len())],
+            Some(Indent::Tab) => "\t",
+            None => "",
+        }
+    }
+
+    pub(crate) fn newline_str(&self) -> &'static str {
+        match self.newline {
+            Newline::Lf => "\n",
+            Newline::CrLf => "\r\n",
+        }
+    }
+}
+
+// an ECMAScript identifier, restricted to ASCII
+pub(crate) fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
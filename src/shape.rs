@@ -0,0 +1,185 @@
+// lightweight structural checks, usually written with the `shape!` macro:
+// shape!({ "id": u64, "name": str, "tags": [str], "meta"?: (object | null) })
+use crate::Value;
+use crate::path::{JsonPath, PathSegment};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Any,
+    Null,
+    Bool,
+    Number,
+    Integer,  // fits i64
+    Unsigned, // fits u64
+    String,
+    AnyArray,
+    AnyObject,
+    Array(Box<Shape>),  // every element has this shape
+    Object(Vec<Field>), // keys not listed are allowed
+    OneOf(Vec<Shape>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub key: String,
+    pub required: bool,
+    pub shape: Shape,
+}
+
+impl Field {
+    pub fn required<S: Into<String>>(key: S, shape: Shape) -> Self {
+        Field {
+            key: key.into(),
+            required: true,
+            shape,
+        }
+    }
+
+    pub fn optional<S: Into<String>>(key: S, shape: Shape) -> Self {
+        Field {
+            key: key.into(),
+            required: false,
+            shape,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub path: JsonPath,
+    pub message: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.segments() {
+            [] => write!(f, "$: {}", self.message),
+            _ => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl Shape {
+    pub fn matches(&self, value: &Value) -> bool {
+        self.check(value).is_ok()
+    }
+
+    // collects every mismatch instead of stopping at the first one
+    pub fn check(&self, value: &Value) -> Result<(), Vec<Mismatch>> {
+        let mut mismatches = Vec::new();
+        self.check_at(value, &mut JsonPath::root(), &mut mismatches);
+        match mismatches.is_empty() {
+            true => Ok(()),
+            false => Err(mismatches),
+        }
+    }
+
+    fn check_at(&self, value: &Value, path: &mut JsonPath, mismatches: &mut Vec<Mismatch>) {
+        let accepted = match (self, value) {
+            (Shape::Any, _) => true,
+            (Shape::Null, Value::Null) => true,
+            (Shape::Bool, Value::Bool(_)) => true,
+            (Shape::Number, Value::Number(_)) => true,
+            (Shape::Integer, _) => value.as_i64().is_some(),
+            (Shape::Unsigned, _) => value.as_u64().is_some(),
+            (Shape::String, Value::String(_)) => true,
+            (Shape::AnyArray, Value::Array(_)) => true,
+            (Shape::AnyObject, Value::Object(_)) => true,
+            (Shape::Array(element), Value::Array(elements)) => {
+                for (i, child) in elements.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    element.check_at(child, path, mismatches);
+                    path.pop();
+                }
+                true
+            }
+            (Shape::Object(fields), Value::Object(properties)) => {
+                for field in fields {
+                    path.push(PathSegment::Key(field.key.clone()));
+                    match properties.get(&field.key) {
+                        Some(child) => field.shape.check_at(child, path, mismatches),
+                        None if field.required => mismatches.push(Mismatch {
+                            path: path.clone(),
+                            message: format!("missing required {}", field.shape),
+                        }),
+                        None => {}
+                    }
+                    path.pop();
+                }
+                true
+            }
+            (Shape::OneOf(shapes), _) => shapes.iter().any(|shape| shape.matches(value)),
+            _ => false,
+        };
+        if !accepted {
+            mismatches.push(Mismatch {
+                path: path.clone(),
+                message: format!("expected {}, found {}", self, value.type_name()),
+            });
+        }
+    }
+}
+
+// the expected-type wording used in mismatch messages
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shape::Any => f.write_str("any value"),
+            Shape::Null => f.write_str("null"),
+            Shape::Bool => f.write_str("boolean"),
+            Shape::Number => f.write_str("number"),
+            Shape::Integer => f.write_str("integer"),
+            Shape::Unsigned => f.write_str("non-negative integer"),
+            Shape::String => f.write_str("string"),
+            Shape::AnyArray | Shape::Array(_) => f.write_str("array"),
+            Shape::AnyObject | Shape::Object(_) => f.write_str("object"),
+            Shape::OneOf(shapes) => {
+                for (i, shape) in shapes.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" or ")?;
+                    }
+                    write!(f, "{}", shape)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// types: any, null, bool, number, i64, u64, f64 (same as number), str, array, object,
+// [T] for arrays of T, { ... } for objects, (A | B) for either; `"key"?:` marks a key optional
+#[macro_export]
+macro_rules! shape {
+    (@fields [$($out:expr,)*]) => {
+        vec![$($out),*]
+    };
+    (@fields [$($out:expr,)*] $key:literal ?: $shape:tt $(, $($rest:tt)*)?) => {
+        $crate::shape!(@fields [$($out,)* $crate::shape::Field::optional($key, $crate::shape!(@shape $shape)),] $($($rest)*)?)
+    };
+    (@fields [$($out:expr,)*] $key:literal : $shape:tt $(, $($rest:tt)*)?) => {
+        $crate::shape!(@fields [$($out,)* $crate::shape::Field::required($key, $crate::shape!(@shape $shape)),] $($($rest)*)?)
+    };
+    (@shape any) => { $crate::shape::Shape::Any };
+    (@shape null) => { $crate::shape::Shape::Null };
+    (@shape bool) => { $crate::shape::Shape::Bool };
+    (@shape number) => { $crate::shape::Shape::Number };
+    (@shape f64) => { $crate::shape::Shape::Number };
+    (@shape i64) => { $crate::shape::Shape::Integer };
+    (@shape u64) => { $crate::shape::Shape::Unsigned };
+    (@shape str) => { $crate::shape::Shape::String };
+    (@shape array) => { $crate::shape::Shape::AnyArray };
+    (@shape object) => { $crate::shape::Shape::AnyObject };
+    (@shape [$element:tt]) => {
+        $crate::shape::Shape::Array(Box::new($crate::shape!(@shape $element)))
+    };
+    (@shape { $($fields:tt)* }) => {
+        $crate::shape::Shape::Object($crate::shape!(@fields [] $($fields)*))
+    };
+    (@shape ($($alternative:tt)|+)) => {
+        $crate::shape::Shape::OneOf(vec![$($crate::shape!(@shape $alternative)),+])
+    };
+    ($shape:tt) => {
+        $crate::shape!(@shape $shape)
+    };
+}
@@ -0,0 +1,400 @@
+// a read-only Value whose nodes sit behind Arc: clone is O(1) and it can be sent across threads
+use crate::sax::{self, ContentHandler};
+use crate::{Error, Number, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Index;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ArcValue {
+    Object(Arc<[(Arc<str>, ArcValue)]>), // in document order, looked up by linear scan
+    Array(Arc<[ArcValue]>),
+    String(Arc<str>),
+    Number(Number),
+    Bool(bool),
+    #[default]
+    Null,
+}
+
+static NULL: ArcValue = ArcValue::Null;
+
+impl ArcValue {
+    pub fn get(&self, key: &str) -> Option<&ArcValue> {
+        self.as_object()?
+            .iter()
+            .find(|(name, _)| **name == *key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_index(&self, index: usize) -> Option<&ArcValue> {
+        self.as_array()?.get(index)
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, ArcValue::Object(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, ArcValue::Array(_))
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, ArcValue::Null)
+    }
+
+    pub fn as_object(&self) -> Option<&[(Arc<str>, ArcValue)]> {
+        match self {
+            ArcValue::Object(properties) => Some(properties),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[ArcValue]> {
+        match self {
+            ArcValue::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ArcValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            ArcValue::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ArcValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // true when both are the same allocation, not just equal
+    pub fn ptr_eq(&self, other: &ArcValue) -> bool {
+        match (self, other) {
+            (ArcValue::Object(a), ArcValue::Object(b)) => Arc::ptr_eq(a, b),
+            (ArcValue::Array(a), ArcValue::Array(b)) => Arc::ptr_eq(a, b),
+            (ArcValue::String(a), ArcValue::String(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    // identical subtrees, keys and strings end up sharing one allocation
+    pub fn dedup(&self) -> ArcValue {
+        Interner::default().intern(self)
+    }
+
+    // a deep copy back into an editable Value
+    pub fn to_value(&self) -> Value {
+        match self {
+            ArcValue::Object(properties) => Value::Object(
+                properties
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_value()))
+                    .collect(),
+            ),
+            ArcValue::Array(elements) => elements.iter().map(ArcValue::to_value).collect(),
+            ArcValue::String(string) => Value::String(string.to_string()),
+            ArcValue::Number(number) => Value::Number(number.clone()),
+            ArcValue::Bool(value) => Value::Bool(*value),
+            ArcValue::Null => Value::Null,
+        }
+    }
+}
+
+impl Value {
+    // like ArcValue::from(self).dedup(), without building the unshared copy first
+    pub fn dedup(&self) -> ArcValue {
+        Interner::default().intern_value(self)
+    }
+}
+
+// hash-consing, bottom-up: children are interned first, so a node can be keyed
+// by its children's addresses instead of hashing the whole subtree again
+#[derive(Default)]
+struct Interner {
+    strings: KeyInterner,
+    objects: HashMap<Vec<(usize, NodeKey)>, ArcValue>,
+    arrays: HashMap<Vec<NodeKey>, ArcValue>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum NodeKey {
+    Object(usize),
+    Array(usize),
+    String(usize),
+    Number(String), // by text, so 1 and 1.0 stay distinct
+    Bool(bool),
+    Null,
+}
+
+impl NodeKey {
+    fn of(value: &ArcValue) -> NodeKey {
+        match value {
+            ArcValue::Object(properties) => {
+                NodeKey::Object(Arc::as_ptr(properties).cast::<u8>() as usize)
+            }
+            ArcValue::Array(elements) => {
+                NodeKey::Array(Arc::as_ptr(elements).cast::<u8>() as usize)
+            }
+            ArcValue::String(string) => NodeKey::String(Arc::as_ptr(string).cast::<u8>() as usize),
+            ArcValue::Number(number) => NodeKey::Number(number.to_string()),
+            ArcValue::Bool(value) => NodeKey::Bool(*value),
+            ArcValue::Null => NodeKey::Null,
+        }
+    }
+}
+
+impl Interner {
+    fn intern(&mut self, value: &ArcValue) -> ArcValue {
+        match value {
+            ArcValue::Object(properties) => {
+                let properties = properties
+                    .iter()
+                    .map(|(key, value)| (self.string(key), self.intern(value)))
+                    .collect();
+                self.object(properties)
+            }
+            ArcValue::Array(elements) => {
+                let elements = elements.iter().map(|value| self.intern(value)).collect();
+                self.array(elements)
+            }
+            ArcValue::String(string) => ArcValue::String(self.string(string)),
+            value => value.clone(),
+        }
+    }
+
+    fn intern_value(&mut self, value: &Value) -> ArcValue {
+        match value {
+            Value::Object(properties) => {
+                let properties = properties
+                    .iter()
+                    .map(|(key, value)| (self.string(key), self.intern_value(value)))
+                    .collect();
+                self.object(properties)
+            }
+            Value::Array(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|value| self.intern_value(value))
+                    .collect();
+                self.array(elements)
+            }
+            Value::String(string) => ArcValue::String(self.string(string)),
+            Value::Raw(raw) => self.intern_value(&raw.parse().unwrap_or_default()),
+            value => ArcValue::from(value),
+        }
+    }
+
+    fn string(&mut self, string: &str) -> Arc<str> {
+        self.strings.intern(string)
+    }
+
+    fn object(&mut self, properties: Vec<(Arc<str>, ArcValue)>) -> ArcValue {
+        let key = properties
+            .iter()
+            .map(|(name, value)| (Arc::as_ptr(name).cast::<u8>() as usize, NodeKey::of(value)))
+            .collect();
+        self.objects
+            .entry(key)
+            .or_insert_with(|| ArcValue::Object(properties.into()))
+            .clone()
+    }
+
+    fn array(&mut self, elements: Vec<ArcValue>) -> ArcValue {
+        let key = elements.iter().map(NodeKey::of).collect();
+        self.arrays
+            .entry(key)
+            .or_insert_with(|| ArcValue::Array(elements.into()))
+            .clone()
+    }
+}
+
+// hands out one shared allocation per distinct string; keep one around to share
+// keys across many parses, or use a fresh one per document
+#[derive(Debug, Clone, Default)]
+pub struct KeyInterner {
+    keys: HashSet<Arc<str>>,
+}
+
+impl KeyInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn intern(&mut self, key: &str) -> Arc<str> {
+        if let Some(shared) = self.keys.get(key) {
+            return shared.clone();
+        }
+        let shared: Arc<str> = Arc::from(key);
+        self.keys.insert(shared.clone());
+        shared
+    }
+}
+
+// parses straight into an ArcValue, so repeated object keys are allocated once
+pub fn parse_str(input: &str, interner: &mut KeyInterner) -> Result<ArcValue, Error> {
+    let mut builder = Builder {
+        interner,
+        stack: Vec::new(),
+        root: ArcValue::Null,
+    };
+    sax::generate(input, &mut builder)?;
+    Ok(builder.root)
+}
+
+struct Builder<'i> {
+    interner: &'i mut KeyInterner,
+    stack: Vec<Container>,
+    root: ArcValue,
+}
+
+enum Container {
+    Object(Vec<(Arc<str>, ArcValue)>, Option<Arc<str>>),
+    Array(Vec<ArcValue>),
+}
+
+impl Builder<'_> {
+    fn push_value(&mut self, value: ArcValue) {
+        match self.stack.last_mut() {
+            Some(Container::Object(properties, key)) => {
+                let key = key.take().unwrap_or_else(|| self.interner.intern(""));
+                // a repeated key keeps its first position but takes the last value, as in Map;
+                // keys come from one interner, so comparing addresses is enough
+                match properties
+                    .iter_mut()
+                    .find(|(name, _)| Arc::ptr_eq(name, &key))
+                {
+                    Some((_, existing)) => *existing = value,
+                    None => properties.push((key, value)),
+                }
+            }
+            Some(Container::Array(elements)) => elements.push(value),
+            None => self.root = value,
+        }
+    }
+}
+
+impl ContentHandler for Builder<'_> {
+    fn on_object_start(&mut self) {
+        self.stack.push(Container::Object(Vec::new(), None));
+    }
+    fn on_object_end(&mut self) {
+        if let Some(Container::Object(properties, _)) = self.stack.pop() {
+            self.push_value(ArcValue::Object(properties.into()));
+        }
+    }
+    fn on_array_start(&mut self) {
+        self.stack.push(Container::Array(Vec::new()));
+    }
+    fn on_array_end(&mut self) {
+        if let Some(Container::Array(elements)) = self.stack.pop() {
+            self.push_value(ArcValue::Array(elements.into()));
+        }
+    }
+    fn on_key(&mut self, key: &str) {
+        let key = self.interner.intern(key);
+        if let Some(Container::Object(_, pending)) = self.stack.last_mut() {
+            *pending = Some(key);
+        }
+    }
+    fn on_string(&mut self, value: &str) {
+        self.push_value(ArcValue::String(Arc::from(value)));
+    }
+    fn on_number(&mut self, value: Number) {
+        self.push_value(ArcValue::Number(value));
+    }
+    fn on_bool(&mut self, value: bool) {
+        self.push_value(ArcValue::Bool(value));
+    }
+    fn on_null(&mut self) {
+        self.push_value(ArcValue::Null);
+    }
+}
+
+impl From<&Value> for ArcValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Object(properties) => ArcValue::Object(
+                properties
+                    .iter()
+                    .map(|(key, value)| (Arc::from(key.as_str()), ArcValue::from(value)))
+                    .collect(),
+            ),
+            Value::Array(elements) => {
+                ArcValue::Array(elements.iter().map(ArcValue::from).collect())
+            }
+            Value::String(string) => ArcValue::String(Arc::from(string.as_str())),
+            Value::Number(number) => ArcValue::Number(number.clone()),
+            Value::Bool(value) => ArcValue::Bool(*value),
+            Value::Null => ArcValue::Null,
+            // the text was validated when the RawValue was made, so parsing can't fail
+            Value::Raw(raw) => ArcValue::from(&raw.parse().unwrap_or_default()),
+        }
+    }
+}
+
+impl From<Value> for ArcValue {
+    fn from(value: Value) -> Self {
+        ArcValue::from(&value)
+    }
+}
+
+impl From<&ArcValue> for Value {
+    fn from(value: &ArcValue) -> Self {
+        value.to_value()
+    }
+}
+
+// missing keys and out-of-range indexes give Null, as with Value
+impl Index<&str> for ArcValue {
+    type Output = ArcValue;
+
+    fn index(&self, key: &str) -> &ArcValue {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl Index<usize> for ArcValue {
+    type Output = ArcValue;
+
+    fn index(&self, index: usize) -> &ArcValue {
+        self.get_index(index).unwrap_or(&NULL)
+    }
+}
+
+// goes through Value, so it costs a deep copy
+impl fmt::Display for ArcValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_value(), f)
+    }
+}
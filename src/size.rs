@@ -0,0 +1,78 @@
+// rough memory accounting: the inline Value plus everything it allocates
+use crate::path::{JsonPath, PathSegment};
+use crate::{Number, Value};
+
+impl Value {
+    // an estimate: allocator overhead and unused hash table slots are approximated
+    pub fn estimated_size_bytes(&self) -> usize {
+        size_of::<Value>() + heap_bytes(self)
+    }
+
+    // the estimated size of every subtree down to `max_depth` (0 is just the root),
+    // parents before children
+    pub fn size_breakdown(&self, max_depth: usize) -> Vec<(JsonPath, usize)> {
+        let mut sizes = Vec::new();
+        measure(self, &mut JsonPath::root(), max_depth, &mut sizes);
+        sizes
+    }
+}
+
+fn heap_bytes(value: &Value) -> usize {
+    let children: usize = match value {
+        Value::Object(properties) => properties.values().map(heap_bytes).sum(),
+        Value::Array(elements) => elements.iter().map(heap_bytes).sum(),
+        _ => 0,
+    };
+    own_bytes(value) + children
+}
+
+fn measure(
+    value: &Value,
+    path: &mut JsonPath,
+    max_depth: usize,
+    sizes: &mut Vec<(JsonPath, usize)>,
+) -> usize {
+    if path.segments().len() > max_depth {
+        return heap_bytes(value);
+    }
+    // reserve the parent's slot now and fill it in once the children are counted
+    let slot = sizes.len();
+    sizes.push((path.clone(), 0));
+    let mut heap = own_bytes(value);
+    match value {
+        Value::Object(properties) => {
+            for (key, child) in properties {
+                path.push(PathSegment::Key(key.clone()));
+                heap += measure(child, path, max_depth, sizes);
+                path.pop();
+            }
+        }
+        Value::Array(elements) => {
+            for (i, child) in elements.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                heap += measure(child, path, max_depth, sizes);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    sizes[slot].1 = size_of::<Value>() + heap;
+    heap
+}
+
+// allocations held directly by this node; children live inside these buffers
+fn own_bytes(value: &Value) -> usize {
+    match value {
+        Value::Object(properties) => properties.heap_bytes(),
+        Value::Array(elements) => elements.capacity() * size_of::<Value>(),
+        Value::String(string) => string.capacity(),
+        Value::Number(number) => match number {
+            Number::Raw(text) => text.len(),
+            #[cfg(feature = "arbitrary_precision")]
+            Number::Arbitrary(text) => text.len(),
+            _ => 0,
+        },
+        Value::Raw(raw) => raw.get().len(),
+        Value::Bool(_) | Value::Null => 0,
+    }
+}
@@ -0,0 +1,34 @@
+// normalizing documents before diffing or snapshot tests
+use crate::value::mismatch;
+use crate::{Error, Value};
+use std::cmp::Ordering;
+
+impl Value {
+    pub fn sort_keys_recursive(&mut self) {
+        match self {
+            Value::Object(properties) => {
+                properties.sort_keys();
+                properties.values_mut().for_each(Value::sort_keys_recursive);
+            }
+            Value::Array(elements) => elements.iter_mut().for_each(Value::sort_keys_recursive),
+            _ => {}
+        }
+    }
+
+    // `path` uses get_path syntax, e.g. "users" or "data.rows[0]"; "" is this value
+    pub fn sort_array_by_path<F>(&mut self, path: &str, compare: F) -> Result<(), Error>
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        let node = self
+            .get_path_mut(path)
+            .ok_or_else(|| Error::Conversion(format!("no value at path '{}'", path)))?;
+        match node {
+            Value::Array(elements) => {
+                elements.sort_by(compare);
+                Ok(())
+            }
+            _ => Err(mismatch("array", node)),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+// arrays of objects → a SQLite script: a CREATE TABLE with a column per top-level key
+// and one INSERT per object, in a single transaction, so `sqlite3 dump.db < dump.sql`
+// makes a JSON dump queryable. Nested values are stored as their JSON text, which
+// SQLite's json_extract and ->> can reach into.
+use crate::serializer::{SerializeOptions, write_f64};
+use crate::{Error, Map, Number, Value};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+
+// what a column has held, to pick its declared type
+#[derive(Debug, Clone, Copy, Default)]
+struct Column {
+    integer: bool, // booleans too, stored as 0 and 1
+    real: bool,
+    text: bool, // strings, and objects and arrays as JSON text
+}
+
+impl Column {
+    fn add(&mut self, value: &Value) {
+        match value {
+            Value::Null => {}
+            Value::Bool(_) => self.integer = true,
+            Value::Number(Number::I64(_) | Number::U64(_)) => self.integer = true,
+            Value::Number(_) => self.real = true,
+            Value::String(_) | Value::Array(_) | Value::Object(_) => self.text = true,
+            Value::Raw(raw) => self.add(&raw.parse().unwrap_or_default()),
+        }
+    }
+
+    // a column that held both numbers and text is left untyped, so SQLite keeps each
+    // value as it was written rather than converting it
+    fn declared_type(&self) -> Option<&'static str> {
+        match (self.integer, self.real, self.text) {
+            (true, false, false) => Some("INTEGER"),
+            (_, true, false) => Some("REAL"),
+            (false, false, true) => Some("TEXT"),
+            _ => None,
+        }
+    }
+}
+
+// columns are the union of every object's keys in the order they first appear; null and
+// missing members are NULL, booleans 0 and 1, and NaN and infinity NULL as in JSON output
+pub fn to_sqlite_script<W: Write>(writer: W, table: &str, value: &Value) -> Result<(), Error> {
+    let elements = value.as_array().ok_or_else(|| {
+        Error::Conversion(format!(
+            "SQLite export needs an array of objects, not {}",
+            value.type_name()
+        ))
+    })?;
+    let mut rows: Vec<&Map> = Vec::with_capacity(elements.len());
+    for (index, element) in elements.iter().enumerate() {
+        match element.as_object() {
+            Some(row) => rows.push(row),
+            None => {
+                return Err(Error::Conversion(format!(
+                    "[{}]: SQLite rows must be objects, not {}",
+                    index,
+                    element.type_name()
+                )));
+            }
+        }
+    }
+    let mut names: Vec<&str> = Vec::new();
+    let mut columns: Vec<Column> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for row in &rows {
+        for (key, value) in row.iter() {
+            let position = *positions.entry(key).or_insert_with(|| {
+                names.push(key);
+                columns.push(Column::default());
+                names.len() - 1
+            });
+            columns[position].add(value);
+        }
+    }
+    if names.is_empty() {
+        return Err(Error::Conversion(
+            "SQLite export needs at least one member to make a column of".to_string(),
+        ));
+    }
+
+    let mut writer = BufWriter::new(writer);
+    let mut line = String::from("BEGIN TRANSACTION;\nCREATE TABLE ");
+    push_identifier(&mut line, table);
+    line.push_str(" (");
+    for (i, (name, column)) in names.iter().zip(&columns).enumerate() {
+        if i > 0 {
+            line.push_str(", ");
+        }
+        push_identifier(&mut line, name);
+        if let Some(declared) = column.declared_type() {
+            line.push(' ');
+            line.push_str(declared);
+        }
+    }
+    line.push_str(");\n");
+    writer.write_all(line.as_bytes())?;
+
+    // the same for every row, so written once
+    let mut insert = String::from("INSERT INTO ");
+    push_identifier(&mut insert, table);
+    insert.push_str(" (");
+    for (i, name) in names.iter().enumerate() {
+        if i > 0 {
+            insert.push_str(", ");
+        }
+        push_identifier(&mut insert, name);
+    }
+    insert.push_str(") VALUES (");
+    for row in rows {
+        line.clear();
+        line.push_str(&insert);
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                line.push_str(", ");
+            }
+            push_literal(&mut line, row.get(name).unwrap_or(&Value::Null));
+        }
+        line.push_str(");\n");
+        writer.write_all(line.as_bytes())?;
+    }
+    writer.write_all(b"COMMIT;\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn push_identifier(out: &mut String, name: &str) {
+    out.push('"');
+    out.push_str(&name.replace('"', "\"\""));
+    out.push('"');
+}
+
+fn push_literal(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("NULL"),
+        Value::Bool(b) => out.push(if *b { '1' } else { '0' }),
+        Value::Number(Number::I64(n)) => {
+            let _ = write!(out, "{}", n);
+        }
+        // beyond i64, SQLite reads the literal as a REAL
+        Value::Number(Number::U64(n)) => {
+            let _ = write!(out, "{}", n);
+        }
+        Value::Number(number) => match number.as_f64() {
+            n if !n.is_finite() => out.push_str("NULL"),
+            n => {
+                let options = SerializeOptions::new().with_float_point(true);
+                let _ = write_f64(out, n, &options);
+            }
+        },
+        Value::String(s) => push_text(out, s),
+        Value::Array(_) | Value::Object(_) => push_text(out, &value.to_string()),
+        Value::Raw(raw) => push_literal(out, &raw.parse().unwrap_or_default()),
+    }
+}
+
+// a quoted literal, or the bytes in hex when a NUL would cut a quoted one short
+fn push_text(out: &mut String, text: &str) {
+    if text.contains('\0') {
+        out.push_str("CAST(X'");
+        for byte in text.bytes() {
+            let _ = write!(out, "{:02X}", byte);
+        }
+        out.push_str("' AS TEXT)");
+    } else {
+        out.push('\'');
+        out.push_str(&text.replace('\'', "''"));
+        out.push('\'');
+    }
+}
@@ -0,0 +1,222 @@
+// a profile of one document: what it is made of and where its bytes go, for working
+// out why a payload is slow to process or bigger than expected
+use crate::Value;
+use crate::path::{JsonPath, PathSegment};
+use crate::serializer::{SerializeOptions, write_string, write_value};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+// how many of the largest subtrees are kept
+const LARGEST: usize = 10;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+    pub depths: Vec<usize>, // depths[d] is the number of values at depth d; the root is at 0
+    pub serialized_bytes: usize, // as compact JSON
+    // the biggest objects and arrays below the root by compact size, largest first
+    pub largest: Vec<(JsonPath, usize)>,
+    pub keys: Vec<(String, usize)>, // object keys by how often they occur, most common first
+    pub string_lengths: Vec<Bucket>, // in characters, in power-of-two buckets
+}
+
+// string lengths from `min` up to and including `max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub min: usize,
+    pub max: usize,
+    pub count: usize,
+}
+
+impl Stats {
+    pub fn values(&self) -> usize {
+        self.objects + self.arrays + self.strings + self.numbers + self.booleans + self.nulls
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.depths.len().saturating_sub(1)
+    }
+}
+
+pub fn analyze(value: &Value) -> Stats {
+    let mut analyzer = Analyzer::default();
+    let serialized_bytes = analyzer.visit(value, &mut JsonPath::root());
+
+    let mut keys: Vec<(String, usize)> = analyzer.keys.into_iter().collect();
+    keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let string_lengths = analyzer
+        .string_lengths
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(i, &count)| {
+            let (min, max) = bucket_bounds(i);
+            Bucket { min, max, count }
+        })
+        .collect();
+    Stats {
+        serialized_bytes,
+        largest: analyzer.largest,
+        keys,
+        string_lengths,
+        ..analyzer.stats
+    }
+}
+
+#[derive(Default)]
+struct Analyzer {
+    stats: Stats,
+    keys: HashMap<String, usize>,
+    string_lengths: Vec<usize>, // counts per bucket, see bucket_index
+    largest: Vec<(JsonPath, usize)>,
+}
+
+impl Analyzer {
+    // returns the compact serialized size of `value`
+    fn visit(&mut self, value: &Value, path: &mut JsonPath) -> usize {
+        if let Value::Raw(raw) = value {
+            return self.visit(&raw.parse().unwrap_or_default(), path);
+        }
+        let depth = path.segments().len();
+        if self.stats.depths.len() <= depth {
+            self.stats.depths.resize(depth + 1, 0);
+        }
+        self.stats.depths[depth] += 1;
+
+        let size = match value {
+            Value::Object(properties) => {
+                self.stats.objects += 1;
+                // braces, plus a colon per member and a comma between members
+                let mut size = 2 + (properties.len() * 2).saturating_sub(1);
+                for (key, child) in properties {
+                    *self.keys.entry(key.clone()).or_default() += 1;
+                    size += string_bytes(key);
+                    path.push(PathSegment::Key(key.clone()));
+                    size += self.visit(child, path);
+                    path.pop();
+                }
+                size
+            }
+            Value::Array(elements) => {
+                self.stats.arrays += 1;
+                let mut size = 2 + elements.len().saturating_sub(1);
+                for (i, child) in elements.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    size += self.visit(child, path);
+                    path.pop();
+                }
+                size
+            }
+            Value::String(string) => {
+                self.stats.strings += 1;
+                let bucket = bucket_index(string.chars().count());
+                if self.string_lengths.len() <= bucket {
+                    self.string_lengths.resize(bucket + 1, 0);
+                }
+                self.string_lengths[bucket] += 1;
+                string_bytes(string)
+            }
+            leaf => {
+                match leaf {
+                    Value::Number(_) => self.stats.numbers += 1,
+                    Value::Bool(_) => self.stats.booleans += 1,
+                    _ => self.stats.nulls += 1,
+                }
+                let mut counter = Counter(0);
+                let _ = write_value(&mut counter, leaf, &SerializeOptions::default(), 0);
+                counter.0
+            }
+        };
+        if depth > 0 && (value.is_object() || value.is_array()) {
+            self.record_largest(path, size);
+        }
+        size
+    }
+
+    fn record_largest(&mut self, path: &JsonPath, size: usize) {
+        if self.largest.len() == LARGEST && self.largest[LARGEST - 1].1 >= size {
+            return;
+        }
+        let at = self.largest.partition_point(|(_, other)| *other >= size);
+        self.largest.insert(at, (path.clone(), size));
+        self.largest.truncate(LARGEST);
+    }
+}
+
+// bucket 0 holds the empty string, bucket i holds lengths 2^(i-1) ..= 2^i - 1
+fn bucket_index(length: usize) -> usize {
+    (usize::BITS - length.leading_zeros()) as usize
+}
+
+fn bucket_bounds(index: usize) -> (usize, usize) {
+    match index {
+        0 => (0, 0),
+        i => (1 << (i - 1), (1 << i) - 1),
+    }
+}
+
+fn string_bytes(string: &str) -> usize {
+    let mut counter = Counter(0);
+    let _ = write_string(&mut counter, string, &SerializeOptions::default());
+    counter.0
+}
+
+// counts bytes instead of keeping them
+struct Counter(usize);
+
+impl Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+// a plain-text report
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} values, {} bytes as compact JSON, max depth {}",
+            self.values(),
+            self.serialized_bytes,
+            self.max_depth()
+        )?;
+        writeln!(
+            f,
+            "types: {} objects, {} arrays, {} strings, {} numbers, {} booleans, {} nulls",
+            self.objects, self.arrays, self.strings, self.numbers, self.booleans, self.nulls
+        )?;
+        writeln!(f, "values per depth:")?;
+        for (depth, count) in self.depths.iter().enumerate() {
+            writeln!(f, "  {:>3}: {}", depth, count)?;
+        }
+        if !self.largest.is_empty() {
+            writeln!(f, "largest subtrees:")?;
+            for (path, size) in &self.largest {
+                writeln!(f, "  {:>8} bytes  {}", size, path)?;
+            }
+        }
+        if !self.keys.is_empty() {
+            writeln!(f, "most common keys:")?;
+            for (key, count) in self.keys.iter().take(LARGEST) {
+                writeln!(f, "  {:>8}  {}", count, key)?;
+            }
+        }
+        if !self.string_lengths.is_empty() {
+            writeln!(f, "string lengths:")?;
+            for bucket in &self.string_lengths {
+                writeln!(
+                    f,
+                    "  {:>5}..={:<5} {}",
+                    bucket.min, bucket.max, bucket.count
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
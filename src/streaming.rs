@@ -0,0 +1,375 @@
+use crate::compression::Decoder;
+use crate::sax::{ContentHandler, TreeBuilder};
+use crate::{Error, Number, Token, TokenType, Value, lexer};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+// how often cancellation is checked when no progress interval is set
+const CANCELLATION_CHECK_INTERVAL: u64 = 64 * 1024;
+
+pub fn parse_reader<R: BufRead>(reader: R) -> Result<Value, Error> {
+    feed_reader(reader, StreamingParser::new(TreeBuilder::new()))?
+        .into_value()
+        .ok_or_else(|| "Unexpected end of input".into())
+}
+
+// drives an already configured parser (progress, cancellation, custom handler) to the end of the reader;
+// gzip and zstd input is decompressed first when the matching feature is enabled
+pub fn feed_reader<R: BufRead, H: ContentHandler>(
+    reader: R,
+    mut parser: StreamingParser<H>,
+) -> Result<H, Error> {
+    let mut reader = Decoder::new(reader);
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok([]) => break, // end of input
+            Ok(chunk) => chunk,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        parser.feed(chunk)?;
+        let consumed = chunk.len();
+        reader.consume(consumed);
+    }
+    parser.finish()
+}
+
+pub fn parse_reader_with_buffer_size<R: Read>(
+    reader: R,
+    buffer_size: usize,
+) -> Result<Value, Error> {
+    parse_reader(BufReader::with_capacity(buffer_size, reader))
+}
+
+// a token whose bytes are split across feed() calls
+#[derive(Clone, Copy)]
+enum Partial {
+    String,
+    Number,
+    Keyword,
+}
+
+#[derive(Clone, Copy)]
+enum Container {
+    Object,
+    Array,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,        // top level or after ':'
+    FirstElement, // after '['
+    NextElement,  // after ',' in an array
+    FirstKey,     // after '{'
+    NextKey,      // after ',' in an object
+    Colon,
+    Separator, // after a value inside a container
+    Done,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub bytes: u64,
+    pub values: u64,
+}
+
+// shared flag; cancelling any clone aborts every parse holding the token
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type ProgressCallback = Box<dyn FnMut(Progress) + Send>;
+
+pub struct StreamingParser<H: ContentHandler> {
+    handler: H,
+    partial: Option<Partial>,
+    buffer: Vec<u8>,
+    stack: Vec<Container>,
+    expect: Expect,
+    error: Option<Error>,
+    progress: Progress,
+    on_progress: Option<(u64, ProgressCallback)>,
+    cancellation: Option<CancellationToken>,
+    next_checkpoint: u64,
+    raw_numbers: bool,
+}
+
+impl<H: ContentHandler> StreamingParser<H> {
+    pub fn new(handler: H) -> Self {
+        StreamingParser {
+            handler,
+            partial: None,
+            buffer: Vec::new(),
+            stack: Vec::new(),
+            expect: Expect::Value,
+            error: None,
+            progress: Progress {
+                bytes: 0,
+                values: 0,
+            },
+            on_progress: None,
+            cancellation: None,
+            next_checkpoint: CANCELLATION_CHECK_INTERVAL,
+            raw_numbers: false,
+        }
+    }
+
+    // calls `callback` every time another `every_bytes` bytes have been consumed
+    pub fn with_progress<F>(mut self, every_bytes: u64, callback: F) -> Self
+    where
+        F: FnMut(Progress) + Send + 'static,
+    {
+        let every_bytes = every_bytes.max(1);
+        self.next_checkpoint = self.progress.bytes + every_bytes;
+        self.on_progress = Some((every_bytes, Box::new(callback)));
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    // hand numbers to the handler as Number::Raw, with their source text
+    pub fn with_raw_numbers(mut self, raw_numbers: bool) -> Self {
+        self.raw_numbers = raw_numbers;
+        self
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        let result = self.check_cancelled().and_then(|()| {
+            bytes.iter().try_for_each(|&byte| {
+                self.feed_byte(byte)?;
+                self.progress.bytes += 1;
+                match self.progress.bytes >= self.next_checkpoint {
+                    true => self.checkpoint(),
+                    false => Ok(()),
+                }
+            })
+        });
+        if let Err(error) = &result {
+            self.error = Some(error.clone()); // the parser can't recover past an error
+        }
+        result
+    }
+
+    fn checkpoint(&mut self) -> Result<(), Error> {
+        let interval = match &mut self.on_progress {
+            Some((every_bytes, callback)) => {
+                callback(self.progress);
+                *every_bytes
+            }
+            None => CANCELLATION_CHECK_INTERVAL,
+        };
+        self.next_checkpoint += interval;
+        self.check_cancelled()
+    }
+
+    fn check_cancelled(&self) -> Result<(), Error> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    pub fn finish(mut self) -> Result<H, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.flush_partial()?;
+        if self.expect != Expect::Done {
+            return Err("Unexpected end of input".into());
+        }
+        Ok(self.handler)
+    }
+
+    fn feed_byte(&mut self, byte: u8) -> Result<(), Error> {
+        match self.partial {
+            Some(Partial::String) => {
+                if byte == b'"' {
+                    self.partial = None;
+                    let value = self.take_buffer()?;
+                    return self.accept(Token {
+                        token_type: TokenType::String,
+                        value,
+                    });
+                }
+                self.buffer.push(byte);
+                return Ok(());
+            }
+            Some(Partial::Number) if byte.is_ascii_digit() || byte == b'.' => {
+                self.buffer.push(byte);
+                return Ok(());
+            }
+            Some(Partial::Keyword) if byte.is_ascii_alphabetic() || !byte.is_ascii() => {
+                self.buffer.push(byte);
+                return Ok(());
+            }
+            Some(_) => self.flush_partial()?, // the byte ends the pending token
+            None => {}
+        }
+        match byte {
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                self.accept(lexer::simple_token(byte as char)?)
+            }
+            b'"' => {
+                self.partial = Some(Partial::String);
+                Ok(())
+            }
+            b'0'..=b'9' => {
+                self.partial = Some(Partial::Number);
+                self.buffer.push(byte);
+                Ok(())
+            }
+            b'a'..=b'z' | b'A'..=b'Z' => {
+                self.partial = Some(Partial::Keyword);
+                self.buffer.push(byte);
+                Ok(())
+            }
+            _ if byte.is_ascii() && (byte as char).is_whitespace() => Ok(()),
+            _ if byte.is_ascii() => Err(format!("Unexpected character: '{}'", byte as char).into()),
+            _ => Err(format!("Unexpected byte: 0x{:02X}", byte).into()),
+        }
+    }
+
+    fn flush_partial(&mut self) -> Result<(), Error> {
+        let token = match self.partial.take() {
+            None => return Ok(()),
+            Some(Partial::String) => return Err("Unexpected end of input".into()),
+            Some(Partial::Number) => Token {
+                token_type: TokenType::Number,
+                value: self.take_buffer()?,
+            },
+            Some(Partial::Keyword) => lexer::keyword_token(self.take_buffer()?)?,
+        };
+        self.accept(token)
+    }
+
+    fn take_buffer(&mut self) -> Result<String, Error> {
+        String::from_utf8(std::mem::take(&mut self.buffer))
+            .map_err(|_| "Invalid UTF-8 in input".into())
+    }
+
+    fn accept(&mut self, token: Token) -> Result<(), Error> {
+        match (self.expect, token.token_type) {
+            (Expect::Value, _) => self.accept_value(token),
+            (Expect::FirstElement, TokenType::CloseArray) => self.close(Container::Array),
+            (Expect::FirstElement, _) => self.accept_value(token),
+            (Expect::NextElement, TokenType::CloseArray) => Err("Trailing comma in array".into()),
+            (Expect::NextElement, _) => self.accept_value(token),
+            (Expect::FirstKey, TokenType::CloseObject) => self.close(Container::Object),
+            (Expect::NextKey, TokenType::CloseObject) => Err("Trailing comma in object".into()),
+            (Expect::FirstKey | Expect::NextKey, TokenType::String) => {
+                self.handler.on_key(&token.value);
+                self.expect = Expect::Colon;
+                Ok(())
+            }
+            (Expect::FirstKey | Expect::NextKey, _) => Err("Expected string".into()),
+            (Expect::Colon, TokenType::Colon) => {
+                self.expect = Expect::Value;
+                Ok(())
+            }
+            (Expect::Colon, _) => {
+                Err(format!("Expected {:?}, found unexpected token", TokenType::Colon).into())
+            }
+            (Expect::Separator, token_type) => self.accept_separator(token_type),
+            (Expect::Done, _) => {
+                Err(format!("Unexpected trailing token: '{}'", token.value).into())
+            }
+        }
+    }
+
+    fn accept_value(&mut self, token: Token) -> Result<(), Error> {
+        match token.token_type {
+            TokenType::OpenObject => {
+                self.handler.on_object_start();
+                self.stack.push(Container::Object);
+                self.expect = Expect::FirstKey;
+                return Ok(());
+            }
+            TokenType::OpenArray => {
+                self.handler.on_array_start();
+                self.stack.push(Container::Array);
+                self.expect = Expect::FirstElement;
+                return Ok(());
+            }
+            TokenType::True => self.handler.on_bool(true),
+            TokenType::False => self.handler.on_bool(false),
+            TokenType::Null => self.handler.on_null(),
+            TokenType::Number if self.raw_numbers => {
+                self.handler.on_number(Number::from_raw(&token.value)?);
+            }
+            TokenType::Number => {
+                let number = token.value.parse::<Number>()?;
+                self.handler.on_number(number);
+            }
+            TokenType::String => self.handler.on_string(&token.value),
+            _ => return Err("Invalid JSON token".into()),
+        }
+        self.end_value();
+        Ok(())
+    }
+
+    fn accept_separator(&mut self, token_type: TokenType) -> Result<(), Error> {
+        match (self.stack.last(), token_type) {
+            (Some(Container::Object), TokenType::Comma) => self.expect = Expect::NextKey,
+            (Some(Container::Object), TokenType::CloseObject) => {
+                return self.close(Container::Object);
+            }
+            (Some(Container::Object), _) => {
+                return Err("Expected ',' or '}' in object".into());
+            }
+            (Some(Container::Array), TokenType::Comma) => self.expect = Expect::NextElement,
+            (Some(Container::Array), TokenType::CloseArray) => {
+                return self.close(Container::Array);
+            }
+            (Some(Container::Array), _) => {
+                return Err("Expected ',' or ']' in array".into());
+            }
+            (None, _) => unreachable!("separators are only expected inside containers"),
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, container: Container) -> Result<(), Error> {
+        self.stack.pop();
+        match container {
+            Container::Object => self.handler.on_object_end(),
+            Container::Array => self.handler.on_array_end(),
+        }
+        self.end_value();
+        Ok(())
+    }
+
+    fn end_value(&mut self) {
+        self.progress.values += 1;
+        self.expect = if self.stack.is_empty() {
+            Expect::Done
+        } else {
+            Expect::Separator
+        };
+    }
+}
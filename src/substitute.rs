@@ -0,0 +1,213 @@
+// expanding ${...} placeholders inside string values after parsing: "${db.host}" refers to
+// another value in the same document, "${HOME}" to a variable or the environment;
+// "$${" stands for a literal "${"
+use crate::path::{JsonPath, PathSegment};
+use crate::{Error, Value};
+use std::collections::HashMap;
+
+// what to do with a placeholder that names nothing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unresolved {
+    Error,
+    Keep,  // leave the "${NAME}" text as it is
+    Empty, // replace it with ""
+}
+
+#[derive(Debug, Clone)]
+pub struct SubstituteOptions {
+    variables: HashMap<String, String>,
+    environment: bool,
+    unresolved: Unresolved,
+}
+
+impl Default for SubstituteOptions {
+    fn default() -> Self {
+        SubstituteOptions {
+            variables: HashMap::new(),
+            environment: true,
+            unresolved: Unresolved::Error,
+        }
+    }
+}
+
+impl SubstituteOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // checked before the process environment
+    pub fn with_variable(mut self, name: &str, value: &str) -> Self {
+        self.variables.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_environment(mut self, environment: bool) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    pub fn with_unresolved(mut self, unresolved: Unresolved) -> Self {
+        self.unresolved = unresolved;
+        self
+    }
+}
+
+impl Value {
+    pub fn substitute(&self) -> Result<Value, Error> {
+        self.substitute_with(&SubstituteOptions::default())
+    }
+
+    // a name is looked up as a path in this document first (placeholders in the value
+    // found there are expanded too), then among the variables, then in the environment.
+    // A string that is nothing but one placeholder for a document value takes that value
+    // with its type, so "${defaults.port}" can become the number 8080; elsewhere values
+    // are spliced in as text, and non-string values as compact JSON.
+    pub fn substitute_with(&self, options: &SubstituteOptions) -> Result<Value, Error> {
+        let mut substituter = Substituter {
+            root: self,
+            options,
+            active: Vec::new(),
+        };
+        substituter.value(self, &mut JsonPath::root())
+    }
+}
+
+struct Substituter<'a> {
+    root: &'a Value,
+    options: &'a SubstituteOptions,
+    active: Vec<String>, // document paths being expanded right now, to catch cycles
+}
+
+enum Piece<'t> {
+    Text(&'t str),
+    Placeholder(&'t str),
+}
+
+impl Substituter<'_> {
+    fn value(&mut self, node: &Value, path: &mut JsonPath) -> Result<Value, Error> {
+        match node {
+            Value::Object(properties) => {
+                let mut substituted = Value::object();
+                for (key, child) in properties {
+                    path.push(PathSegment::Key(key.clone()));
+                    substituted[key.as_str()] = self.value(child, path)?;
+                    path.pop();
+                }
+                Ok(substituted)
+            }
+            Value::Array(elements) => {
+                let mut substituted = Vec::with_capacity(elements.len());
+                for (i, child) in elements.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    substituted.push(self.value(child, path)?);
+                    path.pop();
+                }
+                Ok(Value::Array(substituted))
+            }
+            Value::String(text) => self.string(text, path),
+            value => Ok(value.clone()),
+        }
+    }
+
+    fn string(&mut self, text: &str, path: &mut JsonPath) -> Result<Value, Error> {
+        let pieces = split(text).map_err(|reason| failed(path, reason))?;
+        if let [Piece::Placeholder(name)] = pieces.as_slice()
+            && let Some(value) = self.document_value(name, path)?
+        {
+            return Ok(value);
+        }
+        let mut expanded = String::new();
+        for piece in pieces {
+            match piece {
+                Piece::Text(text) => expanded.push_str(text),
+                Piece::Placeholder(name) => match self.lookup(name, path)? {
+                    Some(Value::String(text)) => expanded.push_str(&text),
+                    Some(value) => expanded.push_str(&value.to_string()),
+                    None => match self.options.unresolved {
+                        Unresolved::Error => {
+                            return Err(failed(
+                                path,
+                                format!("unresolved placeholder '${{{}}}'", name),
+                            ));
+                        }
+                        Unresolved::Keep => {
+                            expanded.push_str("${");
+                            expanded.push_str(name);
+                            expanded.push('}');
+                        }
+                        Unresolved::Empty => {}
+                    },
+                },
+            }
+        }
+        Ok(Value::String(expanded))
+    }
+
+    fn lookup(&mut self, name: &str, path: &mut JsonPath) -> Result<Option<Value>, Error> {
+        if let Some(value) = self.document_value(name, path)? {
+            return Ok(Some(value));
+        }
+        if let Some(value) = self.options.variables.get(name) {
+            return Ok(Some(Value::String(value.clone())));
+        }
+        if self.options.environment
+            && let Ok(value) = std::env::var(name)
+        {
+            return Ok(Some(Value::String(value)));
+        }
+        Ok(None)
+    }
+
+    fn document_value(&mut self, name: &str, path: &mut JsonPath) -> Result<Option<Value>, Error> {
+        let Some(target) = self.root.get_path(name) else {
+            return Ok(None);
+        };
+        if self.active.iter().any(|active| active == name) {
+            let mut chain = self.active.clone();
+            chain.push(name.to_string());
+            return Err(failed(
+                path,
+                format!("circular placeholders: {}", chain.join(" -> ")),
+            ));
+        }
+        self.active.push(name.to_string());
+        let mut target_path = JsonPath::parse(name)?;
+        let value = self.value(target, &mut target_path);
+        self.active.pop();
+        value.map(Some)
+    }
+}
+
+// "$${" is an escaped "${"; a "$" anywhere else is just a character
+fn split(text: &str) -> Result<Vec<Piece<'_>>, String> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if start > 0 && rest[..start].ends_with('$') {
+            pieces.push(Piece::Text(&rest[..start - 1]));
+            pieces.push(Piece::Text("${"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        pieces.push(Piece::Text(&rest[..start]));
+        let inner = &rest[start + 2..];
+        let end = inner
+            .find('}')
+            .ok_or_else(|| format!("unclosed placeholder in \"{}\"", text))?;
+        if end == 0 {
+            return Err(format!("empty placeholder in \"{}\"", text));
+        }
+        pieces.push(Piece::Placeholder(&inner[..end]));
+        rest = &inner[end + 1..];
+    }
+    pieces.push(Piece::Text(rest));
+    pieces.retain(|piece| !matches!(piece, Piece::Text("")));
+    Ok(pieces)
+}
+
+fn failed(path: &JsonPath, reason: String) -> Error {
+    match path.segments() {
+        [] => Error::Conversion(reason),
+        _ => Error::Conversion(format!("{} at {}", reason, path)),
+    }
+}
@@ -0,0 +1,203 @@
+// two-stage parsing: stage 1 indexes every structural byte, stage 2 walks the index
+use crate::{Error, Map, Number, TokenType, Value, lexer};
+
+pub struct StructuralIndex {
+    positions: Vec<usize>,
+}
+
+impl StructuralIndex {
+    pub fn build(input: &str) -> Result<Self, Error> {
+        let bytes = input.as_bytes();
+        let mut positions = Vec::with_capacity(bytes.len() / 4);
+        let mut in_string = false;
+        for (i, &byte) in bytes.iter().enumerate() {
+            if in_string {
+                if byte == b'"' {
+                    positions.push(i); // closing quote
+                    in_string = false;
+                }
+                continue;
+            }
+            match byte {
+                b'{' | b'}' | b'[' | b']' | b':' | b',' => positions.push(i),
+                b'"' => {
+                    positions.push(i); // opening quote
+                    in_string = true;
+                }
+                _ => {}
+            }
+        }
+        if in_string {
+            return Err("Unexpected end of input".into());
+        }
+        Ok(StructuralIndex { positions })
+    }
+
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+pub fn parse_str_fast(input: &str) -> Result<Value, Error> {
+    let index = StructuralIndex::build(input)?;
+    let mut walker = Walker::new(input, index.positions(), 0, 0);
+    let value = walker.parse()?;
+    if walker.next < walker.positions.len() || !is_blank(walker.gap()) {
+        return Err("Unexpected trailing token".into());
+    }
+    Ok(value)
+}
+
+#[derive(Clone)]
+pub(crate) struct Walker<'a, 'p> {
+    input: &'a str,
+    positions: &'p [usize], // may live shorter than the input: slices borrow only from `input`
+    pub(crate) next: usize, // index of the next unconsumed structural
+    pub(crate) offset: usize, // byte offset just past the last consumed structural
+}
+
+impl<'a, 'p> Walker<'a, 'p> {
+    pub(crate) fn new(input: &'a str, positions: &'p [usize], next: usize, offset: usize) -> Self {
+        Walker {
+            input,
+            positions,
+            next,
+            offset,
+        }
+    }
+
+    // the text between the last consumed structural and the next one
+    pub(crate) fn gap(&self) -> &'a str {
+        let end = self
+            .positions
+            .get(self.next)
+            .copied()
+            .unwrap_or(self.input.len());
+        &self.input[self.offset..end]
+    }
+
+    // the next structural byte, provided only whitespace precedes it
+    pub(crate) fn peek(&self) -> Option<u8> {
+        let position = *self.positions.get(self.next)?;
+        is_blank(self.gap()).then(|| self.input.as_bytes()[position])
+    }
+
+    pub(crate) fn bump(&mut self) -> usize {
+        let position = self.positions[self.next];
+        self.next += 1;
+        self.offset = position + 1;
+        position
+    }
+
+    pub(crate) fn parse(&mut self) -> Result<Value, Error> {
+        let gap = self.gap();
+        let scalar = gap.trim();
+        if !scalar.is_empty() {
+            self.offset += gap.len();
+            return parse_scalar(scalar);
+        }
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => Ok(Value::String(self.parse_string())),
+            Some(_) => Err("Invalid JSON token".into()),
+            None => Err("Unexpected end of input".into()),
+        }
+    }
+
+    fn parse_string(&mut self) -> String {
+        self.string_slice().to_string()
+    }
+
+    pub(crate) fn string_slice(&mut self) -> &'a str {
+        let open = self.bump();
+        let close = self.bump(); // stage 1 guarantees quotes come in pairs
+        &self.input[open + 1..close]
+    }
+
+    fn parse_object(&mut self) -> Result<Value, Error> {
+        self.bump(); // consume '{'
+        let mut properties = Map::new();
+        if self.peek() == Some(b'}') {
+            self.bump();
+            return Ok(Value::Object(properties));
+        }
+        loop {
+            // resolve "key": value
+            if self.peek() != Some(b'"') {
+                return Err("Expected string".into());
+            }
+            let key = self.parse_string();
+            if self.peek() != Some(b':') {
+                return Err(
+                    format!("Expected {:?}, found unexpected token", TokenType::Colon).into(),
+                );
+            }
+            self.bump();
+            let value = self.parse()?;
+            properties.insert(key, value);
+
+            // check separator
+            match self.peek() {
+                Some(b',') => {
+                    self.bump();
+                    if self.peek() == Some(b'}') {
+                        return Err("Trailing comma in object".into());
+                    }
+                }
+                Some(b'}') => {
+                    self.bump();
+                    return Ok(Value::Object(properties));
+                }
+                _ => return Err("Expected ',' or '}' in object".into()),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, Error> {
+        self.bump(); // consume '['
+        let mut elements = Vec::new();
+        if self.peek() == Some(b']') {
+            self.bump();
+            return Ok(Value::Array(elements));
+        }
+        loop {
+            elements.push(self.parse()?);
+            // handle separator
+            match self.peek() {
+                Some(b',') => {
+                    self.bump();
+                    if self.peek() == Some(b']') {
+                        return Err("Trailing comma in array".into());
+                    }
+                }
+                Some(b']') => {
+                    self.bump();
+                    return Ok(Value::Array(elements));
+                }
+                _ => return Err("Expected ',' or ']' in array".into()),
+            }
+        }
+    }
+}
+
+pub(crate) fn is_blank(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+// numbers and keywords are the only values stage 1 doesn't index
+pub(crate) fn parse_scalar(text: &str) -> Result<Value, Error> {
+    let first = text.as_bytes()[0];
+    if first.is_ascii_digit() && text.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+        let number = text.parse::<Number>()?;
+        return Ok(Value::Number(number));
+    }
+    if first.is_ascii_alphabetic() && text.chars().all(char::is_alphabetic) {
+        return match lexer::keyword_token(text.to_string())?.token_type {
+            TokenType::True => Ok(Value::Bool(true)),
+            TokenType::False => Ok(Value::Bool(false)),
+            _ => Ok(Value::Null),
+        };
+    }
+    Err(format!("Unexpected token: '{}'", text).into())
+}
@@ -0,0 +1,747 @@
+// TOML conversion for Values that fit TOML's model: the document has to be an object,
+// integers have to fit i64, and null has no TOML form, so TomlOptions says what to do
+// with it. Dates and times read from TOML become strings, since JSON has no such type.
+use crate::path::{JsonPath, PathSegment};
+use crate::serializer::{SerializeOptions, write_f64};
+use crate::{Error, Map, Number, Value};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullPolicy {
+    #[default]
+    Error, // fail with Error::Conversion naming where the null is
+    Skip,        // leave out members and elements that are null
+    EmptyString, // write null as ""
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TomlOptions {
+    null_policy: NullPolicy,
+}
+
+impl TomlOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_null_policy(mut self, null_policy: NullPolicy) -> Self {
+        self.null_policy = null_policy;
+        self
+    }
+}
+
+pub fn to_toml_string(value: &Value) -> Result<String, Error> {
+    to_toml_string_with(value, &TomlOptions::default())
+}
+
+// members holding objects become [tables] and arrays of objects [[arrays of tables]],
+// after the table's plain key/value pairs; everything deeper inside an array is inline
+pub fn to_toml_string_with(value: &Value, options: &TomlOptions) -> Result<String, Error> {
+    let mut writer = Writer {
+        out: String::new(),
+        options,
+        path: JsonPath::root(),
+    };
+    match value {
+        Value::Object(properties) => writer.table(properties, &mut Vec::new())?,
+        Value::Raw(raw) => return to_toml_string_with(&raw.parse()?, options),
+        other => {
+            return Err(Error::Conversion(format!(
+                "a TOML document must be an object, not {}",
+                other.type_name()
+            )));
+        }
+    }
+    Ok(writer.out)
+}
+
+pub fn from_toml_str(input: &str) -> Result<Value, Error> {
+    let mut parser = Parser {
+        text: input.strip_prefix('\u{feff}').unwrap_or(input),
+        position: 0,
+    };
+    parser.document()
+}
+
+struct Writer<'o> {
+    out: String,
+    options: &'o TomlOptions,
+    path: JsonPath, // to say where an unwritable value is
+}
+
+// an object member that gets its own [header] rather than a key = value line
+enum Section<'v> {
+    Table(&'v Map),
+    Tables(&'v [Value]),
+}
+
+fn section(value: &Value) -> Option<Section<'_>> {
+    match value {
+        Value::Object(properties) => Some(Section::Table(properties)),
+        Value::Array(elements) if !elements.is_empty() && elements.iter().all(Value::is_object) => {
+            Some(Section::Tables(elements))
+        }
+        _ => None,
+    }
+}
+
+impl Writer<'_> {
+    fn error(&self, message: &str) -> Error {
+        match self.path.segments().is_empty() {
+            true => Error::Conversion(format!("$: {}", message)),
+            false => Error::Conversion(format!("{}: {}", self.path, message)),
+        }
+    }
+
+    fn skips(&self, value: &Value) -> bool {
+        value.is_null() && self.options.null_policy == NullPolicy::Skip
+    }
+
+    fn table(&mut self, properties: &Map, keys: &mut Vec<String>) -> Result<(), Error> {
+        for (key, value) in properties {
+            if section(value).is_some() || self.skips(value) {
+                continue;
+            }
+            self.path.push(PathSegment::from(key.as_str()));
+            write_key(&mut self.out, key);
+            self.out.push_str(" = ");
+            self.inline(value)?;
+            self.out.push('\n');
+            self.path.pop();
+        }
+        for (key, value) in properties {
+            let Some(kind) = section(value) else {
+                continue;
+            };
+            keys.push(key.clone());
+            self.path.push(PathSegment::from(key.as_str()));
+            match kind {
+                Section::Table(table) => {
+                    // a table holding only other tables needs no header of its own
+                    let only_sections = !table.is_empty()
+                        && table
+                            .values()
+                            .all(|v| section(v).is_some() || self.skips(v));
+                    if !only_sections {
+                        self.header(keys, "[", "]");
+                    }
+                    self.table(table, keys)?;
+                }
+                Section::Tables(elements) => {
+                    for (index, element) in elements.iter().enumerate() {
+                        self.path.push(PathSegment::from(index));
+                        self.header(keys, "[[", "]]");
+                        if let Value::Object(table) = element {
+                            self.table(table, keys)?;
+                        }
+                        self.path.pop();
+                    }
+                }
+            }
+            self.path.pop();
+            keys.pop();
+        }
+        Ok(())
+    }
+
+    fn header(&mut self, keys: &[String], open: &str, close: &str) {
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out.push_str(open);
+        for (i, key) in keys.iter().enumerate() {
+            if i > 0 {
+                self.out.push('.');
+            }
+            write_key(&mut self.out, key);
+        }
+        self.out.push_str(close);
+        self.out.push('\n');
+    }
+
+    fn inline(&mut self, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => match self.options.null_policy {
+                NullPolicy::Error => return Err(self.error("null can't be written as TOML")),
+                _ => self.out.push_str("\"\""),
+            },
+            Value::Bool(b) => self.out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(number) => self.number(number)?,
+            Value::String(s) => write_basic_string(&mut self.out, s),
+            Value::Array(elements) => {
+                self.out.push('[');
+                let mut first = true;
+                for (index, element) in elements.iter().enumerate() {
+                    if self.skips(element) {
+                        continue;
+                    }
+                    if !first {
+                        self.out.push_str(", ");
+                    }
+                    first = false;
+                    self.path.push(PathSegment::from(index));
+                    self.inline(element)?;
+                    self.path.pop();
+                }
+                self.out.push(']');
+            }
+            Value::Object(properties) => {
+                self.out.push('{');
+                let mut first = true;
+                for (key, value) in properties {
+                    if self.skips(value) {
+                        continue;
+                    }
+                    self.out.push_str(if first { " " } else { ", " });
+                    first = false;
+                    self.path.push(PathSegment::from(key.as_str()));
+                    write_key(&mut self.out, key);
+                    self.out.push_str(" = ");
+                    self.inline(value)?;
+                    self.path.pop();
+                }
+                self.out.push_str(if first { "}" } else { " }" });
+            }
+            Value::Raw(raw) => self.inline(&raw.parse()?)?,
+        }
+        Ok(())
+    }
+
+    // TOML integers are i64; floats always carry a point or an exponent, so 1.0 stays
+    // a float. Raw and arbitrary precision numbers go by their text.
+    fn number(&mut self, number: &Number) -> Result<(), Error> {
+        let number = match number {
+            Number::I64(_) | Number::U64(_) | Number::F64(_) => number.clone(),
+            other => other
+                .to_string()
+                .parse()
+                .unwrap_or(Number::F64(other.as_f64())),
+        };
+        match number {
+            Number::I64(n) => {
+                let _ = write!(self.out, "{}", n);
+            }
+            Number::U64(n) => match i64::try_from(n) {
+                Ok(n) => {
+                    let _ = write!(self.out, "{}", n);
+                }
+                Err(_) => return Err(self.error("integer too large for TOML")),
+            },
+            number => match number.as_f64() {
+                n if n.is_nan() => self.out.push_str("nan"),
+                n if n.is_infinite() => self.out.push_str(if n > 0.0 { "inf" } else { "-inf" }),
+                n => {
+                    let options = SerializeOptions::new().with_float_point(true);
+                    let _ = write_f64(&mut self.out, n, &options);
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+fn write_key(out: &mut String, key: &str) {
+    let bare = !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+    if bare {
+        out.push_str(key);
+    } else {
+        write_basic_string(out, key);
+    }
+}
+
+fn write_basic_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            c if c < ' ' || c == '\u{7f}' => {
+                let _ = write!(out, "\\u{:04X}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        let line = self.text[..self.position].matches('\n').count() + 1;
+        Error::Line(line, Box::new(message.into()))
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char, message: &str) -> Result<(), Error> {
+        match self.eat(c.encode_utf8(&mut [0; 4])) {
+            true => Ok(()),
+            false => Err(self.error(message)),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.bump();
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while self.peek().is_some_and(|c| c != '\n') {
+                self.bump();
+            }
+        }
+    }
+
+    // spaces, comments and line breaks, as between array elements
+    fn skip_blank(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            if !self.eat("\n") && !self.eat("\r\n") {
+                return;
+            }
+        }
+    }
+
+    // after a header or key/value pair only a comment may follow on the line
+    fn end_of_line(&mut self) -> Result<(), Error> {
+        self.skip_spaces();
+        self.skip_comment();
+        if self.peek().is_none() || self.eat("\n") || self.eat("\r\n") {
+            return Ok(());
+        }
+        Err(self.error("expected the end of the line"))
+    }
+
+    fn document(&mut self) -> Result<Value, Error> {
+        let mut root = Map::new();
+        let mut current: Vec<String> = Vec::new();
+        let mut defined = HashSet::new(); // tables given a [header], by resolved path
+        loop {
+            self.skip_blank();
+            if self.peek().is_none() {
+                return Ok(Value::Object(root));
+            }
+            if self.eat("[[") {
+                let keys = self.keys()?;
+                self.expect(']', "expected ']]' after the table name")?;
+                self.expect(']', "expected ']]' after the table name")?;
+                let (last, parents) = keys.split_last().expect("keys are never empty");
+                let (parent, _) = table_at(&mut root, parents).map_err(|e| self.error(&e))?;
+                match parent.get_or_insert_mut(last.clone(), Value::Array(Vec::new())) {
+                    Value::Array(tables) if tables.iter().all(Value::is_object) => {
+                        tables.push(Value::Object(Map::new()));
+                    }
+                    _ => return Err(self.error(&format!("'{}' is not an array of tables", last))),
+                }
+                current = keys;
+            } else if self.eat("[") {
+                let keys = self.keys()?;
+                self.expect(']', "expected ']' after the table name")?;
+                let (_, id) = table_at(&mut root, &keys).map_err(|e| self.error(&e))?;
+                if !defined.insert(id) {
+                    return Err(self.error(&format!("table [{}] is defined twice", keys.join("."))));
+                }
+                current = keys;
+            } else {
+                let keys = self.keys()?;
+                self.skip_spaces();
+                self.expect('=', "expected '=' after the key")?;
+                self.skip_spaces();
+                let value = self.value()?;
+                let (table, _) = table_at(&mut root, &current).map_err(|e| self.error(&e))?;
+                insert(table, &keys, value).map_err(|e| self.error(&e))?;
+            }
+            self.end_of_line()?;
+        }
+    }
+
+    // a dotted key: bare, "basic" or 'literal' parts separated by '.'
+    fn keys(&mut self) -> Result<Vec<String>, Error> {
+        let mut keys = Vec::new();
+        loop {
+            self.skip_spaces();
+            let key = match self.peek() {
+                Some('"') => {
+                    self.bump();
+                    self.basic_string()?
+                }
+                Some('\'') => {
+                    self.bump();
+                    self.literal_string()?
+                }
+                _ => {
+                    let start = self.position;
+                    while self
+                        .peek()
+                        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    {
+                        self.bump();
+                    }
+                    if self.position == start {
+                        return Err(self.error("expected a key"));
+                    }
+                    self.text[start..self.position].to_string()
+                }
+            };
+            keys.push(key);
+            self.skip_spaces();
+            if !self.eat(".") {
+                return Ok(keys);
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        match self.peek() {
+            Some('"') if self.eat("\"\"\"") => self.multiline_string(true).map(Value::String),
+            Some('\'') if self.eat("'''") => self.multiline_string(false).map(Value::String),
+            Some('"') => {
+                self.bump();
+                self.basic_string().map(Value::String)
+            }
+            Some('\'') => {
+                self.bump();
+                self.literal_string().map(Value::String)
+            }
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            Some(_) if self.eat("true") => Ok(Value::Bool(true)),
+            Some(_) if self.eat("false") => Ok(Value::Bool(false)),
+            Some(_) => self.number_or_datetime(),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.bump(); // consume '['
+        let mut elements = Vec::new();
+        loop {
+            self.skip_blank();
+            if self.eat("]") {
+                return Ok(Value::Array(elements));
+            }
+            elements.push(self.value()?);
+            self.skip_blank();
+            if self.eat("]") {
+                return Ok(Value::Array(elements));
+            }
+            self.expect(',', "expected ',' or ']' in an array")?;
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<Value, Error> {
+        self.bump(); // consume '{'
+        let mut properties = Map::new();
+        self.skip_spaces();
+        if self.eat("}") {
+            return Ok(Value::Object(properties));
+        }
+        loop {
+            let keys = self.keys()?;
+            self.expect('=', "expected '=' after the key")?;
+            self.skip_spaces();
+            let value = self.value()?;
+            insert(&mut properties, &keys, value).map_err(|e| self.error(&e))?;
+            self.skip_spaces();
+            if self.eat("}") {
+                return Ok(Value::Object(properties));
+            }
+            self.expect(',', "expected ',' or '}' in an inline table")?;
+        }
+    }
+
+    // positioned after the opening quote
+    fn basic_string(&mut self) -> Result<String, Error> {
+        let mut out = String::new();
+        loop {
+            let c = match self.peek() {
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(c) if c.is_control() && c != '\t' => {
+                    return Err(self.error("control characters must be escaped"));
+                }
+                Some(c) => c,
+            };
+            self.bump();
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let c = self.escape()?;
+                    out.push(c);
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, Error> {
+        let start = self.position;
+        loop {
+            match self.peek() {
+                Some('\'') => {
+                    self.bump();
+                    return Ok(self.text[start..self.position - 1].to_string());
+                }
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    // """...""" or '''...''', positioned after the opening quotes; a line break right
+    // after them is dropped, and in basic ones a '\' at the end of a line joins it to
+    // the next non-blank text
+    fn multiline_string(&mut self, basic: bool) -> Result<String, Error> {
+        let quote = if basic { '"' } else { '\'' };
+        let delimiter = if basic { "\"\"\"" } else { "'''" };
+        if !self.eat("\n") {
+            self.eat("\r\n");
+        }
+        let mut out = String::new();
+        loop {
+            if self.rest().starts_with(delimiter) {
+                // up to two more quotes may sit right before the closing ones
+                let quotes = self
+                    .rest()
+                    .chars()
+                    .take_while(|&c| c == quote)
+                    .count()
+                    .min(5);
+                out.extend(std::iter::repeat_n(quote, quotes - 3));
+                self.position += quotes;
+                return Ok(out);
+            }
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some('\\') if basic => {
+                    let rest = self.rest();
+                    let line = rest.split('\n').next().unwrap_or(rest);
+                    if line.trim().is_empty() && rest.contains('\n') {
+                        while self.peek().is_some_and(char::is_whitespace) {
+                            self.bump();
+                        }
+                    } else {
+                        let c = self.escape()?;
+                        out.push(c);
+                    }
+                }
+                Some(c) if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {
+                    return Err(self.error("control characters must be escaped"));
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    // positioned after the '\'
+    fn escape(&mut self) -> Result<char, Error> {
+        let digits = match self.bump() {
+            Some('b') => return Ok('\u{08}'),
+            Some('t') => return Ok('\t'),
+            Some('n') => return Ok('\n'),
+            Some('f') => return Ok('\u{0C}'),
+            Some('r') => return Ok('\r'),
+            Some('e') => return Ok('\u{1B}'),
+            Some('"') => return Ok('"'),
+            Some('\\') => return Ok('\\'),
+            Some('u') => 4,
+            Some('U') => 8,
+            _ => return Err(self.error("invalid escape")),
+        };
+        let hex = self
+            .rest()
+            .get(..digits)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.position += digits;
+        Ok(hex)
+    }
+
+    fn number_or_datetime(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || "+-_.:".contains(c))
+        {
+            self.bump();
+        }
+        // a date and a time may be separated by a space instead of 'T'
+        if is_date(&self.text[start..self.position])
+            && self.rest().starts_with(' ')
+            && self.rest()[1..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            self.bump();
+            while self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || "+-.:".contains(c))
+            {
+                self.bump();
+            }
+        }
+        let token = &self.text[start..self.position];
+        if is_date(token) || is_time(token) {
+            return Ok(Value::String(token.to_string()));
+        }
+        match parse_number(token) {
+            Some(number) => Ok(Value::Number(number)),
+            None if token.is_empty() => Err(self.error("expected a value")),
+            None => Err(self.error(&format!("invalid value '{}'", token))),
+        }
+    }
+}
+
+// 1979-05-27, possibly followed by a time
+fn is_date(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    bytes.len() >= 10
+        && bytes[..4].iter().all(u8::is_ascii_digit)
+        && bytes[4] == b'-'
+        && bytes[5..7].iter().all(u8::is_ascii_digit)
+        && bytes[7] == b'-'
+        && bytes[8..10].iter().all(u8::is_ascii_digit)
+}
+
+// 07:32:00, a local time
+fn is_time(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    bytes.len() >= 8
+        && bytes[..2].iter().all(u8::is_ascii_digit)
+        && bytes[2] == b':'
+        && bytes[3..5].iter().all(u8::is_ascii_digit)
+        && bytes[5] == b':'
+}
+
+fn parse_number(token: &str) -> Option<Number> {
+    let unsigned = token.strip_prefix(['+', '-']).unwrap_or(token);
+    let negative = token.starts_with('-');
+    match unsigned {
+        "inf" => {
+            return Some(Number::F64(if negative {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            }));
+        }
+        "nan" => return Some(Number::F64(f64::NAN)),
+        _ => {}
+    }
+    // underscores only between digits
+    let valid_underscores = !unsigned.starts_with('_')
+        && !unsigned.ends_with('_')
+        && !unsigned.contains("__")
+        && unsigned.match_indices('_').all(|(i, _)| {
+            let around = [unsigned.as_bytes()[i - 1], unsigned.as_bytes()[i + 1]];
+            around.iter().all(u8::is_ascii_hexdigit)
+        });
+    if !valid_underscores {
+        return None;
+    }
+    let digits = unsigned.replace('_', "");
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(digits) = digits.strip_prefix(prefix) {
+            if token != unsigned {
+                return None; // no sign on these
+            }
+            return i64::from_str_radix(digits, radix).ok().map(Number::I64);
+        }
+    }
+    if digits.len() > 1 && digits.starts_with('0') && !digits[1..].starts_with(['.', 'e', 'E']) {
+        return None; // no leading zeros
+    }
+    let is_float = digits.contains(['.', 'e', 'E']);
+    if is_float {
+        // a point needs digits on both sides
+        let mantissa = digits.split(['e', 'E']).next().unwrap_or("");
+        let whole_and_fraction = mantissa
+            .split('.')
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+        if !whole_and_fraction {
+            return None;
+        }
+    } else if !digits.bytes().all(|b| b.is_ascii_digit()) || digits.is_empty() {
+        return None;
+    }
+    let text = format!("{}{}", if negative { "-" } else { "" }, digits);
+    match text.parse::<Number>().ok()? {
+        Number::U64(n) => i64::try_from(n).ok().map(Number::I64),
+        number => Some(number),
+    }
+}
+
+// the table a [header] names, made on the way if needed; the id tells tables inside
+// different elements of an array of tables apart
+fn table_at<'m>(root: &'m mut Map, keys: &[String]) -> Result<(&'m mut Map, String), String> {
+    let mut table = root;
+    let mut id = String::new();
+    for key in keys {
+        id.push('.');
+        id.push_str(key);
+        table = match table.get_or_insert_mut(key.clone(), Value::Object(Map::new())) {
+            Value::Object(properties) => properties,
+            Value::Array(tables) if tables.last().is_some_and(Value::is_object) => {
+                let _ = write!(id, "[{}]", tables.len() - 1);
+                match tables.last_mut() {
+                    Some(Value::Object(properties)) => properties,
+                    _ => unreachable!(),
+                }
+            }
+            _ => return Err(format!("'{}' already holds a value, not a table", key)),
+        };
+    }
+    Ok((table, id))
+}
+
+// key.sub = value: the dotted parts make tables on the way
+fn insert(table: &mut Map, keys: &[String], value: Value) -> Result<(), String> {
+    let (last, parents) = keys.split_last().expect("keys are never empty");
+    let mut table = table;
+    for key in parents {
+        table = match table.get_or_insert_mut(key.clone(), Value::Object(Map::new())) {
+            Value::Object(properties) => properties,
+            _ => return Err(format!("'{}' already holds a value, not a table", key)),
+        };
+    }
+    if table.contains_key(last) {
+        return Err(format!("duplicate key '{}'", last));
+    }
+    table.insert(last.clone(), value);
+    Ok(())
+}
@@ -0,0 +1,707 @@
+use crate::{Error, Map, Number, RawValue};
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Value {
+    Object(Map),
+    Array(Array),
+    String(String),
+    Number(Number),
+    Bool(bool),
+    #[default]
+    Null,
+    Raw(RawValue), // already-serialized JSON, written out verbatim
+}
+
+pub type Array = Vec<Value>;
+
+impl Value {
+    pub fn object() -> Value {
+        Value::Object(Map::new())
+    }
+
+    pub fn array() -> Value {
+        Value::Array(Array::new())
+    }
+
+    pub fn get<I: JsonIndex>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut<I: JsonIndex>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    // a key sets an object property and returns the previous value; a position shifts
+    // array elements right like Vec::insert. Null turns into the matching container first
+    pub fn insert<I: JsonIndex, V: Into<Value>>(
+        &mut self,
+        index: I,
+        value: V,
+    ) -> Result<Option<Value>, Error> {
+        index.insert_into(self, value.into())
+    }
+
+    pub fn remove<I: JsonIndex>(&mut self, index: I) -> Option<Value> {
+        index.remove_from(self)
+    }
+
+    pub fn push<V: Into<Value>>(&mut self, value: V) -> Result<(), Error> {
+        if self.is_null() {
+            *self = Value::array();
+        }
+        self.expect_array_mut()?.push(value.into());
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<Value> {
+        self.as_array_mut()?.pop()
+    }
+
+    pub fn retain<F: FnMut(&Value) -> bool>(&mut self, predicate: F) -> Result<(), Error> {
+        self.expect_array_mut()?.retain(predicate);
+        Ok(())
+    }
+
+    pub fn truncate(&mut self, len: usize) -> Result<(), Error> {
+        self.expect_array_mut()?.truncate(len);
+        Ok(())
+    }
+
+    fn expect_array_mut(&mut self) -> Result<&mut Array, Error> {
+        match self {
+            Value::Array(elements) => Ok(elements),
+            _ => Err(mismatch("array", self)),
+        }
+    }
+
+    // moves the value out, leaving null behind
+    pub fn take(&mut self) -> Value {
+        std::mem::take(self)
+    }
+
+    pub fn replace<V: Into<Value>>(&mut self, value: V) -> Value {
+        std::mem::replace(self, value.into())
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Object(_) => "object",
+            Value::Array(_) => "array",
+            Value::String(_) => "string",
+            Value::Number(_) => "number",
+            Value::Bool(_) => "boolean",
+            Value::Null => "null",
+            Value::Raw(_) => "raw JSON",
+        }
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Value::Array(_))
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, Value::String(_))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Number(_))
+    }
+
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    pub fn is_f64(&self) -> bool {
+        self.as_number().is_some_and(Number::is_f64)
+    }
+
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Value::Bool(_))
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn is_raw_value(&self) -> bool {
+        matches!(self, Value::Raw(_))
+    }
+
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            Value::Object(properties) => Some(properties),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Value::Object(properties) => Some(properties),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            Value::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Array> {
+        match self {
+            Value::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Value::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+
+    // only whole numbers that fit, e.g. 3.0 but not 3.5
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_raw_value(&self) -> Option<&RawValue> {
+        match self {
+            Value::Raw(raw) => Some(raw),
+            _ => None,
+        }
+    }
+
+    pub fn as_null(&self) -> Option<()> {
+        match self {
+            Value::Null => Some(()),
+            _ => None,
+        }
+    }
+}
+
+// anything that can look up a child: a key for objects, a position for arrays
+pub trait JsonIndex {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value>;
+    // used by `value[index] = ...`; panics where no child can be created
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value;
+    fn insert_into(&self, value: &mut Value, new: Value) -> Result<Option<Value>, Error>;
+    fn remove_from(&self, value: &mut Value) -> Option<Value>;
+}
+
+impl JsonIndex for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_array()?.get(*self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_array_mut()?.get_mut(*self)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        match value {
+            Value::Array(elements) => {
+                let len = elements.len();
+                elements.get_mut(*self).unwrap_or_else(|| {
+                    panic!(
+                        "cannot access index {} of JSON array of length {}",
+                        self, len
+                    )
+                })
+            }
+            _ => panic!("cannot access index {} of JSON {}", self, value.type_name()),
+        }
+    }
+
+    // panics when the position is past the end, as Vec::insert does
+    fn insert_into(&self, value: &mut Value, new: Value) -> Result<Option<Value>, Error> {
+        if value.is_null() {
+            *value = Value::array();
+        }
+        match value {
+            Value::Array(elements) => {
+                elements.insert(*self, new);
+                Ok(None)
+            }
+            _ => Err(mismatch("array", value)),
+        }
+    }
+
+    fn remove_from(&self, value: &mut Value) -> Option<Value> {
+        let elements = value.as_array_mut()?;
+        (*self < elements.len()).then(|| elements.remove(*self))
+    }
+}
+
+impl JsonIndex for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_object()?.get(self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_object_mut()?.get_mut(self)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        if value.is_null() {
+            *value = Value::object(); // auto-vivify, so `v["a"]["b"] = ...` works on fresh values
+        }
+        match value {
+            Value::Object(properties) => properties.entry(self).or_default(),
+            _ => panic!("cannot access key {:?} in JSON {}", self, value.type_name()),
+        }
+    }
+
+    fn insert_into(&self, value: &mut Value, new: Value) -> Result<Option<Value>, Error> {
+        if value.is_null() {
+            *value = Value::object();
+        }
+        match value {
+            Value::Object(properties) => Ok(properties.insert(self.to_string(), new)),
+            _ => Err(mismatch("object", value)),
+        }
+    }
+
+    fn remove_from(&self, value: &mut Value) -> Option<Value> {
+        value.as_object_mut()?.remove(self)
+    }
+}
+
+impl JsonIndex for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        self.as_str().index_or_insert(value)
+    }
+
+    fn insert_into(&self, value: &mut Value, new: Value) -> Result<Option<Value>, Error> {
+        self.as_str().insert_into(value, new)
+    }
+
+    fn remove_from(&self, value: &mut Value) -> Option<Value> {
+        self.as_str().remove_from(value)
+    }
+}
+
+impl<T: JsonIndex + ?Sized> JsonIndex for &T {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        (**self).index_or_insert(value)
+    }
+
+    fn insert_into(&self, value: &mut Value, new: Value) -> Result<Option<Value>, Error> {
+        (**self).insert_into(value, new)
+    }
+
+    fn remove_from(&self, value: &mut Value) -> Option<Value> {
+        (**self).remove_from(value)
+    }
+}
+
+// missing paths read as null instead of panicking
+static NULL: Value = Value::Null;
+
+impl<I: JsonIndex> Index<I> for Value {
+    type Output = Value;
+
+    fn index(&self, index: I) -> &Value {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+impl<I: JsonIndex> IndexMut<I> for Value {
+    fn index_mut(&mut self, index: I) -> &mut Value {
+        index.index_or_insert(self)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+macro_rules! from_number {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(value: $ty) -> Self {
+                    Value::Number(value.into())
+                }
+            }
+        )*
+    };
+}
+
+from_number!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64 Number);
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(value: Vec<T>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+// HashMap has no order of its own, so the properties come out in iteration order
+impl<V: Into<Value>> From<HashMap<String, V>> for Value {
+    fn from(value: HashMap<String, V>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let properties = iter
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        Value::Object(properties)
+    }
+}
+
+pub(crate) fn mismatch(expected: &str, value: &Value) -> Error {
+    Error::Conversion(format!(
+        "expected {}, found {}",
+        expected,
+        value.type_name()
+    ))
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::String(string) => Ok(string),
+            _ => Err(mismatch("string", &value)),
+        }
+    }
+}
+
+impl TryFrom<&Value> for String {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| mismatch("string", value))
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        bool::try_from(&value)
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        value.as_bool().ok_or_else(|| mismatch("boolean", value))
+    }
+}
+
+// integers must be whole and fit the target type, so 3.5 or 300 as u8 fail
+macro_rules! try_from_integer {
+    ($($ty:ident)*) => {
+        $(
+            impl TryFrom<&Value> for $ty {
+                type Error = Error;
+
+                fn try_from(value: &Value) -> Result<Self, Error> {
+                    let number = value
+                        .as_f64()
+                        .ok_or_else(|| mismatch(stringify!($ty), value))?;
+                    value
+                        .as_i64()
+                        .and_then(|n| $ty::try_from(n).ok())
+                        .or_else(|| value.as_u64().and_then(|n| $ty::try_from(n).ok()))
+                        .ok_or_else(|| {
+                            Error::Conversion(format!(
+                                "number {} does not fit in {}",
+                                number,
+                                stringify!($ty)
+                            ))
+                        })
+                }
+            }
+
+            impl TryFrom<Value> for $ty {
+                type Error = Error;
+
+                fn try_from(value: Value) -> Result<Self, Error> {
+                    $ty::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+try_from_integer!(i8 i16 i32 i64 u8 u16 u32 u64 usize);
+
+impl TryFrom<&Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        value.as_f64().ok_or_else(|| mismatch("number", value))
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        f64::try_from(&value)
+    }
+}
+
+impl<T: TryFrom<Value, Error = Error>> TryFrom<Value> for Vec<T> {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Array(elements) => elements.into_iter().map(T::try_from).collect(),
+            _ => Err(mismatch("array", &value)),
+        }
+    }
+}
+
+impl<T> TryFrom<&Value> for Vec<T>
+where
+    T: for<'v> TryFrom<&'v Value, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        let elements = value.as_array().ok_or_else(|| mismatch("array", value))?;
+        elements.iter().map(T::try_from).collect()
+    }
+}
+
+impl<T: TryFrom<Value, Error = Error>> TryFrom<Value> for HashMap<String, T> {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Object(properties) => properties
+                .into_iter()
+                .map(|(key, value)| Ok((key, T::try_from(value)?)))
+                .collect(),
+            _ => Err(mismatch("object", &value)),
+        }
+    }
+}
+
+impl<T> TryFrom<&Value> for HashMap<String, T>
+where
+    T: for<'v> TryFrom<&'v Value, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        let properties = value.as_object().ok_or_else(|| mismatch("object", value))?;
+        properties
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), T::try_from(value)?)))
+            .collect()
+    }
+}
+
+// lets assertions compare against plain Rust values, e.g. `v["status"] == "ok"`
+impl PartialEq<str> for Value {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for Value {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<String> for Value {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == Some(other.as_str())
+    }
+}
+
+impl PartialEq<bool> for Value {
+    fn eq(&self, other: &bool) -> bool {
+        self.as_bool() == Some(*other)
+    }
+}
+
+impl PartialEq<Value> for str {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Value> for &str {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Value> for String {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Value> for bool {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
+    }
+}
+
+macro_rules! partial_eq_number {
+    ($($ty:ty)*) => {
+        $(
+            impl PartialEq<$ty> for Value {
+                fn eq(&self, other: &$ty) -> bool {
+                    self.as_number() == Some(&Number::from(*other))
+                }
+            }
+
+            impl PartialEq<Value> for $ty {
+                fn eq(&self, other: &Value) -> bool {
+                    other == self
+                }
+            }
+        )*
+    };
+}
+
+partial_eq_number!(i32 i64 u32 u64 f64);
+
+// without the serializer feature values still print, for error messages and the like:
+// compactly, also for `{:#}`, which needs the serializer to be pretty-printed
+#[cfg(not(feature = "serializer"))]
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) if n.as_f64().is_finite() => write!(f, "{}", n),
+            Value::Number(_) => f.write_str("null"), // NaN and the infinities
+            Value::String(s) => write_string(f, s),
+            Value::Array(elements) => {
+                f.write_str("[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_str("]")
+            }
+            Value::Object(properties) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in properties.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+            Value::Raw(raw) => write!(f, "{}", raw),
+        }
+    }
+}
+
+#[cfg(not(feature = "serializer"))]
+fn write_string(f: &mut std::fmt::Formatter<'_>, string: &str) -> std::fmt::Result {
+    use std::fmt::Write;
+    f.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
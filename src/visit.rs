@@ -0,0 +1,142 @@
+// walking a tree: iterators over children and every node, and in-place rewriting
+use crate::path::{JsonPath, PathSegment};
+use crate::{Value, map};
+use std::iter::Enumerate;
+use std::slice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Keep,
+    Remove, // drops the node from its parent; a removed root becomes null
+}
+
+impl Value {
+    // empty for anything that is not an object
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.as_object().into_iter().flatten()
+    }
+
+    // empty for anything that is not an array
+    pub fn items(&self) -> impl Iterator<Item = &Value> {
+        self.as_array().into_iter().flatten()
+    }
+
+    // every node with its path, parents before children, in document order
+    pub fn iter_deep(&self) -> DeepIter<'_> {
+        DeepIter {
+            root: Some(self),
+            stack: Vec::new(),
+            path: JsonPath::root(),
+        }
+    }
+
+    // depth-first, in document order, stopping at the first match
+    pub fn find<F>(&self, mut predicate: F) -> Option<(JsonPath, &Value)>
+    where
+        F: FnMut(&JsonPath, &Value) -> bool,
+    {
+        self.iter_deep().find(|(path, node)| predicate(path, node))
+    }
+
+    pub fn find_all<F>(&self, mut predicate: F) -> Vec<(JsonPath, &Value)>
+    where
+        F: FnMut(&JsonPath, &Value) -> bool,
+    {
+        self.iter_deep()
+            .filter(|(path, node)| predicate(path, node))
+            .collect()
+    }
+
+    // children are visited before their parent, so a parent sees its rewritten children,
+    // e.g. an object emptied by dropping nulls can then drop itself
+    pub fn transform<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&JsonPath, &mut Value) -> Action,
+    {
+        if walk(self, &mut JsonPath::root(), &mut f) == Action::Remove {
+            *self = Value::Null;
+        }
+    }
+}
+
+fn walk<F>(node: &mut Value, path: &mut JsonPath, f: &mut F) -> Action
+where
+    F: FnMut(&JsonPath, &mut Value) -> Action,
+{
+    match node {
+        Value::Object(properties) => properties.retain(|key, child| {
+            path.push(PathSegment::Key(key.clone()));
+            let action = walk(child, path, f);
+            path.pop();
+            action == Action::Keep
+        }),
+        Value::Array(elements) => {
+            let mut index = 0; // the original position, even after earlier removals
+            elements.retain_mut(|child| {
+                path.push(PathSegment::Index(index));
+                let action = walk(child, path, f);
+                path.pop();
+                index += 1;
+                action == Action::Keep
+            });
+        }
+        _ => {}
+    }
+    f(path, node)
+}
+
+pub struct DeepIter<'a> {
+    root: Option<&'a Value>,
+    stack: Vec<Children<'a>>,
+    path: JsonPath, // the path of the last node handed out
+}
+
+enum Children<'a> {
+    Object(map::Iter<'a>),
+    Array(Enumerate<slice::Iter<'a, Value>>),
+}
+
+impl<'a> DeepIter<'a> {
+    fn descend(&mut self, value: &'a Value) {
+        match value {
+            Value::Object(properties) => self.stack.push(Children::Object(properties.iter())),
+            Value::Array(elements) => self
+                .stack
+                .push(Children::Array(elements.iter().enumerate())),
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Iterator for DeepIter<'a> {
+    type Item = (JsonPath, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            self.descend(root);
+            return Some((JsonPath::root(), root));
+        }
+        loop {
+            let depth = self.stack.len();
+            let next = match self.stack.last_mut()? {
+                Children::Object(entries) => entries
+                    .next()
+                    .map(|(key, child)| (PathSegment::Key(key.clone()), child)),
+                Children::Array(elements) => elements
+                    .next()
+                    .map(|(index, child)| (PathSegment::Index(index), child)),
+            };
+            let Some((segment, child)) = next else {
+                self.stack.pop();
+                continue;
+            };
+            // the parent sits at depth - 1, so keep that many segments
+            while self.path.segments().len() >= depth {
+                self.path.pop();
+            }
+            self.path.push(segment);
+            self.descend(child);
+            return Some((self.path.clone(), child));
+        }
+    }
+}
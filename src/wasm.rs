@@ -0,0 +1,139 @@
+// wasm-bindgen exports for running the parser in a browser, e.g. a playground showing the
+// tokens and the tree. Failures are thrown as the diagnostic objects of
+// `diagnostic::to_json`, so JavaScript gets the code, message, line and column
+use crate::diagnostic::{self, Diagnostic};
+use crate::filter::Filter;
+use crate::schema::Schema;
+use crate::serializer::{self, Indent, SerializeOptions, Style};
+use crate::{Map, Value, lexer};
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+// objects keep their key order; numbers become doubles, so integers beyond 2^53 lose precision
+#[wasm_bindgen]
+pub fn parse(text: &str) -> Result<JsValue, JsValue> {
+    diagnostic::parse(text)
+        .map(|value| to_js(&value))
+        .map_err(|d| diagnostic_to_js(d, text))
+}
+
+// [{type: "OpenObject", value: "{", start: 0, end: 1}, ...] with byte offsets
+#[wasm_bindgen]
+pub fn tokenize(text: &str) -> Result<JsValue, JsValue> {
+    let tokens = lexer::generate_located(text)
+        .map_err(|(error, span)| diagnostic_to_js(Diagnostic::syntax(&error, span), text))?;
+    let array = Array::new();
+    for (token, span) in tokens {
+        let mut object = Value::object();
+        object["type"] = format!("{:?}", token.token_type).into();
+        object["value"] = token.value.into();
+        object["start"] = span.start.into();
+        object["end"] = span.end.into();
+        array.push(&to_js(&object));
+    }
+    Ok(array.into())
+}
+
+// JSON.stringify with this crate's serializer: compact, or indented by `indent` spaces
+#[wasm_bindgen]
+pub fn stringify(value: JsValue, indent: Option<usize>) -> Result<String, JsValue> {
+    let value = from_js(&value)?;
+    let style = match indent {
+        Some(0) | None => Style::compact(),
+        Some(width) => Style::pretty().with_indent(Some(Indent::Spaces(width.min(16)))),
+    };
+    Ok(serializer::to_string_with(
+        &value,
+        &SerializeOptions::new().with_style(style),
+    ))
+}
+
+// every problem with `text` against the JSON Schema in `schema`, as an array of
+// diagnostics; empty when the document is valid
+#[wasm_bindgen]
+pub fn validate(text: &str, schema: &str) -> Result<JsValue, JsValue> {
+    let schema = diagnostic::parse(schema).map_err(|d| diagnostic_to_js(d, schema))?;
+    let schema = Schema::compile(&schema).map_err(error_to_js)?;
+    Ok(to_js(&diagnostic::to_json(
+        &diagnostic::check(text, &schema),
+        text,
+    )))
+}
+
+// the value at a JSON Pointer such as "/users/0/name", or undefined
+#[wasm_bindgen]
+pub fn pointer(text: &str, pointer: &str) -> Result<JsValue, JsValue> {
+    let value = diagnostic::parse(text).map_err(|d| diagnostic_to_js(d, text))?;
+    Ok(value.pointer(pointer).map_or(JsValue::UNDEFINED, to_js))
+}
+
+// runs a `filter` expression such as ".users[] | .name", returning every output
+#[wasm_bindgen]
+pub fn query(text: &str, filter: &str) -> Result<JsValue, JsValue> {
+    let value = diagnostic::parse(text).map_err(|d| diagnostic_to_js(d, text))?;
+    let filter = Filter::compile(filter).map_err(error_to_js)?;
+    let outputs = filter.apply(&value).map_err(error_to_js)?;
+    Ok(outputs.iter().map(to_js).collect::<Array>().into())
+}
+
+fn diagnostic_to_js(diagnostic: Diagnostic, source: &str) -> JsValue {
+    match diagnostic::to_json(&[diagnostic], source) {
+        Value::Array(mut diagnostics) => to_js(&diagnostics.remove(0)),
+        _ => unreachable!("to_json returns an array"),
+    }
+}
+
+fn error_to_js(error: crate::Error) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+fn to_js(value: &Value) -> JsValue {
+    match value {
+        Value::Null => JsValue::NULL,
+        Value::Bool(b) => JsValue::from_bool(*b),
+        Value::Number(n) => JsValue::from_f64(n.as_f64()),
+        Value::String(s) => JsValue::from_str(s),
+        Value::Array(elements) => elements.iter().map(to_js).collect::<Array>().into(),
+        Value::Object(properties) => {
+            let object = Object::new();
+            for (key, value) in properties.iter() {
+                // setting a property on a fresh plain object can't fail
+                let _ = Reflect::set(&object, &JsValue::from_str(key), &to_js(value));
+            }
+            object.into()
+        }
+        Value::Raw(raw) => raw
+            .parse()
+            .map_or(JsValue::UNDEFINED, |value| to_js(&value)),
+    }
+}
+
+// what JSON.stringify would keep: undefined and functions are rejected rather than
+// dropped, so nothing disappears silently
+fn from_js(value: &JsValue) -> Result<Value, JsValue> {
+    if value.is_null() {
+        Ok(Value::Null)
+    } else if let Some(b) = value.as_bool() {
+        Ok(Value::Bool(b))
+    } else if let Some(n) = value.as_f64() {
+        // JSON.stringify writes NaN and the infinities as null too
+        Ok(if n.is_finite() { n.into() } else { Value::Null })
+    } else if let Some(s) = value.as_string() {
+        Ok(Value::String(s))
+    } else if Array::is_array(value) {
+        Array::from(value)
+            .iter()
+            .map(|element| from_js(&element))
+            .collect()
+    } else if value.is_object() && !value.is_function() {
+        let mut properties = Map::new();
+        for entry in Object::entries(value.unchecked_ref()).iter() {
+            let entry = Array::from(&entry);
+            let key = entry.get(0).as_string().unwrap_or_default();
+            properties.insert(key, from_js(&entry.get(1))?);
+        }
+        Ok(Value::Object(properties))
+    } else {
+        Err(js_sys::TypeError::new("Value has no JSON representation").into())
+    }
+}
@@ -0,0 +1,750 @@
+// YAML conversion for the subset that maps onto JSON: block mappings and sequences, flow
+// collections (so any JSON text is accepted too), plain, quoted and block scalars, and
+// comments. Anchors, aliases, tags, complex keys and multiple documents are rejected.
+// Plain scalars are resolved with the YAML 1.2 core schema; yes/no/on/off stay strings.
+use crate::serializer::{SerializeOptions, write_string};
+use crate::{Error, Map, Number, Value};
+
+// block style, two spaces per level; strings are quoted only when a plain scalar would
+// read back as something else
+pub fn to_yaml_string(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Raw(raw) => return to_yaml_string(&raw.parse().unwrap_or_default()),
+        value if is_block(value) => write_block(&mut out, value, 0, false),
+        value => {
+            write_scalar(&mut out, value);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+pub fn from_yaml_str(input: &str) -> Result<Value, Error> {
+    let mut parser = Parser::new(input);
+    parser.document()
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(elements) => !elements.is_empty(),
+        Value::Object(properties) => !properties.is_empty(),
+        _ => false,
+    }
+}
+
+// a non-empty collection; `inline` when its first line follows a "- " already written
+fn write_block(out: &mut String, value: &Value, indent: usize, mut inline: bool) {
+    let mut start_line = |out: &mut String| {
+        if !inline {
+            out.extend(std::iter::repeat_n(' ', indent));
+        }
+        inline = false;
+    };
+    match value {
+        Value::Array(elements) => {
+            for element in elements {
+                start_line(out);
+                out.push('-');
+                write_child(out, element, indent + 2, true);
+            }
+        }
+        Value::Object(properties) => {
+            for (key, value) in properties {
+                start_line(out);
+                write_str(out, key);
+                out.push(':');
+                write_child(out, value, indent + 2, false);
+            }
+        }
+        _ => {}
+    }
+}
+
+// what follows "-" or "key:"
+fn write_child(out: &mut String, value: &Value, indent: usize, item: bool) {
+    match value {
+        Value::Raw(raw) => write_child(out, &raw.parse().unwrap_or_default(), indent, item),
+        value if is_block(value) && item => {
+            out.push(' ');
+            write_block(out, value, indent, true);
+        }
+        value if is_block(value) => {
+            out.push('\n');
+            write_block(out, value, indent, false);
+        }
+        value => {
+            out.push(' ');
+            write_scalar(out, value);
+            out.push('\n');
+        }
+    }
+}
+
+fn write_scalar(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(number) => match number.as_f64() {
+            n if n.is_nan() => out.push_str(".nan"),
+            n if n == f64::INFINITY => out.push_str(".inf"),
+            n if n == f64::NEG_INFINITY => out.push_str("-.inf"),
+            _ => out.push_str(&number.to_string()),
+        },
+        Value::String(s) => write_str(out, s),
+        Value::Array(_) => out.push_str("[]"),
+        Value::Object(_) => out.push_str("{}"),
+        Value::Raw(raw) => write_scalar(out, &raw.parse().unwrap_or_default()),
+    }
+}
+
+// YAML's double-quoted style accepts every JSON escape, so JSON's quoting is reused
+fn write_str(out: &mut String, s: &str) {
+    if is_plain(s) {
+        out.push_str(s);
+    } else {
+        let _ = write_string(out, s, &SerializeOptions::default());
+    }
+}
+
+fn is_plain(s: &str) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    !"-?:,[]{}#&*!|>'\"%@`".contains(first)
+        && s.trim() == s
+        && !s.chars().any(char::is_control)
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        // YAML 1.1 readers take these as booleans
+        && !matches!(
+            s.to_ascii_lowercase().as_str(),
+            "y" | "n" | "yes" | "no" | "on" | "off"
+        )
+        && matches!(resolve(s), Value::String(_))
+}
+
+// a plain scalar by the core schema: null, booleans, numbers, or else a string
+fn resolve(text: &str) -> Value {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => f64::INFINITY.into(),
+        "-.inf" | "-.Inf" | "-.INF" => f64::NEG_INFINITY.into(),
+        ".nan" | ".NaN" | ".NAN" => f64::NAN.into(),
+        _ => match number(text) {
+            Some(number) => Value::Number(number),
+            None => Value::String(text.to_string()),
+        },
+    }
+}
+
+fn number(text: &str) -> Option<Number> {
+    let text = text.strip_prefix('+').unwrap_or(text);
+    if let Some(hex) = text.strip_prefix("0x") {
+        return u64::from_str_radix(hex, 16).ok().map(Number::U64);
+    }
+    if let Some(octal) = text.strip_prefix("0o") {
+        return u64::from_str_radix(octal, 8).ok().map(Number::U64);
+    }
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", text),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(e) => (&unsigned[..e], Some(&unsigned[e + 1..])),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let valid = digits(whole)
+        && fraction.is_none_or(digits)
+        && !(whole.is_empty() && fraction.is_none_or(str::is_empty))
+        && exponent.is_none_or(|e| {
+            let e = e.strip_prefix(['+', '-']).unwrap_or(e);
+            !e.is_empty() && digits(e)
+        });
+    if !valid {
+        return None;
+    }
+    // ".5" and "5." aren't JSON numbers, so they are spelled out before parsing
+    let mut normalized = format!("{}{}", sign, if whole.is_empty() { "0" } else { whole });
+    if let Some(fraction) = fraction {
+        normalized.push('.');
+        normalized.push_str(if fraction.is_empty() { "0" } else { fraction });
+    }
+    if let Some(exponent) = exponent {
+        normalized.push('e');
+        normalized.push_str(exponent);
+    }
+    normalized.parse().ok()
+}
+
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    indent: usize,
+    text: &'a str, // after the indentation, trailing whitespace kept
+}
+
+impl Line<'_> {
+    fn is_blank(&self) -> bool {
+        let text = self.text.trim_start();
+        text.is_empty() || text.starts_with('#')
+    }
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    current: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let lines = input
+            .lines()
+            .map(|line| {
+                let text = line.trim_start_matches(' ');
+                Line {
+                    indent: line.len() - text.len(),
+                    text,
+                }
+            })
+            .collect();
+        Parser { lines, current: 0 }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::Line(self.current + 1, Box::new(message.into()))
+    }
+
+    // the next line with content, skipping blank and comment lines
+    fn peek(&mut self) -> Result<Option<Line<'a>>, Error> {
+        while let Some(line) = self.lines.get(self.current) {
+            if !line.is_blank() {
+                if line.text.starts_with('\t') {
+                    return Err(self.error("tabs can't be used for indentation"));
+                }
+                return Ok(Some(*line));
+            }
+            self.current += 1;
+        }
+        Ok(None)
+    }
+
+    fn document(&mut self) -> Result<Value, Error> {
+        let Some(first) = self.peek()? else {
+            return Ok(Value::Null);
+        };
+        if first.text.starts_with('%') {
+            return Err(self.error("directives are not supported"));
+        }
+        if let Some(rest) = document_marker(first.text, "---") {
+            match rest.trim_start() {
+                "" => self.current += 1,
+                inline => {
+                    self.lines[self.current] = Line {
+                        indent: 4,
+                        text: inline,
+                    }
+                }
+            }
+        }
+        let value = match self.peek()? {
+            Some(_) => self.node(-1)?,
+            None => Value::Null,
+        };
+        if let Some(line) = self.peek()? {
+            if document_marker(line.text, "---").is_some() {
+                return Err(self.error("multiple documents are not supported"));
+            }
+            if document_marker(line.text, "...").is_none() {
+                return Err(self.error("unexpected content; check the indentation"));
+            }
+            self.current += 1;
+            if self.peek()?.is_some() {
+                return Err(self.error("multiple documents are not supported"));
+            }
+        }
+        Ok(value)
+    }
+
+    // the node starting at the current line, which is more indented than `parent`
+    fn node(&mut self, parent: isize) -> Result<Value, Error> {
+        let line = self.lines[self.current];
+        if is_item(line.text) {
+            return self.sequence(line.indent);
+        }
+        if split_key(line.text).map_err(|e| self.error(&e))?.is_some() {
+            return self.mapping(line.indent);
+        }
+        self.inline(line.text, parent)
+    }
+
+    // the value under a "key:" or "-" with nothing after it
+    fn nested(&mut self, parent: usize, in_mapping: bool) -> Result<Value, Error> {
+        match self.peek()? {
+            Some(line) if line.indent > parent => self.node(parent as isize),
+            // a sequence may sit at its key's own indentation
+            Some(line) if in_mapping && line.indent == parent && is_item(line.text) => {
+                self.sequence(parent)
+            }
+            _ => Ok(Value::Null),
+        }
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Value, Error> {
+        let mut elements = Vec::new();
+        while let Some(line) = self.peek()?
+            && line.indent == indent
+            && is_item(line.text)
+        {
+            let rest = &line.text[1..];
+            let content = rest.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                self.current += 1;
+                elements.push(self.nested(indent, false)?);
+            } else {
+                // the item's content stands in for the line, at its own column
+                self.lines[self.current] = Line {
+                    indent: indent + 1 + rest.len() - content.len(),
+                    text: content,
+                };
+                elements.push(self.node(indent as isize)?);
+            }
+        }
+        Ok(Value::Array(elements))
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Value, Error> {
+        let mut properties = Map::new();
+        while let Some(line) = self.peek()?
+            && line.indent == indent
+            && !is_marker(line)
+        {
+            let Some((key, rest)) = split_key(line.text).map_err(|e| self.error(&e))? else {
+                return Err(self.error("expected a key"));
+            };
+            if properties.contains_key(&key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            let rest = rest.trim_start();
+            let value = if rest.is_empty() || rest.starts_with('#') {
+                self.current += 1;
+                self.nested(indent, true)?
+            } else {
+                self.inline(rest, indent as isize)?
+            };
+            properties.insert(key, value);
+        }
+        Ok(Value::Object(properties))
+    }
+
+    // a value written on the current line, after any "key:" or "-"; consumes the line
+    // and whatever continues it
+    fn inline(&mut self, text: &'a str, parent: isize) -> Result<Value, Error> {
+        match text.chars().next() {
+            Some('|') | Some('>') => return self.block_scalar(text, parent),
+            Some('[' | '{' | '"' | '\'') => return self.flow(text),
+            Some('&' | '*' | '!') => {
+                return Err(self.error("anchors, aliases and tags are not supported"));
+            }
+            Some('?') if text[1..].starts_with([' ', '\t']) || text.len() == 1 => {
+                return Err(self.error("complex keys are not supported"));
+            }
+            _ => {}
+        }
+        let mut scalar = strip_comment(text).trim().to_string();
+        self.current += 1;
+        // a plain scalar continues on more indented lines, joined by spaces
+        while let Some(line) = self.lines.get(self.current)
+            && !line.is_blank()
+            && line.indent as isize > parent
+        {
+            if split_key(line.text).ok().flatten().is_some() {
+                return Err(self.error("unexpected mapping entry; check the indentation"));
+            }
+            scalar.push(' ');
+            scalar.push_str(strip_comment(line.text).trim());
+            self.current += 1;
+        }
+        Ok(resolve(&scalar))
+    }
+
+    // a flow collection or quoted scalar, which may run over several lines
+    fn flow(&mut self, text: &'a str) -> Result<Value, Error> {
+        let start = self.current;
+        let mut source = text.to_string();
+        loop {
+            let mut flow = Flow {
+                text: &source,
+                position: 0,
+            };
+            match flow.value() {
+                Ok(value) => {
+                    let rest = &source[flow.position..];
+                    if !strip_comment(rest).trim().is_empty() {
+                        return Err(self.error("unexpected text after the value"));
+                    }
+                    self.current += 1;
+                    return Ok(value);
+                }
+                Err(None) if self.current + 1 < self.lines.len() => {
+                    self.current += 1;
+                    source.push('\n');
+                    source.push_str(self.lines[self.current].text);
+                }
+                Err(None) => {
+                    self.current = start;
+                    return Err(self.error("unterminated flow collection or quoted string"));
+                }
+                Err(Some(message)) => return Err(self.error(&message)),
+            }
+        }
+    }
+
+    // | keeps line breaks, > folds them into spaces; - strips the final line break,
+    // + keeps trailing blank lines, and a digit gives the indentation explicitly
+    fn block_scalar(&mut self, text: &str, parent: isize) -> Result<Value, Error> {
+        let literal = text.starts_with('|');
+        let mut chomp = ' ';
+        let mut explicit = None;
+        for c in strip_comment(&text[1..]).trim_end().chars() {
+            match c {
+                '-' | '+' if chomp == ' ' => chomp = c,
+                '1'..='9' if explicit.is_none() => explicit = c.to_digit(10),
+                _ => return Err(self.error("invalid block scalar header")),
+            }
+        }
+        self.current += 1;
+        let base = parent.max(0) as usize;
+        let indent = match explicit {
+            Some(digit) => base + digit as usize,
+            None => self.lines[self.current..]
+                .iter()
+                .find(|line| !line.text.trim().is_empty())
+                .map_or(0, |line| line.indent),
+        };
+        let mut lines = Vec::new();
+        if indent as isize > parent {
+            while let Some(line) = self.lines.get(self.current) {
+                if line.text.trim().is_empty() {
+                    lines.push(String::new());
+                } else if line.indent >= indent {
+                    lines.push(format!("{}{}", " ".repeat(line.indent - indent), line.text));
+                } else {
+                    break;
+                }
+                self.current += 1;
+            }
+        }
+        let mut content = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            content.push_str(line);
+            let Some(next) = lines.get(i + 1) else {
+                content.push('\n');
+                break;
+            };
+            let folds = |line: &str| !line.is_empty() && !line.starts_with([' ', '\t']);
+            match (literal, folds(line), folds(next)) {
+                (false, true, true) => content.push(' '),
+                (false, true, false) if next.is_empty() => {}
+                _ => content.push('\n'),
+            }
+        }
+        let content = match chomp {
+            '+' => content,
+            '-' => content.trim_end_matches('\n').to_string(),
+            _ if content.trim_end_matches('\n').is_empty() => String::new(),
+            _ => format!("{}\n", content.trim_end_matches('\n')),
+        };
+        Ok(Value::String(content))
+    }
+}
+
+fn document_marker<'t>(text: &'t str, marker: &str) -> Option<&'t str> {
+    let rest = text.strip_prefix(marker)?;
+    (rest.is_empty() || rest.starts_with([' ', '\t'])).then_some(rest)
+}
+
+fn is_marker(line: Line) -> bool {
+    line.indent == 0
+        && (document_marker(line.text, "---").is_some()
+            || document_marker(line.text, "...").is_some())
+}
+
+fn is_item(text: &str) -> bool {
+    text.strip_prefix('-')
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+// a comment starts at a '#' at the start or after whitespace
+fn strip_comment(text: &str) -> &str {
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        if c == '#' && previous.is_whitespace() {
+            return &text[..i];
+        }
+        previous = c;
+    }
+    text
+}
+
+fn after_colon(rest: &str) -> Option<&str> {
+    rest.strip_prefix(':')
+        .filter(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+// "key: rest" into the key and what follows the colon; None when the line isn't a
+// mapping entry
+fn split_key(text: &str) -> Result<Option<(String, &str)>, String> {
+    if text.starts_with(['"', '\'']) {
+        let mut flow = Flow { text, position: 0 };
+        let key = match flow.quoted() {
+            Ok(key) => key,
+            Err(_) => return Ok(None), // a multi-line quoted scalar, not a key
+        };
+        let rest = text[flow.position..].trim_start_matches([' ', '\t']);
+        return Ok(after_colon(rest).map(|rest| (key, rest)));
+    }
+    if text.starts_with(['[', '{', '#']) || is_item(text) {
+        return Ok(None);
+    }
+    let content = strip_comment(text);
+    for (i, _) in content.match_indices(':') {
+        if let Some(rest) = after_colon(&text[i..]) {
+            let key = content[..i].trim_end();
+            if key.starts_with(['&', '*', '!']) {
+                return Err("anchors, aliases and tags are not supported".into());
+            }
+            if key.starts_with("? ") {
+                return Err("complex keys are not supported".into());
+            }
+            return Ok(Some((key.to_string(), rest)));
+        }
+    }
+    Ok(None)
+}
+
+// the flow style, a superset of JSON. Err(None) means the text ended too early.
+struct Flow<'s> {
+    text: &'s str,
+    position: usize,
+}
+
+type FlowResult<T> = Result<T, Option<String>>;
+
+impl Flow<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_space(&mut self) {
+        let mut previous = ' ';
+        while let Some(c) = self.peek() {
+            if c == '#' && previous.is_whitespace() {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.bump();
+                }
+            } else if !c.is_whitespace() {
+                return;
+            }
+            previous = c;
+            self.bump();
+        }
+    }
+
+    fn value(&mut self) -> FlowResult<Value> {
+        self.skip_space();
+        match self.peek() {
+            None => Err(None),
+            Some('[') => self.sequence(),
+            Some('{') => self.mapping(),
+            Some('"' | '\'') => self.quoted().map(Value::String),
+            Some('&' | '*' | '!') => {
+                Err(Some("anchors, aliases and tags are not supported".into()))
+            }
+            Some(_) => {
+                let text = self.plain()?;
+                Ok(resolve(&text))
+            }
+        }
+    }
+
+    fn sequence(&mut self) -> FlowResult<Value> {
+        self.bump(); // consume '['
+        let mut elements = Vec::new();
+        loop {
+            self.skip_space();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(Value::Array(elements));
+            }
+            elements.push(self.value()?);
+            self.skip_space();
+            match self.bump() {
+                Some(',') => {}
+                Some(']') => return Ok(Value::Array(elements)),
+                Some(_) => return Err(Some("expected ',' or ']' in a flow sequence".into())),
+                None => return Err(None),
+            }
+        }
+    }
+
+    fn mapping(&mut self) -> FlowResult<Value> {
+        self.bump(); // consume '{'
+        let mut properties = Map::new();
+        loop {
+            self.skip_space();
+            let key = match self.peek() {
+                Some('}') => {
+                    self.bump();
+                    return Ok(Value::Object(properties));
+                }
+                Some('"' | '\'') => self.quoted()?,
+                Some('[' | '{') => return Err(Some("complex keys are not supported".into())),
+                Some(_) => self.plain()?,
+                None => return Err(None),
+            };
+            self.skip_space();
+            let value = match self.peek() {
+                Some(':') => {
+                    self.bump();
+                    self.skip_space();
+                    match self.peek() {
+                        Some(',' | '}') => Value::Null,
+                        _ => self.value()?,
+                    }
+                }
+                _ => Value::Null, // {a, b} is a mapping of keys to nulls
+            };
+            properties.insert(key, value);
+            self.skip_space();
+            match self.bump() {
+                Some(',') => {}
+                Some('}') => return Ok(Value::Object(properties)),
+                Some(_) => return Err(Some("expected ',' or '}' in a flow mapping".into())),
+                None => return Err(None),
+            }
+        }
+    }
+
+    // up to a flow indicator, a ": ", a comment or the end of the line
+    fn plain(&mut self) -> FlowResult<String> {
+        let start = self.position;
+        let mut previous = ' ';
+        while let Some(c) = self.peek() {
+            let rest = &self.text[self.position + c.len_utf8()..];
+            let ends_key = c == ':'
+                && (rest.is_empty()
+                    || rest.starts_with(|c: char| c.is_whitespace() || ",[]{}".contains(c)));
+            if ",[]{}\n".contains(c) || ends_key || (c == '#' && previous.is_whitespace()) {
+                break;
+            }
+            previous = c;
+            self.bump();
+        }
+        let text = self.text[start..self.position].trim();
+        if text.is_empty() {
+            return Err(Some("expected a value".into()));
+        }
+        Ok(text.to_string())
+    }
+
+    // a quoted scalar; a line break inside one folds into a space, as in plain scalars
+    fn quoted(&mut self) -> FlowResult<String> {
+        let quote = self.bump().unwrap_or('"');
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(None),
+                Some('\'') if quote == '\'' => {
+                    if self.peek() != Some('\'') {
+                        return Ok(out);
+                    }
+                    self.bump();
+                    out.push('\'');
+                }
+                Some('"') if quote == '"' => return Ok(out),
+                Some('\\') if quote == '"' => self.escape(&mut out)?,
+                Some('\n') => {
+                    out.truncate(out.trim_end_matches([' ', '\t']).len());
+                    while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                        self.bump();
+                    }
+                    out.push(' ');
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self, out: &mut String) -> FlowResult<()> {
+        let c = match self.bump().ok_or(None)? {
+            '0' => '\0',
+            'a' => '\u{07}',
+            'b' => '\u{08}',
+            't' | '\t' => '\t',
+            'n' => '\n',
+            'v' => '\u{0B}',
+            'f' => '\u{0C}',
+            'r' => '\r',
+            'e' => '\u{1B}',
+            ' ' => ' ',
+            '"' => '"',
+            '/' => '/',
+            '\\' => '\\',
+            'N' => '\u{85}',
+            '_' => '\u{A0}',
+            'L' => '\u{2028}',
+            'P' => '\u{2029}',
+            '\n' => {
+                // an escaped line break joins the lines without a space
+                while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                    self.bump();
+                }
+                return Ok(());
+            }
+            'x' => self.code_point(2)?,
+            'u' => self.code_point(4)?,
+            'U' => self.code_point(8)?,
+            other => return Err(Some(format!("invalid escape '\\{}'", other))),
+        };
+        out.push(c);
+        Ok(())
+    }
+
+    // \uXXXX may be the first half of a surrogate pair, as JSON writes them
+    fn code_point(&mut self, digits: usize) -> FlowResult<char> {
+        let high = self.hex(digits)?;
+        if (0xd800..0xdc00).contains(&high) && self.text[self.position..].starts_with("\\u") {
+            self.position += 2;
+            let low = self.hex(4)?;
+            if (0xdc00..0xe000).contains(&low) {
+                let combined = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return char::from_u32(combined).ok_or(Some("invalid escape".into()));
+            }
+        }
+        char::from_u32(high).ok_or_else(|| Some(format!("invalid code point {:x}", high)))
+    }
+
+    fn hex(&mut self, digits: usize) -> FlowResult<u32> {
+        let end = self.position + digits;
+        let text = self.text.get(self.position..end).ok_or(None)?;
+        let value =
+            u32::from_str_radix(text, 16).map_err(|_| Some("invalid escape".to_string()))?;
+        self.position = end;
+        Ok(value)
+    }
+}